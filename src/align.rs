@@ -0,0 +1,69 @@
+use crate::plugins::Record;
+use crate::render::{ellipsize, insert, locate, strip_ansi};
+
+// fields wider than this, like paths and user agents, aren't padded
+const MAX_WIDTH: usize = 24;
+
+// Pads fields into columns for --align, each as wide as the widest value seen
+// for it so far, so the columns settle as lines stream in. Numbers are
+// right-aligned, everything else left-aligned, and the last field on a line
+// is left alone.
+#[derive(Default)]
+pub struct Align {
+    widths: Vec<(String, usize)>,
+}
+
+impl Align {
+    pub fn new() -> Self {
+        Align::default()
+    }
+
+    // text is the line as shown, with fields shortened to widths
+    pub fn apply(&mut self, text: &str, record: &Record, widths: &[(String, usize)]) -> String {
+        let values: Vec<String> = record.fields.iter().map(|(name, value)| {
+            match widths.iter().find(|(f, _)| f == name) {
+                Some((_, width)) => { ellipsize(value, *width) }
+                None => { value.clone() }
+            }
+        }).collect();
+
+        let plain = strip_ansi(text);
+        let offsets = locate(&plain, &values.iter().map(|v| v.as_str()).collect::<Vec<&str>>());
+
+        let mut found: Vec<(usize, &str, &str)> = record.fields.iter().zip(&values).zip(&offsets)
+            .filter_map(|(((name, _), value), offset)| offset.map(|o| (o, name.as_str(), value.as_str())))
+            .collect();
+        found.sort_by_key(|(offset, _, _)| *offset);
+        found.pop();
+
+        let mut text = text.to_string();
+
+        // from the end, so the offsets before stay right
+        for (offset, name, value) in found.into_iter().rev() {
+            let len = value.chars().count();
+            if len > MAX_WIDTH {
+                continue;
+            }
+
+            let width = match self.widths.iter_mut().find(|(f, _)| f == name) {
+                Some((_, w)) => {
+                    *w = (*w).max(len);
+                    *w
+                }
+                None => {
+                    self.widths.push((name.to_string(), len));
+                    len
+                }
+            };
+
+            let padding = " ".repeat(width - len);
+
+            text = match value.parse::<f64>().is_ok() {
+                true => { insert(&text, offset, &padding) }
+                false => { insert(&text, offset + value.len(), &padding) }
+            };
+        }
+
+        text
+    }
+}
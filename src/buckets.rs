@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use clap::ValueEnum;
+use colored::{Color, Colorize};
+use serde_json::json;
+use crate::chart;
+use crate::numbers::count;
+use crate::plugins::Record;
+use crate::slo::Slo;
+use crate::stats::Stats;
+use crate::terminal;
+use crate::time::{date_format, parse_timestamp};
+
+// the axis labels take about 16 columns, leaving at least 10 for values
+const MIN_CHART_WIDTH: usize = 26;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum BucketFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Metric {
+    Requests,
+    Errors,
+    Bytes,
+    P95,
+}
+
+impl Metric {
+    fn value(&self, stats: &Stats) -> f64 {
+        match self {
+            Metric::Requests => { stats.records as f64 }
+            Metric::Errors => { stats.errors as f64 }
+            Metric::Bytes => { stats.bytes as f64 }
+            Metric::P95 => { stats.latency_percentile(95.0).unwrap_or(0.0) }
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Metric::Requests => { "requests" }
+            Metric::Errors => { "errors" }
+            Metric::Bytes => { "bytes" }
+            Metric::P95 => { "p95 latency (ms)" }
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Metric::Requests => { Color::BrightCyan }
+            Metric::Errors => { Color::BrightRed }
+            Metric::Bytes => { Color::BrightGreen }
+            Metric::P95 => { Color::BrightBlue }
+        }
+    }
+}
+
+struct Bucket {
+    start: DateTime<FixedOffset>,
+    stats: Stats,
+}
+
+// Aggregates records into fixed width time buckets keyed by the record's
+// datetime field. Records without a parseable timestamp are left out.
+pub struct Buckets {
+    width: i64,
+    buckets: BTreeMap<i64, Bucket>,
+}
+
+impl Buckets {
+    pub fn new(width: Duration) -> Self {
+        Buckets { width: i64::try_from(width.as_secs()).unwrap_or(i64::MAX), buckets: BTreeMap::new() }
+    }
+
+    pub fn add(&mut self, record: &Record, took: Option<Duration>) {
+        let t = match record.field("datetime").and_then(parse_timestamp) {
+            Some(t) => { t }
+            None => { return }
+        };
+
+        let key = t.timestamp().div_euclid(self.width) * self.width;
+
+        let bucket = self.buckets.entry(key).or_insert_with(|| {
+            let start = DateTime::from_timestamp(key, 0).unwrap().with_timezone(t.offset());
+            Bucket { start, stats: Stats::new() }
+        });
+
+        bucket.stats.add(record, took);
+    }
+
+    pub fn print(&self, format: BucketFormat, slo: Option<&Slo>) {
+        match format {
+            BucketFormat::Table => { self.print_table(slo) }
+            BucketFormat::Csv => { self.print_csv() }
+            BucketFormat::Json => { self.print_json() }
+        }
+    }
+
+    pub fn print_chart(&self, metrics: &[Metric]) {
+        let (first, last) = match (self.buckets.first_key_value(), self.buckets.last_key_value()) {
+            (Some(first), Some(last)) => { (first, last) }
+            _ => { return }
+        };
+
+        let keys: Vec<i64> = (*first.0..=*last.0).step_by(self.width as usize).collect();
+
+        // squeeze the time range into the terminal by merging neighbouring buckets
+        let columns = terminal::width().unwrap_or(80);
+
+        if columns < MIN_CHART_WIDTH {
+            eprintln!("Error: the terminal is {} columns wide, charts need at least {}", columns, MIN_CHART_WIDTH);
+            return;
+        }
+
+        let per_column = keys.len().div_ceil(columns - 16);
+
+        for metric in metrics {
+            let values: Vec<f64> = keys.chunks(per_column).map(|chunk| {
+                let values = chunk.iter().map(|k| self.buckets.get(k).map(|b| metric.value(&b.stats)).unwrap_or(0.0));
+
+                match metric {
+                    Metric::P95 => { values.fold(0.0, f64::max) }
+                    _ => { values.sum() }
+                }
+            }).collect();
+
+            let lines = chart::render(
+                metric.title(),
+                &values,
+                metric.color(),
+                &self.label(&first.1.start),
+                &self.label(&last.1.start),
+            );
+
+            for line in lines {
+                println!("{}", line);
+            }
+            println!();
+        }
+    }
+
+    fn label(&self, start: &DateTime<FixedOffset>) -> String {
+        match self.width % 60 {
+            0 => { start.format(&format!("{} %H:%M", date_format())).to_string() }
+            _ => { start.format(&format!("{} %H:%M:%S", date_format())).to_string() }
+        }
+    }
+
+    fn print_table(&self, slo: Option<&Slo>) {
+        let p95_budget = slo.and_then(|s| s.p95_latency_ms);
+
+        println!(
+            "{:<19} {:>8} {:>8} {:>12} {:>10}",
+            "bucket".bold(), "count".bold(), "errors".bold(), "bytes".bold(), "p95 ms".bold(),
+        );
+
+        for bucket in self.buckets.values() {
+            let stats = &bucket.stats;
+
+            let errors = format!("{:>8}", count(stats.errors));
+            let errors = match stats.errors {
+                0 => { errors.normal() }
+                _ => { errors.bright_red() }
+            };
+
+            let p95 = match stats.latency_percentile(95.0) {
+                Some(ms) => {
+                    let cell = format!("{:>10.1}", ms);
+                    match p95_budget {
+                        Some(budget) if ms > budget => { cell.bright_red().bold() }
+                        Some(_) => { cell.bright_green() }
+                        None => { cell.bright_blue() }
+                    }
+                }
+                None => { format!("{:>10}", "-").dimmed() }
+            };
+
+            println!(
+                "{:<19} {:>8} {} {:>12} {}",
+                self.label(&bucket.start).cyan(),
+                count(stats.records),
+                errors,
+                count(stats.bytes),
+                p95,
+            );
+        }
+    }
+
+    fn print_csv(&self) {
+        println!("bucket,count,errors,bytes,p95_latency_ms");
+
+        for bucket in self.buckets.values() {
+            let stats = &bucket.stats;
+            let p95 = stats.latency_percentile(95.0).map(|ms| format!("{:.1}", ms)).unwrap_or_default();
+
+            println!(
+                "{},{},{},{},{}",
+                bucket.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                stats.records,
+                stats.errors,
+                stats.bytes,
+                p95,
+            );
+        }
+    }
+
+    fn print_json(&self) {
+        let rows: Vec<serde_json::Value> = self.buckets.values().map(|bucket| {
+            json!({
+                "bucket": bucket.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                "count": bucket.stats.records,
+                "errors": bucket.stats.errors,
+                "bytes": bucket.stats.bytes,
+                "p95_latency_ms": bucket.stats.latency_percentile(95.0),
+            })
+        }).collect();
+
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use sha2::{Digest, Sha256};
+
+pub const HEADER: &str = "# splash hash chain, sha256";
+
+// the hash before the first line
+pub const SEED: [u8; 32] = [0; 32];
+
+// Each line's hash covers the one before it, so changing, removing or
+// reordering any line changes the hash of every line after it.
+pub fn link(previous: &[u8; 32], line: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(previous);
+    hasher.update(line.as_bytes());
+
+    hasher.finalize().into()
+}
+
+pub fn hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Writes the hash of every raw line read, one per line, for
+// `splash verify-chain` to check the log against later.
+pub struct HashChain {
+    path: String,
+    out: BufWriter<File>,
+    last: [u8; 32],
+}
+
+impl HashChain {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut out = BufWriter::new(file);
+
+        writeln!(out, "{}", HEADER).map_err(|e| format!("{}: {}", path, e))?;
+
+        Ok(HashChain { path: path.to_string(), out, last: SEED })
+    }
+
+    pub fn add(&mut self, line: &str) -> Result<(), String> {
+        self.last = link(&self.last, line);
+
+        writeln!(self.out, "{}", hex(&self.last)).map_err(|e| format!("{}: {}", self.path, e))
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.out.flush().map_err(|e| format!("{}: {}", self.path, e))
+    }
+}
@@ -0,0 +1,66 @@
+use colored::{Color, Colorize};
+
+const HEIGHT: usize = 8;
+
+// Plots values as an ASCII line chart, one column per value, with points
+// joined vertically to the previous column.
+pub fn render(title: &str, values: &[f64], color: Color, start: &str, end: &str) -> Vec<String> {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    let levels: Vec<usize> = values.iter().map(|v| {
+        match max > 0.0 {
+            true => { ((v / max) * (HEIGHT - 1) as f64).round() as usize }
+            false => { 0 }
+        }
+    }).collect();
+
+    let max_label = format_value(max);
+    let axis_width = max_label.len().max(1);
+
+    let mut lines = vec![title.bold().to_string()];
+
+    for row in (0..HEIGHT).rev() {
+        let label = match row {
+            r if r == HEIGHT - 1 => { max_label.clone() }
+            0 => { "0".to_string() }
+            _ => { "".to_string() }
+        };
+
+        let mut line = format!("{:>width$} {} ", label, "┤".dimmed(), width = axis_width);
+
+        for (i, level) in levels.iter().enumerate() {
+            let prev = if i == 0 { *level } else { levels[i - 1] };
+            let (low, high) = (prev.min(*level), prev.max(*level));
+
+            let c = if row == *level {
+                "*"
+            } else if row > low && row < high {
+                "|"
+            } else {
+                " "
+            };
+
+            line.push_str(&c.color(color).to_string());
+        }
+
+        lines.push(line);
+    }
+
+    let span = levels.len().max(start.len() + end.len() + 1);
+    lines.push(format!(
+        "{:>width$}   {}{}{}",
+        "",
+        start.dimmed(),
+        " ".repeat(span - start.len() - end.len()),
+        end.dimmed(),
+        width = axis_width,
+    ));
+
+    lines
+}
+
+fn format_value(v: f64) -> String {
+    match v.fract() == 0.0 {
+        true => { format!("{}", v) }
+        false => { format!("{:.1}", v) }
+    }
+}
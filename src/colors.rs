@@ -0,0 +1,156 @@
+use std::env;
+use std::process::Command;
+use std::sync::LazyLock;
+
+// How many colors the terminal shows. Styles and themes may use any of them;
+// what the terminal can't show is swapped for the nearest color it can.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Depth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+static DEPTH: LazyLock<Depth> = LazyLock::new(detect);
+
+// the 16 ANSI colors as xterm shows them, black to bright white
+const ANSI: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0), (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0), (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+// the levels of each channel in the 6x6x6 cube of the 256 color palette
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+pub fn depth() -> Depth {
+    *DEPTH
+}
+
+// COLORTERM is set by terminals that take 24-bit colors; otherwise the
+// terminal type's name or its terminfo entry says how many it has
+fn detect() -> Depth {
+    if let Ok(colorterm) = env::var("COLORTERM") {
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Depth::TrueColor;
+        }
+    }
+
+    let term = env::var("TERM").unwrap_or_default();
+
+    if term.ends_with("-direct") {
+        return Depth::TrueColor;
+    }
+    if term.contains("256color") {
+        return Depth::Ansi256;
+    }
+    if term.is_empty() || term == "dumb" {
+        return Depth::Ansi16;
+    }
+
+    let colors = Command::new("tput").arg("colors").output().ok()
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse::<u32>().ok())
+        .unwrap_or(8);
+
+    match colors {
+        16_777_216.. => { Depth::TrueColor }
+        256.. => { Depth::Ansi256 }
+        _ => { Depth::Ansi16 }
+    }
+}
+
+pub fn rgb_of_256(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => { ANSI[n as usize] }
+        16..=231 => {
+            let i = n - 16;
+            (CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize])
+        }
+        _ => {
+            let level = 8 + 10 * (n - 232);
+            (level, level, level)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).unsigned_abs();
+    d(a.0, b.0).pow(2) + d(a.1, b.1).pow(2) + d(a.2, b.2).pow(2)
+}
+
+// the nearest of the cube and the gray ramp
+pub fn to_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| CUBE.iter().enumerate().min_by_key(|(_, l)| (**l as i32 - c as i32).abs()).map(|(i, _)| i as u8).unwrap_or(0);
+
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(8) + 5) / 10).min(23) as u8;
+
+    match distance(rgb_of_256(gray), rgb) < distance(rgb_of_256(cube), rgb) {
+        true => { gray }
+        false => { cube }
+    }
+}
+
+pub fn to_16(rgb: (u8, u8, u8)) -> u8 {
+    (0..16).min_by_key(|i| distance(ANSI[*i as usize], rgb)).unwrap_or(7)
+}
+
+// Rewrites the extended colors (38;5;N, 38;2;R;G;B and the 48 background
+// forms) of an SGR sequence's parameters for the terminal's depth.
+pub fn degrade(params: &str) -> String {
+    if !params.contains("8;5;") && !params.contains("8;2;") {
+        return params.to_string();
+    }
+
+    let depth = depth();
+    let params: Vec<&str> = params.split(';').collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < params.len() {
+        let base = match params[i] {
+            "38" => { 30 }
+            "48" => { 40 }
+            p => {
+                out.push(p.to_string());
+                i += 1;
+                continue;
+            }
+        };
+
+        let channel = |j: usize| params.get(i + j).and_then(|c| c.parse::<u8>().ok());
+
+        let (rgb, indexed, len) = match params.get(i + 1) {
+            Some(&"5") => { (channel(2).map(rgb_of_256), channel(2), 3) }
+            Some(&"2") => {
+                match (channel(2), channel(3), channel(4)) {
+                    (Some(r), Some(g), Some(b)) => { (Some((r, g, b)), None, 5) }
+                    _ => { (None, None, 5) }
+                }
+            }
+            _ => { (None, None, 1) }
+        };
+
+        let end = (i + len).min(params.len());
+
+        match (rgb, indexed, depth) {
+            (Some(_), _, Depth::TrueColor) | (Some(_), Some(_), Depth::Ansi256) | (None, _, _) => {
+                out.push(params[i..end].join(";"));
+            }
+            (Some(rgb), None, Depth::Ansi256) => { out.push(format!("{};5;{}", base + 8, to_256(rgb))) }
+            (Some(rgb), _, Depth::Ansi16) => {
+                let n = to_16(rgb);
+
+                match n < 8 {
+                    true => { out.push((base + n as u32).to_string()) }
+                    false => { out.push((base + 60 + n as u32 - 8).to_string()) }
+                }
+            }
+        }
+
+        i = end;
+    }
+
+    out.join(";")
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use colored::control::{self, SHOULD_COLORIZE};
+use colored::Colorize;
+use crate::config::Matcher;
+use crate::emphasis::Emphasis;
+use crate::numbers::{count, short};
+use crate::plugins::{ParseResult, Plugin};
+use crate::render::strip_ansi;
+use crate::theme;
+
+// what's timed: parsing alone, then all of the coloring a line goes through
+// with colors on and with them off
+#[derive(Clone, Copy)]
+enum Stage {
+    Parse,
+    Color,
+    NoColor,
+}
+
+// Times the mode's parser and the coloring of its records over a log file,
+// reading it again until each stage has run for the given seconds, so
+// releases can be compared on the same file.
+pub fn run(path: &str, plugin: &dyn Plugin, matchers: &BTreeMap<String, Matcher>, seconds: u64) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let lines: Vec<&str> = contents.lines().collect();
+
+    if lines.is_empty() {
+        return Err(format!("{}: no lines to read", path));
+    }
+
+    let parsed = lines.iter().filter(|l| matches!(plugin.parse_line(l), ParseResult::Parsed(_))).count();
+
+    if parsed == 0 {
+        return Err(format!("{}: no records parsed, is --mode {} right?", path, plugin.metadata().name));
+    }
+
+    let emphasis = Emphasis::new(&[], &[], false, false).with_matchers(matchers)?;
+    let least = Duration::from_secs(seconds);
+    let color = SHOULD_COLORIZE.should_colorize();
+
+    println!("{:<10} {}, {} lines, {}B", "file".bold(), path, count(lines.len() as u64), short(contents.len() as f64));
+    println!("{:<10} {}, {} records", "mode".bold(), plugin.metadata().name, count(parsed as u64));
+    println!();
+    println!("{:<10} {:>12} {:>10}", "", "lines/s".bold(), "MB/s".bold());
+
+    for (name, stage) in [("parse", Stage::Parse), ("color", Stage::Color), ("no color", Stage::NoColor)] {
+        control::set_override(!matches!(stage, Stage::NoColor));
+
+        let started = Instant::now();
+        let mut passes: u64 = 0;
+
+        while passes == 0 || started.elapsed() < least {
+            pass(&lines, plugin, &emphasis, stage);
+            passes += 1;
+        }
+
+        let elapsed = started.elapsed().as_secs_f64();
+        let per_second = (passes * lines.len() as u64) as f64 / elapsed;
+        let mb_per_second = (passes * contents.len() as u64) as f64 / elapsed / 1_000_000.0;
+
+        control::set_override(color);
+
+        println!("{:<10} {:>12} {:>10.1}", name, count(per_second as u64), mb_per_second);
+    }
+
+    Ok(())
+}
+
+// one read of the lines, as the pipeline colors them for the terminal
+fn pass(lines: &[&str], plugin: &dyn Plugin, emphasis: &Emphasis, stage: Stage) {
+    for line in lines {
+        let record = match plugin.parse_line(line) {
+            ParseResult::Parsed(record) => { record }
+            ParseResult::NotMatched | ParseResult::Incomplete => { continue }
+        };
+
+        let shown = match stage {
+            Stage::Parse => {
+                black_box(record);
+                continue;
+            }
+            Stage::Color => {
+                let record = theme::current().paint_roles(plugin, record);
+                theme::current().apply(&emphasis.apply(&record.text))
+            }
+            Stage::NoColor => {
+                let record = theme::current().paint_roles(plugin, record);
+                strip_ansi(&emphasis.apply(&record.text))
+            }
+        };
+
+        black_box(shown);
+    }
+}
@@ -0,0 +1,67 @@
+use clap::Command;
+use serde_json::{json, Map, Value};
+use crate::plugins::PluginRegistry;
+use crate::theme::{self, THEMES};
+
+// Describes this binary and its loaded plugins as JSON: modes and the fields
+// they parse, inputs, sinks, themes and every command line option, for
+// wrappers, GUIs and completion generators.
+pub fn dump(registry: &PluginRegistry, cli: &Command) -> Result<(), String> {
+    let modes: Vec<Value> = registry.plugins().iter().map(|p| {
+        let metadata = p.metadata();
+        let styles: Map<String, Value> = metadata.styles.into_iter().map(|(tag, style)| (tag, Value::String(style))).collect();
+
+        json!({
+            "name": metadata.name,
+            "fields": p.fields(),
+            "styles": styles,
+        })
+    }).collect();
+
+    let capabilities = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "modes": modes,
+        "inputs": registry.source_names(),
+        "sinks": registry.sink_names(),
+        "themes": {
+            "builtin": THEMES,
+            "imported": theme::imported(),
+        },
+        "cli": command(cli),
+    });
+
+    println!("{}", serde_json::to_string_pretty(&capabilities).map_err(|e| e.to_string())?);
+
+    Ok(())
+}
+
+fn command(cmd: &Command) -> Value {
+    let args: Vec<Value> = cmd.get_arguments()
+        .filter(|a| !a.is_hide_set())
+        .map(|a| {
+            let values: Vec<String> = a.get_possible_values().iter().map(|v| v.get_name().to_string()).collect();
+            let takes_value = a.get_action().takes_values();
+
+            json!({
+                "name": a.get_id().as_str(),
+                "long": a.get_long(),
+                "short": a.get_short().map(|c| c.to_string()),
+                "help": a.get_help().map(|h| h.to_string()),
+                "positional": a.is_positional(),
+                "takes_value": takes_value,
+                "repeatable": matches!(a.get_action(), clap::ArgAction::Append),
+                "values": values,
+                "default": a.get_default_values().iter().map(|v| v.to_string_lossy().to_string()).collect::<Vec<String>>(),
+            })
+        })
+        .collect();
+
+    let subcommands: Vec<Value> = cmd.get_subcommands().filter(|s| s.get_name() != "help").map(command).collect();
+
+    json!({
+        "name": cmd.get_name(),
+        "about": cmd.get_about().map(|a| a.to_string()),
+        "args": args,
+        "subcommands": subcommands,
+    })
+}
@@ -0,0 +1,49 @@
+use std::fs;
+use colored::Colorize;
+use crate::chain::{hex, link, HEADER, SEED};
+
+// Checks a log against the chain recorded with --hash-chain. Lines appended
+// since are reported but don't fail the check; a changed, removed or
+// reordered line does. Returns whether the recorded lines are intact.
+pub fn verify(path: &str, chain_path: &str) -> Result<bool, String> {
+    let log = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let log = String::from_utf8_lossy(&log);
+    let chain = fs::read_to_string(chain_path).map_err(|e| format!("{}: {}", chain_path, e))?;
+
+    let mut hashes = chain.lines();
+    if hashes.next() != Some(HEADER) {
+        return Err(format!("{}: not a splash hash chain", chain_path));
+    }
+
+    let mut lines = log.lines();
+    let mut last = SEED;
+    let mut verified = 0;
+
+    for expected in hashes {
+        let line = match lines.next() {
+            Some(l) => { l }
+            None => {
+                println!("{} {}: {} lines verified, then the log ends before the chain does", "✗".bright_red(), path, verified);
+                return Ok(false);
+            }
+        };
+
+        last = link(&last, line);
+
+        if hex(&last) != expected.trim() {
+            println!("{} {}: line {} was changed, removed or moved", "✗".bright_red(), path, verified + 1);
+            println!("  {}", line);
+            return Ok(false);
+        }
+
+        verified += 1;
+    }
+
+    let unrecorded = lines.count();
+    match unrecorded {
+        0 => { println!("{} {}: {} lines verified", "✓".bright_green(), path, verified) }
+        _ => { println!("{} {}: {} lines verified, {} later lines not in the chain", "✓".bright_green(), path, verified, unrecorded) }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::io::{self, Read};
+use colored::Colorize;
+use crate::numbers::count;
+use crate::plugins::{ParseResult, Plugin};
+
+// Counts the lines of a log that a mode parses into records with fields and
+// the ones it doesn't, showing the first few of those. Lines of a multi-line
+// record pass once it's whole. Returns whether every line parsed.
+pub fn run(path: Option<&str>, plugin: &dyn Plugin, show: usize) -> Result<bool, String> {
+    let contents = match path {
+        Some(p) => { fs::read_to_string(p).map_err(|e| format!("{}: {}", p, e))? }
+        None => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s).map_err(|e| e.to_string())?;
+            s
+        }
+    };
+
+    // modes without fields, like ad-hoc, parse whatever they take
+    let fieldless = plugin.fields().is_empty();
+    let name = path.unwrap_or("stdin");
+
+    let mut parsed: u64 = 0;
+    let mut failed: Vec<(usize, String)> = Vec::new();
+    let mut pending: Vec<(usize, &str)> = Vec::new();
+    let mut failures: u64 = 0;
+
+    let mut fail = |(number, line): (usize, &str)| {
+        failures += 1;
+
+        if failed.len() < show {
+            failed.push((number, line.to_string()));
+        }
+    };
+
+    for (i, line) in contents.lines().enumerate() {
+        let blank = line.trim().is_empty();
+
+        match plugin.parse_line(line) {
+            ParseResult::Parsed(r) if fieldless || !r.fields.is_empty() => {
+                parsed += pending.len() as u64 + !blank as u64;
+                pending.clear();
+            }
+            ParseResult::Incomplete => {
+                if !blank {
+                    pending.push((i + 1, line));
+                }
+            }
+            _ => {
+                pending.drain(..).for_each(&mut fail);
+
+                if !blank {
+                    fail((i + 1, line));
+                }
+            }
+        }
+    }
+
+    pending.drain(..).for_each(&mut fail);
+
+    for (number, line) in &failed {
+        println!("{} {}", format!("{}:{}:", name, number).bright_white().bold(), line);
+    }
+
+    if failures > failed.len() as u64 {
+        println!("{}", format!("... {} more", count(failures - failed.len() as u64)).dimmed());
+    }
+
+    if !failed.is_empty() {
+        println!();
+    }
+
+    let total = parsed + failures;
+    let share = match total {
+        0 => { 0.0 }
+        _ => { failures as f64 * 100.0 / total as f64 }
+    };
+    let summary = format!("{}: {} lines in {} mode, {} parsed, {} failed ({:.1}%)", name, count(total), plugin.metadata().name, count(parsed), count(failures), share);
+
+    match failures {
+        0 => { println!("{} {}", "✓".bright_green(), summary) }
+        _ => { println!("{} {}", "✗".bright_red(), summary) }
+    }
+
+    Ok(failures == 0)
+}
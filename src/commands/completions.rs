@@ -0,0 +1,42 @@
+use std::io;
+use clap::builder::PossibleValuesParser;
+use clap::Command;
+use clap_complete::Shell;
+use crate::config::Config;
+use crate::plugins::PluginRegistry;
+use crate::theme::{self, THEMES};
+
+// Prints a completion script for a shell. The modes, themes and profiles are
+// the ones there when it is generated, external plugins and imported themes
+// included, so the script is worth generating again after adding some.
+pub fn generate(shell: Shell, mut cli: Command, registry: &PluginRegistry, config: &Config) {
+    let mut modes = registry.mode_names();
+    modes.push("list".to_string());
+
+    let themes: Vec<String> = THEMES.iter().map(|t| t.to_string())
+        .chain(config.themes.keys().cloned())
+        .chain(theme::imported())
+        .collect();
+
+    cli = values(cli, "mode", &modes);
+    cli = values(cli, "theme", &themes);
+    cli = values(cli, "profile", &config.profiles.keys().cloned().collect::<Vec<String>>());
+
+    let name = cli.get_name().to_string();
+    clap_complete::generate(shell, &mut cli, name, &mut io::stdout());
+}
+
+// offers values for an option, wherever it is: tail and cat have the display
+// options of their own
+fn values(cmd: Command, arg: &str, values: &[String]) -> Command {
+    let subcommands: Vec<String> = cmd.get_subcommands().map(|s| s.get_name().to_string()).collect();
+
+    let has = cmd.get_arguments().any(|a| a.get_id() == arg);
+
+    let cmd = match has {
+        true => { cmd.mut_arg(arg, |a| a.value_parser(PossibleValuesParser::new(values.to_vec()))) }
+        false => { cmd }
+    };
+
+    subcommands.iter().fold(cmd, |cmd, name| cmd.mut_subcommand(name, |s| self::values(s, arg, values)))
+}
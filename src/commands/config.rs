@@ -0,0 +1,179 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use crate::config::{self, Config};
+use crate::plugins::PluginDiscovery;
+
+// every setting, commented out, with its default or an example
+const TEMPLATE: &str = r##"# splash configuration. Every setting is commented out; options given on the
+# command line win over the ones here. `splash config dump` shows the result.
+
+# Mode when neither --mode nor [modes] picks one
+# mode = "ad-hoc"
+
+# default, deuteranopia, protanopia, tritanopia, solarized-dark,
+# solarized-light, monochrome, high-contrast, a theme from [themes] below or
+# an imported one
+# theme = "default"
+
+# deuteranopia, protanopia or tritanopia: that theme's colors under any theme,
+# with severity also marked as by redundant_styles
+# palette = "deuteranopia"
+
+# dark or light; asked of the terminal when left out
+# background = "dark"
+
+# Also mark severity with symbols, bold and underline instead of color alone
+# redundant_styles = false
+
+# Seconds between polls of an http(s):// path (default 5), or of followed
+# files and spool folders where changes can't be watched for (default 2)
+# poll_interval = 5
+
+# terminal, file:PATH, html:PATH, quickfix[:PATH], elasticsearch:URL,
+# loki:URL, syslog:HOST[:PORT] or webhook:URL
+# sinks = ["terminal"]
+
+# Modes by file name; a pattern with a / is matched against the whole path
+# [modes]
+# "*access*.log" = "clf"
+# "/var/log/auth.log" = "auth"
+
+# Modes each line is offered to in turn, for files mixing formats, picked
+# with --mode web like any other mode
+# [chains]
+# web = ["clf", "tomcat", "ad-hoc"]
+
+# Patterns painted in a style of their own, like --highlight
+# [matchers.request_id]
+# pattern = "req-[0-9a-f]{8}"
+# style = "bold magenta"
+
+# Most characters of a field to show, by "field" or "mode.field"
+# [widths]
+# user_agent = 40
+
+# Line numbers and input names on each line, for every mode or as
+# "mode.line_numbers"
+# [prefix]
+# line_numbers = false
+# show_source = false
+
+# Styles of the clf mode's fields and status classes
+# [clf]
+# client = "cyan"
+# status_4xx = "yellow"
+# status_5xx = "bold red"
+
+# The ad-hoc mode's matchers: those in order are tried first, the disabled
+# ones not at all
+# [adhoc]
+# order = ["ip_addr"]
+# disabled = ["number"]
+
+# What to look at and how, picked with --profile nginx-prod
+# [profile.nginx-prod]
+# path = "/var/log/nginx/access.log"
+# mode = "clf"
+# theme = "ops"
+# min_level = "warning"
+# include = ["/api/"]
+
+# Options for a mode: clf's slow_ms, or whatever a scripted or external
+# format takes
+# [plugin.clf]
+# slow_ms = 500
+
+# Modes, sinks and inputs turned off, as by splash plugins disable
+# [plugins]
+# disabled = ["webhook"]
+
+# Latency and error budgets for --slo
+# [slo]
+# p95_latency_ms = 200
+# p99_latency_ms = 300
+# error_rate = 0.1
+
+# Themes written out in full, picked with --theme ops
+# [themes.ops]
+# bright_red = "#ff5f5f"
+#
+# [themes.ops.roles]
+# ip = "bold cyan"
+# status-5xx = "white on_red"
+"##;
+
+fn path(path: Option<&str>) -> Result<PathBuf, String> {
+    match path {
+        Some(p) => { Ok(PathBuf::from(p)) }
+        None => { config::default_path().ok_or("no home directory for the config file, pass --config".to_string()) }
+    }
+}
+
+// writes the commented template, leaving an existing file alone unless forced
+pub fn init(target: Option<&str>, force: bool) -> Result<(), String> {
+    let target = path(target)?;
+
+    if target.exists() && !force {
+        return Err(format!("{} already exists, use --force to replace it", target.display()));
+    }
+
+    if let Some(dir) = target.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+
+    fs::write(&target, TEMPLATE).map_err(|e| format!("{}: {}", target.display(), e))?;
+
+    println!("Wrote {}", target.display());
+    Ok(())
+}
+
+// The configuration in effect, as TOML, under comments for what the
+// environment decides that the file has no setting for. `color` is the
+// option that set the color choice, when the command line did.
+pub fn dump(source: Option<&str>, config: &Config, color: Option<&str>) -> Result<(), String> {
+    let file = match path(source) {
+        Ok(p) if p.exists() => { p.display().to_string() }
+        Ok(p) => { format!("no config file at {}", p.display()) }
+        Err(_) => { "no config file".to_string() }
+    };
+
+    println!("# {}, with the command line and environment applied", file);
+
+    if source.is_none() && env::var_os("XDG_CONFIG_HOME").is_some_and(|d| !d.is_empty()) {
+        println!("# config directory from $XDG_CONFIG_HOME");
+    }
+
+    let colored = colored::control::SHOULD_COLORIZE.should_colorize();
+    let why = color.unwrap_or(match colored {
+        true => { color_source().unwrap_or("stdout is a terminal") }
+        false => { color_source().unwrap_or("stdout isn't a terminal") }
+    });
+
+    match colored {
+        true => { println!("# color on ({})", why) }
+        false => { println!("# color off ({})", why) }
+    }
+
+    let dirs: Vec<String> = PluginDiscovery::new().dirs().iter().map(|d| d.display().to_string()).collect();
+    let from = match env::var_os("SPLASH_PLUGIN_PATH") {
+        Some(_) => { " ($SPLASH_PLUGIN_PATH first)" }
+        None => { "" }
+    };
+    println!("# plugin directories {}{}", dirs.join(", "), from);
+
+    print!("{}", toml::to_string(config).map_err(|e| e.to_string())?);
+
+    Ok(())
+}
+
+// the variable that decides color when --color is auto, as colored weighs
+// them, if one does
+fn color_source() -> Option<&'static str> {
+    match (env::var_os("CLICOLOR_FORCE"), env::var_os("NO_COLOR"), env::var_os("CLICOLOR")) {
+        (Some(force), _, _) if force != "0" => { Some("$CLICOLOR_FORCE") }
+        (_, Some(_), _) => { Some("$NO_COLOR") }
+        (_, _, Some(clicolor)) if clicolor == "0" => { Some("$CLICOLOR") }
+        _ => { None }
+    }
+}
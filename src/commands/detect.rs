@@ -0,0 +1,47 @@
+use std::fs;
+use colored::Colorize;
+use crate::plugins::PluginRegistry;
+
+// lines read from the start of the file
+const SAMPLE: usize = 500;
+
+// Ranks the modes by how much of a sample of the file each one recognizes,
+// for picking --mode for an unfamiliar log. ad-hoc colors any line, so it
+// isn't ranked.
+pub fn run(path: &str, registry: &PluginRegistry) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let lines: Vec<&str> = contents.lines().take(SAMPLE).collect();
+    let sampled = lines.iter().filter(|l| !l.trim().is_empty()).count();
+
+    if sampled == 0 {
+        return Err(format!("{}: no lines to sample", path));
+    }
+
+    let mut scores: Vec<(String, f64)> = registry.plugins().iter()
+        .filter(|p| p.metadata().name != "ad-hoc")
+        .map(|p| (p.metadata().name, p.detect_format(&lines)))
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    println!("{:<14} {:>10} {:>12}", "mode".bold(), "confidence".bold(), "lines".bold());
+
+    for (i, (name, score)) in scores.iter().enumerate() {
+        let confidence = format!("{:.1}%", score * 100.0);
+        let lines = format!("{}/{}", (score * sampled as f64).round() as usize, sampled);
+
+        match (i, *score > 0.0) {
+            (0, true) => { println!("{:<14} {:>10} {:>12}", name.bright_green().bold(), confidence.bright_green(), lines) }
+            (_, true) => { println!("{:<14} {:>10} {:>12}", name, confidence, lines) }
+            (_, false) => { println!("{}", format!("{:<14} {:>10} {:>12}", name, confidence, lines).dimmed()) }
+        }
+    }
+
+    println!();
+
+    match scores.first() {
+        Some((name, score)) if *score > 0.0 => { println!("Try --mode {}", name) }
+        _ => { println!("No mode recognizes these lines, --mode ad-hoc colors them anyway") }
+    }
+
+    Ok(())
+}
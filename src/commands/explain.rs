@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::sync::LazyLock;
+use colored::control::{self, SHOULD_COLORIZE};
+use colored::Colorize;
+use regex::Regex;
+use crate::config::Matcher;
+use crate::emphasis::Emphasis;
+use crate::plugins::{self, ParseResult, Plugin, Record};
+use crate::render::strip_ansi;
+use crate::theme;
+
+static SGR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[([0-9;]*)m").unwrap()
+});
+
+const COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+// a parsed record, the line it comes out as and, in ad-hoc mode, the
+// matchers that colored it
+struct Explained {
+    record: Record,
+    output: String,
+    fired: Vec<(String, &'static str)>,
+}
+
+// Shows how splash colors a line: whether the mode parsed it and into what
+// fields, the theme roles those have, the matchers that fired and the colors
+// that came out. Several lines are taken as the lines of one input, so a
+// multi-line record is explained once it's whole.
+pub fn run(line: Option<&str>, plugin: &dyn Plugin, matchers: &BTreeMap<String, Matcher>) -> Result<(), String> {
+    let input = match line {
+        Some(l) => { l.to_string() }
+        None => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s).map_err(|e| e.to_string())?;
+            s
+        }
+    };
+
+    // escape sequences are what's explained, even when output isn't colored
+    let color = SHOULD_COLORIZE.should_colorize();
+    control::set_override(true);
+
+    let emphasis = Emphasis::new(&[], &[], false, false).with_matchers(matchers)?;
+    let name = plugin.metadata().name;
+    let mut explained: Vec<Explained> = Vec::new();
+    let mut pending = false;
+
+    for line in input.lines() {
+        match plugin.parse_line(line) {
+            ParseResult::Parsed(record) => {
+                let record = theme::current().paint_roles(plugin, record);
+                let output = theme::current().apply(&emphasis.apply(&record.text));
+                let fired = match name.as_str() {
+                    "ad-hoc" => { plugins::fired_matchers(line) }
+                    _ => { Vec::new() }
+                };
+
+                explained.push(Explained { record, output, fired });
+                pending = false;
+            }
+            ParseResult::Incomplete => { pending = true }
+            ParseResult::NotMatched => { pending = false }
+        }
+    }
+
+    control::set_override(color);
+
+    if explained.is_empty() {
+        let why = match pending {
+            true => { "took the lines as the start of a multi-line record that never ended" }
+            false => { "didn't match, the line would not be shown" }
+        };
+
+        println!("{:<10} {} {}", "mode".bold(), name, why.bright_yellow());
+        return Ok(());
+    }
+
+    for (i, e) in explained.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        explain(&name, e, matchers, color);
+    }
+
+    Ok(())
+}
+
+fn explain(plugin: &str, explained: &Explained, matchers: &BTreeMap<String, Matcher>, color: bool) {
+    let Explained { record, output, fired } = explained;
+    let plain = strip_ansi(output);
+
+    println!("{:<10} {} {}", "mode".bold(), plugin, "parsed".bright_green());
+
+    if !record.fields.is_empty() {
+        println!("{}", "fields".bold());
+
+        for (name, value) in &record.fields {
+            // the type, for fields the mode read as more than text
+            let typed = match record.values.iter().find(|(n, _)| n == name) {
+                Some((_, v)) => { format!("{}, ", v.type_name()) }
+                None => { "".to_string() }
+            };
+
+            let mut roles = theme::roles_of(name, value);
+            roles.dedup();
+            let roles = roles.join(", ");
+
+            let styled = match theme::current().role_style(plugin, name, value) {
+                Some((role, style)) => { format!("  theme {} = \"{}\"", role, style) }
+                None => { "".to_string() }
+            };
+
+            println!("  {:<16} {:<30} {}{}", name, value, format!("{}roles {}", typed, roles).dimmed(), styled);
+        }
+    }
+
+    let highlighted: Vec<(&String, String, &Matcher)> = matchers.iter()
+        .filter_map(|(name, m)| Some((name, Regex::new(&m.pattern).ok()?, m)))
+        .flat_map(|(name, re, m)| re.find_iter(&plain).map(|found| (name, found.as_str().to_string(), m)).collect::<Vec<_>>())
+        .collect();
+
+    if !fired.is_empty() || !highlighted.is_empty() {
+        println!("{}", "matchers".bold());
+    }
+
+    for (text, matcher) in fired {
+        println!("  {:<16} {}", matcher, text);
+    }
+
+    for (name, text, matcher) in highlighted {
+        println!("  {:<16} {:<30} {}", name, text, format!("style \"{}\"", matcher.style).dimmed());
+    }
+
+    let spans = spans(output);
+
+    if !spans.is_empty() {
+        println!("{}", "colors".bold());
+    }
+
+    for (params, text) in spans {
+        let shown = match color {
+            true => { format!("\x1b[{}m{}\x1b[0m", params.join(";"), text) }
+            false => { text.clone() }
+        };
+        let pad = 30usize.saturating_sub(text.chars().count());
+
+        println!("  {}{} {} {}", shown, " ".repeat(pad), describe(&params), format!("\\e[{}m", params.join(";")).dimmed());
+    }
+
+    match color {
+        true => { println!("{:<10} {}", "output".bold(), output) }
+        false => { println!("{:<10} {}", "output".bold(), plain) }
+    }
+}
+
+// The runs of text in the same style, with the parameters of the escape
+// sequences in effect for each, leaving out unstyled and blank runs.
+fn spans(s: &str) -> Vec<(Vec<String>, String)> {
+    let mut spans: Vec<(Vec<String>, String)> = Vec::new();
+    let mut active: Vec<String> = Vec::new();
+    let mut pos = 0;
+
+    let mut push = |active: &Vec<String>, text: &str| {
+        if text.is_empty() {
+            return;
+        }
+
+        match spans.last_mut() {
+            Some((params, run)) if params == active => { run.push_str(text) }
+            _ => { spans.push((active.clone(), text.to_string())) }
+        }
+    };
+
+    for c in SGR.captures_iter(s) {
+        let m = c.get(0).unwrap();
+        push(&active, &s[pos..m.start()]);
+        pos = m.end();
+
+        apply(&mut active, &c[1]);
+    }
+
+    push(&active, &s[pos..]);
+
+    spans.retain(|(params, text)| !params.is_empty() && !text.trim().is_empty());
+    spans
+}
+
+// what an escape sequence's parameters do to the style in effect
+fn apply(active: &mut Vec<String>, params: &str) {
+    let params: Vec<&str> = params.split(';').collect();
+    let mut i = 0;
+
+    while i < params.len() {
+        let (code, len) = match params[i] {
+            "38" | "48" if params.get(i + 1) == Some(&"5") => { (params[i..(i + 3).min(params.len())].join(";"), 3) }
+            "38" | "48" if params.get(i + 1) == Some(&"2") => { (params[i..(i + 5).min(params.len())].join(";"), 5) }
+            p => { (p.to_string(), 1) }
+        };
+        i += len;
+
+        let fg = |p: &String| p.starts_with("38;") || matches!(p.parse::<u8>(), Ok(30..=37 | 90..=97));
+        let bg = |p: &String| p.starts_with("48;") || matches!(p.parse::<u8>(), Ok(40..=47 | 100..=107));
+
+        match code.as_str() {
+            "" | "0" => { active.clear() }
+            "22" => { active.retain(|p| p != "1" && p != "2") }
+            "23" | "24" | "27" | "29" => {
+                let on = (code.parse::<u8>().unwrap_or(0) - 20).to_string();
+                active.retain(|p| *p != on)
+            }
+            "39" => { active.retain(|p| !fg(p)) }
+            "49" => { active.retain(|p| !bg(p)) }
+            _ if fg(&code) => {
+                active.retain(|p| !fg(p));
+                active.push(code);
+            }
+            _ if bg(&code) => {
+                active.retain(|p| !bg(p));
+                active.push(code);
+            }
+            _ => {
+                if !active.contains(&code) {
+                    active.push(code);
+                }
+            }
+        }
+    }
+}
+
+// the parameters in the words a style is written with
+fn describe(params: &[String]) -> String {
+    let words: Vec<String> = params.iter().map(|p| {
+        let n: Vec<u8> = p.split(';').filter_map(|n| n.parse().ok()).collect();
+
+        match n.as_slice() {
+            [1] => { "bold".to_string() }
+            [2] => { "dimmed".to_string() }
+            [3] => { "italic".to_string() }
+            [4] => { "underline".to_string() }
+            [7] => { "reversed".to_string() }
+            [9] => { "strikethrough".to_string() }
+            [c @ 30..=37] => { COLORS[(c - 30) as usize].to_string() }
+            [c @ 90..=97] => { format!("bright_{}", COLORS[(c - 90) as usize]) }
+            [c @ 40..=47] => { format!("on_{}", COLORS[(c - 40) as usize]) }
+            [c @ 100..=107] => { format!("on_bright_{}", COLORS[(c - 100) as usize]) }
+            [38, 5, c] => { format!("color {}", c) }
+            [48, 5, c] => { format!("on color {}", c) }
+            [38, 2, r, g, b] => { format!("#{:02x}{:02x}{:02x}", r, g, b) }
+            [48, 2, r, g, b] => { format!("on #{:02x}{:02x}{:02x}", r, g, b) }
+            _ => { p.clone() }
+        }
+    }).collect();
+
+    words.join(" ")
+}
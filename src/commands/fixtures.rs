@@ -0,0 +1,450 @@
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::net::Ipv4Addr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use crate::time::parse_bound;
+
+const MODES: [&str; 12] = ["ad-hoc", "clf", "serilog", "auth", "auditd", "firewall", "tomcat", "winevent", "asa", "gelf", "logcat", "journald"];
+
+const PATHS: [&str; 12] = [
+    "/", "/index.html", "/login", "/logout", "/api/users", "/api/users/42", "/api/orders",
+    "/api/orders/1001/items", "/static/app.js", "/static/style.css", "/health", "/search?q=splash",
+];
+
+const STATUSES: [u16; 10] = [200, 200, 200, 200, 200, 201, 204, 301, 304, 404];
+const ERROR_STATUSES: [u16; 4] = [500, 502, 503, 504];
+const METHODS: [&str; 6] = ["GET", "GET", "GET", "GET", "POST", "PUT"];
+const USERS: [&str; 6] = ["alice", "bob", "carol", "dave", "deploy", "root"];
+const HOSTS: [&str; 4] = ["web1", "web2", "db1", "gateway"];
+const USER_AGENTS: [&str; 4] = [
+    "Mozilla/5.0 (X11; Linux x86_64; rv:126.0) Gecko/20100101 Firefox/126.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+    "curl/8.5.0",
+    "Googlebot/2.1 (+http://www.google.com/bot.html)",
+];
+
+const INFO_MESSAGES: [&str; 6] = [
+    "Request finished in {ms}ms",
+    "User {user} signed in",
+    "Cache refreshed with {n} entries",
+    "Order {n} created",
+    "Connected to database",
+    "Scheduled job completed",
+];
+const WARNING_MESSAGES: [&str; 3] = [
+    "Slow query took {ms}ms",
+    "Retrying request to payments, attempt {n}",
+    "Connection pool is {n}% used",
+];
+const ERROR_MESSAGES: [&str; 4] = [
+    "Unhandled exception processing order {n}",
+    "Timeout waiting for payments after {ms}ms",
+    "Database connection refused",
+    "Failed to write to /var/lib/app/cache",
+];
+
+// xorshift64*, enough to vary fixtures without a dependency and reproducible
+// from a seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+
+    fn float(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.float() < p
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+pub struct Options {
+    pub lines: u64,
+    pub error_rate: f64,
+    pub ip_pool: String,
+    pub clients: u64,
+    pub paths: Vec<String>,
+    pub statuses: Vec<u16>,
+    pub start: Option<String>,
+    pub rate: f64,
+    pub seed: Option<u64>,
+}
+
+struct Generator {
+    rng: Rng,
+    clients: Vec<String>,
+    paths: Vec<String>,
+    statuses: Vec<u16>,
+    serial: u64,
+}
+
+// Writes synthetic logs in a built-in mode's format to stdout: a pool of
+// clients, paths and statuses, a share of error lines, and timestamps spaced
+// like independent arrivals at an average rate.
+pub fn generate(mode: &str, options: &Options) -> Result<(), String> {
+    if !MODES.contains(&mode) {
+        return Err(format!("no fixtures for mode {}, expected one of {}", mode, MODES.join(", ")));
+    }
+
+    if !(0.0..=1.0).contains(&options.error_rate) {
+        return Err(format!("invalid error rate {}, expected a number from 0 to 1", options.error_rate));
+    }
+
+    if !options.rate.is_finite() || options.rate <= 0.0 {
+        return Err(format!("invalid rate {}, expected lines per second above 0", options.rate));
+    }
+
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(1)
+    });
+
+    let mut rng = Rng::new(seed);
+    let clients = client_pool(&mut rng, &options.ip_pool, options.clients)?;
+
+    let mut generator = Generator {
+        rng,
+        clients,
+        paths: match options.paths.is_empty() {
+            true => { PATHS.iter().map(|p| p.to_string()).collect() }
+            false => { options.paths.clone() }
+        },
+        statuses: match options.statuses.is_empty() {
+            true => { STATUSES.to_vec() }
+            false => { options.statuses.clone() }
+        },
+        serial: 1000,
+    };
+
+    // by default the fixtures end about now
+    let mut t: DateTime<Local> = match &options.start {
+        Some(s) => { parse_bound(s)?.with_timezone(&Local) }
+        None => { Local::now() - TimeDelta::milliseconds((options.lines as f64 / options.rate * 1000.0) as i64) }
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    for _ in 0..options.lines {
+        let error = generator.rng.chance(options.error_rate);
+        let entry = generator.entry(mode, t, error);
+
+        if let Err(e) = writeln!(out, "{}", entry) {
+            return match e.kind() {
+                ErrorKind::BrokenPipe => { Ok(()) }
+                _ => { Err(e.to_string()) }
+            };
+        }
+
+        // exponential gaps between arrivals
+        let gap = -(1.0 - generator.rng.float()).ln() / options.rate;
+        t += TimeDelta::microseconds((gap * 1_000_000.0) as i64);
+    }
+
+    match out.flush() {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => { Err(e.to_string()) }
+        _ => { Ok(()) }
+    }
+}
+
+// distinct addresses drawn from an IPv4 block
+fn client_pool(rng: &mut Rng, pool: &str, size: u64) -> Result<Vec<String>, String> {
+    let invalid = || format!("invalid ip pool '{}', expected an IPv4 block like 203.0.113.0/24", pool);
+
+    let (base, prefix) = pool.split_once('/').ok_or_else(invalid)?;
+    let base: Ipv4Addr = base.parse().map_err(|_| invalid())?;
+    let prefix: u32 = prefix.parse().ok().filter(|p| *p <= 32).ok_or_else(invalid)?;
+
+    let hosts = 1u64 << (32 - prefix);
+    let network = u32::from(base) & u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+
+    Ok((0..size.max(1)).map(|_| {
+        Ipv4Addr::from(network | rng.below(hosts) as u32).to_string()
+    }).collect())
+}
+
+impl Generator {
+    fn entry(&mut self, mode: &str, t: DateTime<Local>, error: bool) -> String {
+        self.serial += 1 + self.rng.below(3);
+
+        match mode {
+            "serilog" => { self.serilog(t, error) }
+            "auth" => { self.auth(t, error) }
+            "auditd" => { self.auditd(t, error) }
+            "firewall" => { self.firewall(t, error) }
+            "tomcat" => { self.tomcat(t, error) }
+            "winevent" => { self.winevent(t, error) }
+            "asa" => { self.asa(t, error) }
+            "gelf" => { self.gelf(t, error) }
+            "logcat" => { self.logcat(t, error) }
+            "journald" => { self.journald(t, error) }
+            _ => { self.clf(t, error) }
+        }
+    }
+
+    fn client(&mut self) -> String {
+        self.rng.pick(&self.clients).clone()
+    }
+
+    // a message of the given severity, 0 info, 1 warning and 2 error
+    fn message(&mut self, severity: u8) -> String {
+        let template = match severity {
+            0 => { *self.rng.pick(&INFO_MESSAGES) }
+            1 => { *self.rng.pick(&WARNING_MESSAGES) }
+            _ => { *self.rng.pick(&ERROR_MESSAGES) }
+        };
+
+        let user = *self.rng.pick(&USERS);
+
+        template
+            .replace("{ms}", &(20 + self.rng.below(5000)).to_string())
+            .replace("{n}", &(1 + self.rng.below(1000)).to_string())
+            .replace("{user}", user)
+    }
+
+    // mostly info, some warnings, or an error
+    fn severity(&mut self, error: bool) -> u8 {
+        match (error, self.rng.chance(0.1)) {
+            (true, _) => { 2 }
+            (false, true) => { 1 }
+            (false, false) => { 0 }
+        }
+    }
+
+    fn clf(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let status = match error {
+            true => { *self.rng.pick(&ERROR_STATUSES) }
+            false => { *self.rng.pick(&self.statuses) }
+        };
+
+        let size = match status {
+            204 | 304 => { 0 }
+            _ => { 200 + self.rng.below(20_000) }
+        };
+
+        let seconds = match error {
+            true => { 0.5 + self.rng.float() * 30.0 }
+            false => { 0.002 + self.rng.float() * self.rng.float() * 0.8 }
+        };
+
+        format!(
+            "{} - {} [{}] \"{} {} HTTP/1.1\" {} {} \"-\" \"{}\" {:.3}",
+            self.client(),
+            if self.rng.chance(0.2) { *self.rng.pick(&USERS) } else { "-" },
+            t.format("%d/%b/%Y:%H:%M:%S %z"),
+            self.rng.pick(&METHODS),
+            self.rng.pick(&self.paths),
+            status,
+            size,
+            self.rng.pick(&USER_AGENTS),
+            seconds,
+        )
+    }
+
+    fn serilog(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let severity = self.severity(error);
+        let level = match severity {
+            0 if self.rng.chance(0.2) => { "DBG" }
+            0 => { "INF" }
+            1 => { "WRN" }
+            _ => { "ERR" }
+        };
+
+        let mut entry = format!("[{} {}] {}", t.format("%Y-%m-%d %H:%M:%S%.3f"), level, self.message(severity));
+
+        if severity == 2 {
+            entry.push_str("\nSystem.InvalidOperationException: Sequence contains no elements");
+            entry.push_str("\n   at System.Linq.ThrowHelper.ThrowNoElementsException()");
+            entry.push_str("\n   at Shop.Orders.OrderService.Submit(Order order) in /src/Shop/Orders/OrderService.cs:line 87");
+        }
+
+        entry
+    }
+
+    fn auth(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let message = match error {
+            true if self.rng.chance(0.5) => { format!("Failed password for invalid user admin from {} port {} ssh2", self.client(), 1024 + self.rng.below(60_000)) }
+            true => { format!("Failed password for {} from {} port {} ssh2", self.rng.pick(&USERS), self.client(), 1024 + self.rng.below(60_000)) }
+            false => { format!("Accepted publickey for {} from {} port {} ssh2: ED25519 SHA256:q8BzXa2L1", self.rng.pick(&USERS), self.client(), 1024 + self.rng.below(60_000)) }
+        };
+
+        format!("{} {} sshd[{}]: {}", t.format("%b %e %H:%M:%S"), self.rng.pick(&HOSTS), 1000 + self.rng.below(30_000), message)
+    }
+
+    fn auditd(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let command = *self.rng.pick(&["ls", "cat", "curl", "python3", "sudo"]);
+        let (success, exit) = match error {
+            true => { ("no", "-13") }
+            false => { ("yes", "0") }
+        };
+
+        format!(
+            "type=SYSCALL msg=audit({}.{:03}:{}): arch=c000003e syscall=59 success={} exit={} ppid={} pid={} auid={} uid={} tty=pts0 comm=\"{}\" exe=\"/usr/bin/{}\" key=\"exec\"",
+            t.timestamp(),
+            t.timestamp_subsec_millis(),
+            self.serial,
+            success,
+            exit,
+            1000 + self.rng.below(30_000),
+            1000 + self.rng.below(30_000),
+            1000 + self.rng.below(5),
+            1000 + self.rng.below(5),
+            command,
+            command,
+        )
+    }
+
+    fn firewall(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let action = if error { "BLOCK" } else { "ALLOW" };
+        let port = *self.rng.pick(&[22, 80, 443, 3306, 8080]);
+
+        format!(
+            "{} {} kernel: [{}.{:06}] [UFW {}] IN=eth0 OUT= MAC=52:54:00:12:34:56:52:54:00:65:43:21:08:00 SRC={} DST=10.0.0.5 LEN=60 TOS=0x00 PREC=0x00 TTL=52 ID={} DF PROTO=TCP SPT={} DPT={} WINDOW=64240 RES=0x00 SYN URGP=0",
+            t.format("%b %e %H:%M:%S"),
+            self.rng.pick(&HOSTS),
+            10_000 + self.rng.below(900_000),
+            self.rng.below(1_000_000),
+            action,
+            self.client(),
+            self.rng.below(65_536),
+            1024 + self.rng.below(60_000),
+            port,
+        )
+    }
+
+    fn tomcat(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let severity = self.severity(error);
+        let level = match severity {
+            0 => { "INFO" }
+            1 => { "WARNING" }
+            _ => { "SEVERE" }
+        };
+
+        let mut entry = format!(
+            "{} {} [http-nio-8080-exec-{}] com.example.shop.OrderController {}",
+            t.format("%d-%b-%Y %H:%M:%S%.3f"),
+            level,
+            1 + self.rng.below(20),
+            self.message(severity),
+        );
+
+        if severity == 2 {
+            entry.push_str("\njava.lang.IllegalStateException: order has no items");
+            entry.push_str("\n\tat com.example.shop.OrderService.submit(OrderService.java:87)");
+            entry.push_str("\n\tat com.example.shop.OrderController.create(OrderController.java:42)");
+            entry.push_str("\n\t... 23 more");
+        }
+
+        entry
+    }
+
+    fn winevent(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let (provider, id, level, message) = match error {
+            true => { ("Service Control Manager", 7031, 2, "The Print Spooler service terminated unexpectedly.".to_string()) }
+            false => { ("Microsoft-Windows-Security-Auditing", 4624, 4, format!("An account was successfully logged on from {}.", self.client())) }
+        };
+
+        format!(
+            "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='{}'/><EventID>{}</EventID><Level>{}</Level><TimeCreated SystemTime='{}'/><EventRecordID>{}</EventRecordID><Computer>{}</Computer></System><EventData><Data Name='TargetUserName'>{}</Data></EventData><RenderingInfo Culture='en-US'><Message>{}</Message></RenderingInfo></Event>",
+            provider,
+            id,
+            level,
+            t.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%S%.6fZ"),
+            self.serial,
+            self.rng.pick(&HOSTS).to_uppercase(),
+            self.rng.pick(&USERS),
+            message,
+        )
+    }
+
+    fn asa(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let client = self.client();
+        let port = 1024 + self.rng.below(60_000);
+
+        let message = match error {
+            true => { format!("%ASA-3-710003: TCP access denied by ACL from {}/{} to outside:10.0.0.1/22", client, port) }
+            false => { format!("%ASA-6-302013: Built inbound TCP connection {} for outside:{}/{} ({}/{}) to inside:10.0.0.5/443 (10.0.0.5/443)", self.serial, client, port, client, port) }
+        };
+
+        format!("{}: {}", t.format("%b %d %Y %H:%M:%S"), message)
+    }
+
+    fn gelf(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let severity = self.severity(error);
+        let level = match severity {
+            0 => { 6 }
+            1 => { 4 }
+            _ => { 3 }
+        };
+
+        serde_json::json!({
+            "version": "1.1",
+            "host": self.rng.pick(&HOSTS),
+            "short_message": self.message(severity),
+            "timestamp": t.timestamp_millis() as f64 / 1000.0,
+            "level": level,
+            "_user": self.rng.pick(&USERS),
+            "_client": self.client(),
+        }).to_string()
+    }
+
+    fn logcat(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let severity = self.severity(error);
+        let level = match severity {
+            0 if self.rng.chance(0.3) => { "D" }
+            0 => { "I" }
+            1 => { "W" }
+            _ => { "E" }
+        };
+
+        let pid = 1000 + self.rng.below(30_000);
+
+        format!(
+            "{} {:>5} {:>5} {} {}: {}",
+            t.format("%m-%d %H:%M:%S%.3f"),
+            pid,
+            pid + self.rng.below(50),
+            level,
+            self.rng.pick(&["ActivityManager", "OkHttp", "ShopApp", "chromium"]),
+            self.message(severity),
+        )
+    }
+
+    fn journald(&mut self, t: DateTime<Local>, error: bool) -> String {
+        let severity = self.severity(error);
+        let priority = match severity {
+            0 => { "6" }
+            1 => { "4" }
+            _ => { "3" }
+        };
+
+        let (unit, identifier) = *self.rng.pick(&[("nginx.service", "nginx"), ("shop.service", "shop"), ("sshd.service", "sshd")]);
+
+        serde_json::json!({
+            "__REALTIME_TIMESTAMP": t.timestamp_micros().to_string(),
+            "_HOSTNAME": self.rng.pick(&HOSTS),
+            "_SYSTEMD_UNIT": unit,
+            "SYSLOG_IDENTIFIER": identifier,
+            "_PID": (1000 + self.rng.below(30_000)).to_string(),
+            "PRIORITY": priority,
+            "MESSAGE": self.message(severity),
+        }).to_string()
+    }
+}
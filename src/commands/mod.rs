@@ -0,0 +1,15 @@
+pub mod bench;
+pub mod capabilities;
+pub mod chain;
+pub mod check;
+pub mod completions;
+pub mod config;
+pub mod detect;
+pub mod explain;
+pub mod fixtures;
+pub mod pipeline;
+pub mod plugins;
+pub mod profile;
+pub mod stats;
+pub mod theme;
+pub mod tmux;
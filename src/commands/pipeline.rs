@@ -0,0 +1,259 @@
+use std::fs;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+use serde::Deserialize;
+use crate::align::Align;
+use crate::dedupe::Dedupe;
+use crate::emphasis::Emphasis;
+use crate::filter::Filter;
+use crate::flow::Flow;
+use crate::lanes::Lanes;
+use crate::origin::Origin;
+use crate::pipeline::Pipeline;
+use crate::plugins::{Fallback, Input, Plugin, PluginRegistry};
+use crate::sample::Sampler;
+use crate::severity::{Severity, Tint};
+use crate::stats::Stats;
+use crate::template::Template;
+use crate::theme;
+use crate::gaps::Pauses;
+use crate::time::{parse_period, TimeFormat};
+use crate::title::Title;
+
+// [[source]]
+// input = "file:/var/log/nginx/access.log"
+// mode = "clf"
+#[derive(Deserialize)]
+struct PipelineFile {
+    #[serde(default, rename = "source")]
+    sources: Vec<SourceSpec>,
+
+    #[serde(default)]
+    transform: Transform,
+
+    #[serde(default)]
+    sinks: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SourceSpec {
+    input: String,
+    mode: Option<String>,
+}
+
+// the same transforms as the command line options of the same names
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Transform {
+    include: Vec<String>,
+    include_i: Vec<String>,
+    invert_match: bool,
+    max_count: Option<u64>,
+    filter: Vec<String>,
+    #[serde(rename = "where")]
+    where_: Vec<String>,
+    min_level: Option<Severity>,
+    since: Option<String>,
+    until: Option<String>,
+    client_net: Vec<String>,
+    not_client_net: Vec<String>,
+    emphasize: Vec<String>,
+    emphasize_i: Vec<String>,
+    highlight: Vec<String>,
+    format: Option<String>,
+    align: bool,
+    timefmt: TimeFormat,
+    mark_gaps: Option<String>,
+    line_numbers: bool,
+    show_source: bool,
+    word: bool,
+    fuzzy: bool,
+    lanes: bool,
+    dedupe: bool,
+    sample: Option<String>,
+    rate_limit: Option<String>,
+    plain: bool,
+    redundant_styles: bool,
+    tint_lines: Option<Tint>,
+    title: bool,
+    pair_start: Option<String>,
+    pair_end: Option<String>,
+    pair_timeout: Option<u64>,
+}
+
+enum Event {
+    Contents(usize, String),
+    Stderr(usize, String),
+    Idle,
+    Failed(usize, String),
+    Done,
+}
+
+// runs on a source's thread, handing what it reads back to the pipeline
+struct Forward {
+    index: usize,
+    tx: Sender<Event>,
+}
+
+impl Input for Forward {
+    fn contents(&mut self, contents: &str) {
+        let _ = self.tx.send(Event::Contents(self.index, contents.to_string()));
+    }
+
+    fn stderr(&mut self, contents: &str) {
+        let _ = self.tx.send(Event::Stderr(self.index, contents.to_string()));
+    }
+
+    fn idle(&mut self) {
+        let _ = self.tx.send(Event::Idle);
+    }
+}
+
+pub fn run(path: &str, registry: &PluginRegistry) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let file: PipelineFile = toml::from_str(&contents).map_err(|e| format!("{}: {}", path, e))?;
+
+    if file.sources.is_empty() {
+        return Err(format!("{}: no [[source]] sections", path));
+    }
+
+    let chains: Vec<Fallback> = file.sources.iter()
+        .map(|s| s.mode.as_deref().unwrap_or("ad-hoc"))
+        .map(|m| registry.chain(m, &m.split(',').map(|m| m.trim().to_string()).collect::<Vec<String>>()).map_err(|e| format!("{}: {}", path, e)))
+        .collect::<Result<_, _>>()?;
+    let plugins: Vec<&dyn Plugin> = chains.iter().map(|c| c.plugin()).collect();
+
+    let mut sources = Vec::new();
+    for spec in &file.sources {
+        sources.push(registry.source(&spec.input)?);
+    }
+
+    // what --show-source calls each input, files by their path
+    let inputs: Vec<String> = file.sources.iter().map(|s| s.input.strip_prefix("file:").unwrap_or(&s.input).to_string()).collect();
+
+    let transform = file.transform;
+
+    let flow = match (&transform.pair_start, &transform.pair_end) {
+        (Some(start), Some(end)) => {
+            Some(Flow::new(start, end, Duration::from_secs(transform.pair_timeout.unwrap_or(30)))?)
+        }
+        (None, None) => { None }
+        _ => { return Err(format!("{}: pair_start and pair_end must be used together", path)) }
+    };
+
+    let sink_specs = match file.sinks.is_empty() {
+        true => { vec!["terminal".to_string()] }
+        false => { file.sinks }
+    };
+
+    let mut sinks = Vec::new();
+    for spec in &sink_specs {
+        sinks.push(registry.sink(spec)?);
+    }
+
+    let mut pipeline = Pipeline {
+        plugin: plugins[0],
+        filter: Filter::new(&transform.include, &transform.include_i, transform.word).map_err(|e| e.to_string())?.with_invert(transform.invert_match).with_fields(&transform.filter)?.with_where(&transform.where_)?.with_min_level(transform.min_level).with_window(transform.since.as_deref(), transform.until.as_deref())?.with_client_nets(&transform.client_net, &transform.not_client_net)?,
+        sampler: match (&transform.sample, &transform.rate_limit) {
+            (Some(spec), _) => { Some(Sampler::sample(spec)?) }
+            (None, Some(spec)) => { Some(Sampler::rate_limit(spec)?) }
+            (None, None) => { None }
+        },
+        remaining: transform.max_count,
+        until_match: None,
+        matched: false,
+        emphasis: Emphasis::new(&transform.emphasize, &transform.emphasize_i, transform.word, transform.fuzzy).with_highlights(&transform.highlight)?,
+        widths: theme::current().widths(&plugins[0].metadata().name),
+        template: transform.format.as_deref().map(Template::parse).transpose()?,
+        origin: None,
+        align: if transform.align { Some(Align::new()) } else { None },
+        fit: None,
+        timefmt: transform.timefmt,
+        lanes: if transform.lanes { Some(Lanes::new()) } else { None },
+        flow,
+        dedupe: if transform.dedupe { Some(Dedupe::new()) } else { None },
+        gaps: None,
+        pauses: transform.mark_gaps.as_deref().map(parse_period).transpose()?.map(Pauses::new),
+        replay: None,
+        watchdog: None,
+        title: None,
+        chain: None,
+        stats: Stats::new(),
+        lines: 0,
+        slo: None,
+        buckets: None,
+        chart: Vec::new(),
+        sinks,
+        plain: transform.plain,
+        redundant_styles: transform.redundant_styles,
+        tint: transform.tint_lines,
+        reload: None,
+    };
+
+    let names: Vec<String> = sources.iter().map(|s| s.metadata().name).collect();
+
+    if transform.title {
+        pipeline.title = Some(Title::new(names.clone()));
+    }
+
+    if transform.line_numbers || transform.show_source {
+        pipeline.origin = Some(Origin::new(transform.line_numbers, transform.show_source, &inputs[0]));
+    }
+
+    let (tx, rx) = mpsc::channel();
+
+    for (index, mut source) in sources.into_iter().enumerate() {
+        let tx = tx.clone();
+
+        thread::spawn(move || {
+            let mut forward = Forward { index, tx: tx.clone() };
+
+            let _ = match source.run(&mut forward) {
+                Ok(()) => { tx.send(Event::Done) }
+                Err(e) => { tx.send(Event::Failed(index, e)) }
+            };
+        });
+    }
+    drop(tx);
+
+    let mut running = names.len();
+    let mut result = Ok(());
+
+    // the sources' threads are left behind once --max-count is reached
+    while running > 0 && !pipeline.done() {
+        match rx.recv() {
+            Ok(Event::Contents(index, contents)) => {
+                pipeline.plugin = plugins[index];
+                if let Some(title) = &mut pipeline.title {
+                    title.source(index);
+                }
+                if let Some(origin) = &mut pipeline.origin {
+                    origin.source(&inputs[index]);
+                }
+                pipeline.print_contents(&contents);
+            }
+            Ok(Event::Stderr(index, contents)) => {
+                pipeline.plugin = plugins[index];
+                if let Some(title) = &mut pipeline.title {
+                    title.source(index);
+                }
+                if let Some(origin) = &mut pipeline.origin {
+                    origin.source(&inputs[index]);
+                }
+                pipeline.stderr(&contents);
+            }
+            Ok(Event::Idle) => { pipeline.tick() }
+            Ok(Event::Done) => { running -= 1 }
+            Ok(Event::Failed(index, e)) => {
+                result = Err(format!("input {}: {}", names[index], e));
+                break;
+            }
+            Err(_) => { break }
+        }
+    }
+
+    pipeline.finish();
+
+    result
+}
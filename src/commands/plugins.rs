@@ -0,0 +1,208 @@
+use std::io::{self, ErrorKind, Write};
+use colored::Colorize;
+use crate::config::{self, Config};
+use crate::plugins::{self, Installed, PluginDiscovery, PluginRegistry};
+
+// what splash can't do without
+const REQUIRED: [&str; 3] = ["ad-hoc", "terminal", "stdin"];
+
+// the modes, built in and discovered, with the fields each one parses
+pub fn modes(registry: &PluginRegistry) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+
+    for plugin in registry.plugins() {
+        closed(writeln!(out, "{:<12} {}", plugin.metadata().name.bold(), plugin.fields().join(" ").dimmed()))?;
+    }
+
+    Ok(())
+}
+
+// every mode, sink and input with its state, version and where it's from
+pub fn list(registry: &PluginRegistry) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+    closed(writeln!(out, "{:<14} {:<6} {:<9} {:<10} {}", "name".bold(), "kind".bold(), "state".bold(), "version".bold(), "from".bold()))?;
+
+    for (kind, name) in registry.entries() {
+        let (version, from) = match registry.discovered(kind, &name) {
+            Some(d) => { (d.version.clone().unwrap_or("-".to_string()), d.path.display().to_string()) }
+            None => { (env!("CARGO_PKG_VERSION").to_string(), "built in".to_string()) }
+        };
+
+        let line = format!("{:<14} {:<6} {:<9} {:<10} {}", name, kind, state(registry, &name), version, from);
+
+        match registry.is_disabled(&name) {
+            true => { closed(writeln!(out, "{}", line.dimmed()))? }
+            false => { closed(writeln!(out, "{}", line))? }
+        }
+    }
+
+    Ok(())
+}
+
+// what a plugin is, for each kind it's installed as
+pub fn info(registry: &PluginRegistry, name: &str) -> Result<(), String> {
+    let kinds: Vec<&str> = registry.entries().into_iter().filter(|(_, n)| n == name).map(|(k, _)| k).collect();
+
+    if kinds.is_empty() {
+        return Err(unknown(registry, name));
+    }
+
+    for (i, kind) in kinds.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        let discovered = registry.discovered(kind, name);
+
+        println!("{:<10} {}", "name".bold(), name);
+        println!("{:<10} {}", "kind".bold(), kind);
+        println!("{:<10} {}", "state".bold(), state(registry, name));
+
+        match discovered {
+            Some(d) => {
+                println!("{:<10} {}", "version".bold(), d.version.as_deref().unwrap_or("-"));
+                println!("{:<10} {}", "author".bold(), d.author.as_deref().unwrap_or("-"));
+                println!("{:<10} {}", "path".bold(), d.path.display());
+            }
+            None => {
+                println!("{:<10} {}", "version".bold(), env!("CARGO_PKG_VERSION"));
+                println!("{:<10} built in", "path".bold());
+            }
+        }
+
+        if let (Some(plugin), "mode") = (registry.get(name), kind) {
+            let styles: Vec<String> = plugin.metadata().styles.iter().map(|(tag, style)| format!("{} = \"{}\"", tag, style)).collect();
+
+            if !plugin.fields().is_empty() {
+                println!("{:<10} {}", "fields".bold(), plugin.fields().join(" "));
+            }
+            if !styles.is_empty() {
+                println!("{:<10} {}", "styles".bold(), styles.join(", "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// turns a plugin on or off in the config file's [plugins] disabled
+pub fn enable(registry: &PluginRegistry, path: Option<&str>, config: &Config, name: &str, on: bool) -> Result<(), String> {
+    if !registry.entries().iter().any(|(_, n)| n == name) {
+        return Err(unknown(registry, name));
+    }
+
+    if !on && REQUIRED.contains(&name) {
+        return Err(format!("{} can't be disabled, splash falls back on it", name));
+    }
+
+    let mut disabled: Vec<String> = config.plugins.disabled.iter().filter(|d| *d != name).cloned().collect();
+
+    if !on {
+        disabled.push(name.to_string());
+    }
+
+    let written = config::save_disabled(path, &disabled)?;
+
+    match on {
+        true => { println!("Enabled {} in {}", name, written.display()) }
+        false => { println!("Disabled {} in {}", name, written.display()) }
+    }
+
+    Ok(())
+}
+
+// puts a plugin file from a path or URL in the user plugin directory
+pub fn install(registry: &PluginRegistry, source: &str) -> Result<(), String> {
+    let installed = plugins::install(registry, source)?;
+
+    println!("Installed {} {}{} in {}", installed.kind, installed.name, version(&installed), installed.path.display());
+    Ok(())
+}
+
+pub fn remove(registry: &PluginRegistry, name: &str) -> Result<(), String> {
+    if !registry.entries().iter().any(|(_, n)| n == name) {
+        return Err(unknown(registry, name));
+    }
+
+    for path in plugins::remove(registry, name)? {
+        println!("Removed {}", path.display());
+    }
+
+    Ok(())
+}
+
+// installs again, from where they came from, the plugin or every plugin
+// that splash plugins install put in place
+pub fn update(registry: &PluginRegistry, name: Option<&str>) -> Result<(), String> {
+    let mut installed: Vec<(String, String)> = plugins::installed().into_iter().collect();
+
+    if let Some(name) = name {
+        if !registry.entries().iter().any(|(_, n)| n == name) {
+            return Err(unknown(registry, name));
+        }
+
+        let files: Vec<String> = registry.entries().into_iter()
+            .filter(|(_, n)| n == name)
+            .filter_map(|(kind, _)| registry.discovered(kind, name)?.path.file_name()?.to_str().map(String::from))
+            .collect();
+
+        installed.retain(|(file, _)| files.contains(file));
+
+        if installed.is_empty() {
+            return Err(format!("{} wasn't installed with splash plugins install, there's nowhere to update it from", name));
+        }
+    }
+
+    if installed.is_empty() {
+        println!("No plugins were installed with splash plugins install");
+    }
+
+    for (_, source) in installed {
+        let updated = plugins::install(registry, &source)?;
+
+        match updated.changed {
+            true => { println!("Updated {} {}{} from {}", updated.kind, updated.name, version(&updated), source) }
+            false => { println!("{} {}{} is up to date", updated.kind, updated.name, version(&updated)) }
+        }
+    }
+
+    Ok(())
+}
+
+fn version(installed: &Installed) -> String {
+    installed.version.as_ref().map(|v| format!(" {}", v)).unwrap_or_default()
+}
+
+// the directories plugins are looked for in, in order
+pub fn path() {
+    for dir in PluginDiscovery::new().dirs() {
+        match dir.is_dir() {
+            true => { println!("{}", dir.display()) }
+            false => { println!("{} {}", dir.display(), "(missing)".dimmed()) }
+        }
+    }
+}
+
+fn state(registry: &PluginRegistry, name: &str) -> &'static str {
+    match registry.is_disabled(name) {
+        true => { "disabled" }
+        false => { "enabled" }
+    }
+}
+
+fn unknown(registry: &PluginRegistry, name: &str) -> String {
+    let mut names: Vec<String> = registry.entries().into_iter().map(|(_, n)| n).collect();
+    names.sort();
+    names.dedup();
+
+    format!("unknown plugin '{}', expected one of {}", name, names.join(", "))
+}
+
+// stdout piped to something that has stopped reading, like head, isn't an
+// error, what's left of the list just goes nowhere
+fn closed(written: io::Result<()>) -> Result<(), String> {
+    match written {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => { Ok(()) }
+        written => { written.map_err(|e| e.to_string()) }
+    }
+}
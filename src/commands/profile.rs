@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fs;
+use colored::Colorize;
+use crate::numbers::count;
+use crate::plugins::{ParseResult, Plugin};
+
+const TOP_VALUES: usize = 10;
+
+// values that mean "no value" in common log formats
+const NULLS: [&str; 3] = ["", "-", "null"];
+
+struct FieldProfile {
+    name: String,
+    present: u64,
+    nulls: u64,
+    values: HashMap<String, u64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    numeric: bool,
+}
+
+impl FieldProfile {
+    fn new(name: &str) -> Self {
+        FieldProfile {
+            name: name.to_string(),
+            present: 0,
+            nulls: 0,
+            values: HashMap::new(),
+            min: None,
+            max: None,
+            numeric: true,
+        }
+    }
+
+    fn add(&mut self, value: &str) {
+        self.present += 1;
+
+        if NULLS.contains(&value) {
+            self.nulls += 1;
+            return;
+        }
+
+        *self.values.entry(value.to_string()).or_insert(0) += 1;
+
+        match value.parse::<f64>() {
+            Ok(n) if self.numeric => {
+                self.min = Some(self.min.map_or(n, |m| m.min(n)));
+                self.max = Some(self.max.map_or(n, |m| m.max(n)));
+            }
+            _ => { self.numeric = false }
+        }
+    }
+}
+
+pub fn run(path: &str, plugin: &dyn Plugin) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut records: u64 = 0;
+    let mut fields: Vec<FieldProfile> = Vec::new();
+
+    for line in contents.lines() {
+        let record = match plugin.parse_line(line) {
+            ParseResult::Parsed(r) if !r.fields.is_empty() => { r }
+            _ => { continue }
+        };
+
+        records += 1;
+
+        for (name, value) in &record.fields {
+            let index = match fields.iter().position(|f| &f.name == name) {
+                Some(i) => { i }
+                None => {
+                    fields.push(FieldProfile::new(name));
+                    fields.len() - 1
+                }
+            };
+
+            fields[index].add(value);
+        }
+    }
+
+    if records == 0 {
+        return Err(format!("{}: no records parsed, is --mode {} right?", path, plugin.metadata().name));
+    }
+
+    println!("{:<14} {}", "records".bold(), count(records));
+
+    for field in &fields {
+        print_field(field, records);
+    }
+
+    Ok(())
+}
+
+fn print_field(field: &FieldProfile, records: u64) {
+    let missing = records - field.present + field.nulls;
+    let rate = format!("{:.2}%", missing as f64 * 100.0 / records as f64);
+
+    println!();
+    println!("{}", field.name.bold().bright_cyan());
+    println!("  {:<12} {}", "distinct", count(field.values.len() as u64));
+    println!("  {:<12} {}", "missing", if missing > 0 { rate.bright_yellow() } else { rate.normal() });
+
+    if let (true, Some(min), Some(max)) = (field.numeric, field.min, field.max) {
+        println!("  {:<12} {}", "min", min.to_string().bright_blue());
+        println!("  {:<12} {}", "max", max.to_string().bright_blue());
+    }
+
+    let mut values: Vec<(&String, &u64)> = field.values.iter().collect();
+    values.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    for (value, n) in values.iter().take(TOP_VALUES) {
+        let share = **n as f64 * 100.0 / records as f64;
+        println!("  {:>10} {:>7} {}", count(**n), format!("{:.1}%", share).dimmed(), value);
+    }
+
+    if values.len() > TOP_VALUES {
+        println!("  {:>10} {}", "", format!("... {} more", count((values.len() - TOP_VALUES) as u64)).dimmed());
+    }
+}
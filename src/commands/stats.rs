@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::fs;
+use chrono::{NaiveTime, Timelike};
+use colored::{ColoredString, Colorize};
+use crate::numbers::{self, count, total};
+use crate::plugins::{ParseResult, Plugin};
+use crate::stats::Stats;
+use crate::time::parse_timestamp;
+
+const TOP_PATHS: usize = 10;
+
+struct Summary {
+    stats: Stats,
+    paths: HashMap<String, u64>,
+}
+
+impl Summary {
+    fn new() -> Self {
+        Summary { stats: Stats::new(), paths: HashMap::new() }
+    }
+}
+
+// a time of day range like 10:00-10:30, which may wrap past midnight
+struct Window {
+    label: String,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Window {
+    fn parse(s: &str) -> Result<Self, String> {
+        let (start, end) = s.split_once('-').ok_or(format!("invalid window '{}', expected HH:MM-HH:MM", s))?;
+
+        Ok(Window { label: s.to_string(), start: parse_time(start)?, end: parse_time(end)? })
+    }
+
+    fn contains(&self, t: NaiveTime) -> bool {
+        match self.start <= self.end {
+            true => { t >= self.start && t < self.end }
+            false => { t >= self.start || t < self.end }
+        }
+    }
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s.trim(), "%H:%M"))
+        .map_err(|_| format!("invalid time '{}', expected HH:MM", s))
+}
+
+pub fn run(path: &str, plugin: &dyn Plugin, compare: &[String]) -> Result<(), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    let windows = compare.iter().map(|w| Window::parse(w)).collect::<Result<Vec<Window>, String>>()?;
+
+    let mut summaries: Vec<Summary> = match windows.is_empty() {
+        true => { vec![Summary::new()] }
+        false => { windows.iter().map(|_| Summary::new()).collect() }
+    };
+
+    for line in contents.lines() {
+        let record = match plugin.parse_line(line) {
+            ParseResult::Parsed(r) => { r }
+            ParseResult::NotMatched | ParseResult::Incomplete => { continue }
+        };
+
+        let time = record.field("datetime").and_then(parse_timestamp).map(|t| t.time().with_nanosecond(0).unwrap());
+
+        for (i, summary) in summaries.iter_mut().enumerate() {
+            if let Some(window) = windows.get(i) {
+                if !time.is_some_and(|t| window.contains(t)) {
+                    continue;
+                }
+            }
+
+            summary.stats.add(&record, None);
+
+            if let Some(request) = record.field("request") {
+                let path = request.split('?').next().unwrap_or(request);
+                *summary.paths.entry(path.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    match windows.len() {
+        0 => { print_summary(&summaries[0]) }
+        _ => { print_comparison(&windows[0], &windows[1], &summaries[0], &summaries[1]) }
+    }
+
+    Ok(())
+}
+
+fn format_latency(ms: Option<f64>) -> String {
+    match ms {
+        Some(ms) => { format!("{:.1}ms", ms) }
+        None => { "-".to_string() }
+    }
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    match rate {
+        Some(r) => { format!("{:.2}%", r) }
+        None => { "-".to_string() }
+    }
+}
+
+fn print_summary(summary: &Summary) {
+    let stats = &summary.stats;
+
+    println!("{:<14} {}", "records".bold(), total(stats.records));
+    println!("{:<14} {}", "requests".bold(), total(stats.requests));
+    println!("{:<14} {}", "errors".bold(), total(stats.errors).bright_red());
+    println!("{:<14} {}", "error rate".bold(), format_rate(stats.error_rate()));
+    println!("{:<14} {}", "bytes".bold(), total(stats.bytes));
+
+    for p in [50.0, 95.0, 99.0] {
+        println!("{:<14} {}", format!("p{} latency", p).bold(), format_latency(stats.latency_percentile(p)).bright_blue());
+    }
+
+    let mut paths: Vec<(&String, &u64)> = summary.paths.iter().collect();
+    paths.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    if !paths.is_empty() {
+        println!();
+        println!("{}", "top paths".bold());
+
+        for (path, count) in paths.iter().take(TOP_PATHS) {
+            println!("{:>10} {}", numbers::count(**count), path.cyan());
+        }
+    }
+}
+
+// red when a metric moved the wrong way, green when it improved
+fn delta(a: f64, b: f64, worse_when_up: bool) -> ColoredString {
+    let text = match a {
+        0.0 if b == 0.0 => { "0.0%".to_string() }
+        0.0 => { "new".to_string() }
+        _ => { format!("{:+.1}%", (b - a) * 100.0 / a) }
+    };
+
+    match (b > a, b < a, worse_when_up) {
+        (true, _, true) | (_, true, false) => { text.bright_red() }
+        (_, true, true) | (true, _, false) => { text.bright_green() }
+        _ => { text.normal() }
+    }
+}
+
+fn print_comparison(window_a: &Window, window_b: &Window, a: &Summary, b: &Summary) {
+    let (sa, sb) = (&a.stats, &b.stats);
+
+    println!("{:<14} {:>14} {:>14} {:>10}", "", window_a.label.bold(), window_b.label.bold(), "delta".bold());
+
+    println!("{:<14} {:>14} {:>14} {:>10}", "requests", count(sa.requests), count(sb.requests), delta(sa.requests as f64, sb.requests as f64, false));
+    println!("{:<14} {:>14} {:>14} {:>10}", "errors", count(sa.errors), count(sb.errors), delta(sa.errors as f64, sb.errors as f64, true));
+
+    let (ra, rb) = (sa.error_rate().unwrap_or(0.0), sb.error_rate().unwrap_or(0.0));
+    let points = format!("{:+.2}pp", rb - ra);
+    let points = match rb > ra {
+        true => { points.bright_red() }
+        false if rb < ra => { points.bright_green() }
+        false => { points.normal() }
+    };
+    println!("{:<14} {:>14} {:>14} {:>10}", "error rate", format_rate(sa.error_rate()), format_rate(sb.error_rate()), points);
+
+    println!("{:<14} {:>14} {:>14} {:>10}", "bytes", count(sa.bytes), count(sb.bytes), delta(sa.bytes as f64, sb.bytes as f64, false));
+
+    for p in [50.0, 95.0, 99.0] {
+        let (la, lb) = (sa.latency_percentile(p), sb.latency_percentile(p));
+        let d = match (la, lb) {
+            (Some(la), Some(lb)) => { delta(la, lb, true) }
+            _ => { "-".normal() }
+        };
+        println!("{:<14} {:>14} {:>14} {:>10}", format!("p{} latency", p), format_latency(la), format_latency(lb), d);
+    }
+
+    let mut changes: Vec<(&String, i64)> = a.paths.keys()
+        .chain(b.paths.keys().filter(|p| !a.paths.contains_key(*p)))
+        .map(|p| (p, *b.paths.get(p).unwrap_or(&0) as i64 - *a.paths.get(p).unwrap_or(&0) as i64))
+        .filter(|(_, d)| *d != 0)
+        .collect();
+    changes.sort_by(|x, y| y.1.cmp(&x.1).then(x.0.cmp(y.0)));
+
+    let gained: Vec<&(&String, i64)> = changes.iter().filter(|(_, d)| *d > 0).take(TOP_PATHS).collect();
+    let lost: Vec<&(&String, i64)> = changes.iter().rev().filter(|(_, d)| *d < 0).take(TOP_PATHS).collect();
+
+    for (title, rows) in [("top paths gained", gained), ("top paths lost", lost)] {
+        if rows.is_empty() {
+            continue;
+        }
+
+        println!();
+        println!("{}", title.bold());
+
+        for (path, d) in rows {
+            let change = format!("{:>10}", format!("{}{}", if *d > 0 { "+" } else { "-" }, count(d.unsigned_abs())));
+            let change = if *d > 0 { change.bright_green() } else { change.bright_red() };
+            println!("{} {}", change, path.cyan());
+        }
+    }
+}
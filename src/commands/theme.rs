@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+use colored::Colorize;
+use regex::Regex;
+use serde_json::Value;
+use crate::config;
+use crate::theme::{self, parse_hex, SLOTS, THEMES};
+
+type Rgb = (u8, u8, u8);
+
+// how base16 schemes are usually laid out on a terminal's 16 colors
+const BASE16_SLOTS: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05",
+    "base03", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+// VS Code's integrated terminal colors
+const VSCODE_SLOTS: [&str; 16] = [
+    "ansiBlack", "ansiRed", "ansiGreen", "ansiYellow", "ansiBlue", "ansiMagenta", "ansiCyan", "ansiWhite",
+    "ansiBrightBlack", "ansiBrightRed", "ansiBrightGreen", "ansiBrightYellow", "ansiBrightBlue", "ansiBrightMagenta", "ansiBrightCyan", "ansiBrightWhite",
+];
+
+static BASE16_KEY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*"?(base0[0-9A-Fa-f])"?\s*:\s*["']?#?([0-9A-Fa-f]{6})"#).unwrap()
+});
+
+static ITERM_COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<key>(Ansi (\d+)|Background) Color</key>\s*<dict>(.*?)</dict>").unwrap()
+});
+
+static ITERM_COMPONENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<key>(Red|Green|Blue) Component</key>\s*<real>([0-9.eE+-]+)</real>").unwrap()
+});
+
+struct Scheme {
+    palette: [Option<Rgb>; 16],
+    background: Option<Rgb>,
+}
+
+// Converts a base16 YAML scheme, an iTerm2 .itermcolors file or a VS Code
+// color theme into a splash theme in the themes directory.
+pub fn import(file: &str, name: Option<&str>) -> Result<(), String> {
+    let contents = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+    let path = Path::new(file);
+
+    let scheme = match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "itermcolors" => { iterm(&contents) }
+        "json" | "jsonc" => { vscode(&contents) }
+        _ if contents.trim_start().starts_with("<?xml") => { iterm(&contents) }
+        _ => { base16(&contents) }
+    }.map_err(|e| format!("{}: {}", file, e))?;
+
+    let name = match name {
+        Some(n) => { n.to_string() }
+        None => { path.file_stem().and_then(|s| s.to_str()).unwrap_or("imported").to_string() }
+    };
+
+    let dir = config::themes_dir().ok_or("can't find the config directory, is $HOME set?")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let target = dir.join(format!("{}.toml", name));
+    fs::write(&target, to_toml(&scheme, file)).map_err(|e| format!("{}: {}", target.display(), e))?;
+
+    println!("Imported {} as theme '{}' ({}), use it with --theme {}", file, name, target.display(), name);
+
+    Ok(())
+}
+
+fn to_toml(scheme: &Scheme, source: &str) -> String {
+    let mut s = format!("# imported from {}\n", source);
+
+    if let Some((r, g, b)) = scheme.background {
+        let luminance = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+        s.push_str(&format!("background = \"{}\"\n", if luminance > 127.5 { "light" } else { "dark" }));
+    }
+
+    s.push('\n');
+
+    for (slot, color) in SLOTS.iter().zip(&scheme.palette) {
+        if let Some((r, g, b)) = color {
+            s.push_str(&format!("{} = \"#{:02x}{:02x}{:02x}\"\n", slot, r, g, b));
+        }
+    }
+
+    s
+}
+
+fn base16(contents: &str) -> Result<Scheme, String> {
+    let colors: Vec<(String, Rgb)> = BASE16_KEY.captures_iter(contents)
+        .filter_map(|c| Some((c[1].to_lowercase(), parse_hex(&c[2]).ok()?)))
+        .collect();
+
+    if colors.is_empty() {
+        return Err("no base00-base0F colors found, expected a base16 scheme".to_string());
+    }
+
+    let color = |key: &str| colors.iter().find(|(k, _)| k == &key.to_lowercase()).map(|(_, c)| *c);
+
+    Ok(Scheme { palette: BASE16_SLOTS.map(color), background: color("base00") })
+}
+
+fn iterm(contents: &str) -> Result<Scheme, String> {
+    let mut scheme = Scheme { palette: [None; 16], background: None };
+
+    for c in ITERM_COLOR.captures_iter(contents) {
+        let mut rgb = [0u8; 3];
+
+        for component in ITERM_COMPONENT.captures_iter(&c[3]) {
+            let value = component[2].parse::<f64>().unwrap_or(0.0);
+            let i = match &component[1] {
+                "Red" => { 0 }
+                "Green" => { 1 }
+                _ => { 2 }
+            };
+            rgb[i] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+
+        let rgb = (rgb[0], rgb[1], rgb[2]);
+        match c.get(2).and_then(|n| n.as_str().parse::<usize>().ok()) {
+            Some(n) if n < 16 => { scheme.palette[n] = Some(rgb) }
+            Some(_) => {}
+            None => { scheme.background = Some(rgb) }
+        }
+    }
+
+    match scheme.palette.iter().any(|c| c.is_some()) {
+        true => { Ok(scheme) }
+        false => { Err("no Ansi colors found, expected an .itermcolors file".to_string()) }
+    }
+}
+
+// VS Code themes are JSON with comments, only the terminal colors are used
+fn vscode(contents: &str) -> Result<Scheme, String> {
+    let json: String = contents.lines()
+        .filter(|l| !l.trim_start().starts_with("//"))
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let theme: Value = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    let colors = &theme["colors"];
+    let color = |key: &str| colors[format!("terminal.{}", key)].as_str().and_then(|c| parse_hex(opaque(c)).ok());
+
+    let scheme = Scheme { palette: VSCODE_SLOTS.map(color), background: color("background") };
+
+    match scheme.palette.iter().any(|c| c.is_some()) {
+        true => { Ok(scheme) }
+        false => { Err("no terminal.ansi* colors found in \"colors\"".to_string()) }
+    }
+}
+
+// a VS Code color can carry an alpha channel, #rrggbbaa, which the terminal
+// has no use for
+fn opaque(color: &str) -> &str {
+    match (color.len(), color.get(..7)) {
+        (9, Some(rgb)) if color.starts_with('#') => { rgb }
+        _ => { color }
+    }
+}
+
+// the built-in themes, those defined in the config file and the imported ones
+pub fn list(defined: &BTreeMap<String, toml::Table>) {
+    let defined: Vec<String> = defined.keys().cloned().collect();
+    let builtin: Vec<String> = THEMES.iter().map(|t| t.to_string()).collect();
+
+    for (heading, names) in [("built-in", builtin), ("config", defined), ("imported", theme::imported())] {
+        if names.is_empty() {
+            continue;
+        }
+
+        println!("{}", heading.bold());
+
+        for name in names {
+            println!("  {}", name);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+use std::process::Command;
+
+// Opens one pane per file, each following it with splash in the given mode.
+// Inside tmux the panes go in a new window, inside WezTerm they split the
+// current tab, and anywhere else a new tmux session is started and attached.
+// tmux panes are titled splash:MODE:PATH so scripts can find them again.
+pub fn split(files: &[String], mode: &str) -> Result<(), String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+
+    let commands: Vec<(String, Vec<String>)> = files.iter().map(|f| {
+        let args = vec![exe.clone(), "-m".to_string(), mode.to_string(), "-p".to_string(), f.clone()];
+        (format!("splash:{}:{}", mode, f), args)
+    }).collect();
+
+    match (env::var_os("TMUX"), env::var_os("WEZTERM_PANE")) {
+        (Some(_), _) => { tmux(&commands, false) }
+        (None, Some(_)) => { wezterm(&commands) }
+        (None, None) => { tmux(&commands, true) }
+    }
+}
+
+fn tmux(commands: &[(String, Vec<String>)], attach: bool) -> Result<(), String> {
+    // relative paths are relative to here, not to wherever tmux was started
+    let dir = env::current_dir().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+
+    let create = match attach {
+        true => { vec!["new-session", "-d"] }
+        false => { vec!["new-window"] }
+    };
+
+    let (title, args) = &commands[0];
+    let ids = run("tmux", &[create, vec!["-c", &dir, "-n", "splash", "-P", "-F", "#{session_id} #{window_id} #{pane_id}", "--"], strs(args)].concat())?;
+
+    let ids: Vec<&str> = ids.split_whitespace().collect();
+    let (session, window, pane) = match ids[..] {
+        [s, w, p] => { (s.to_string(), w.to_string(), p.to_string()) }
+        _ => { return Err(format!("unexpected answer from tmux: {}", ids.join(" "))) }
+    };
+
+    run("tmux", &["select-pane", "-t", &pane, "-T", title])?;
+
+    for (title, args) in &commands[1..] {
+        let pane = run("tmux", &[vec!["split-window", "-t", &window, "-c", &dir, "-P", "-F", "#{pane_id}", "--"], strs(args)].concat())?;
+        run("tmux", &["select-pane", "-t", pane.trim(), "-T", title])?;
+
+        // re-tile after every split so later ones still have room
+        run("tmux", &["select-layout", "-t", &window, "tiled"])?;
+    }
+
+    if attach {
+        let status = Command::new("tmux").args(["attach-session", "-t", &session]).status().map_err(|e| format!("tmux: {}", e))?;
+        if !status.success() {
+            return Err(format!("tmux attach-session exited with {}", status));
+        }
+    }
+
+    Ok(())
+}
+
+fn wezterm(commands: &[(String, Vec<String>)]) -> Result<(), String> {
+    let dir = env::current_dir().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+
+    // WezTerm titles panes itself, from what runs in them
+    for (i, (_, args)) in commands.iter().enumerate() {
+        // alternate directions so the panes end up in a grid
+        let direction = if i % 2 == 0 { "--right" } else { "--bottom" };
+        run("wezterm", &[vec!["cli", "split-pane", direction, "--cwd", &dir, "--"], strs(args)].concat())?;
+    }
+
+    Ok(())
+}
+
+fn strs(args: &[String]) -> Vec<&str> {
+    args.iter().map(|a| a.as_str()).collect()
+}
+
+// runs a multiplexer command and returns what it printed
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program).args(args).output().map_err(|e| format!("{}: {}", program, e))?;
+
+    match output.status.success() {
+        true => { Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()) }
+        false => { Err(format!("{} {}: {}", program, args[0], String::from_utf8_lossy(&output.stderr).trim())) }
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::severity::Severity;
+use crate::slo::Slo;
+use crate::theme::{Background, Vision};
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct Config {
+    // when neither --mode nor [modes] picks one
+    pub mode: Option<String>,
+
+    // modes by file name, like "*.access.log" = "clf"; a pattern with a slash
+    // is matched against the whole path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub modes: BTreeMap<String, String>,
+
+    // named mode chains, like web = ["clf", "ad-hoc"]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub chains: BTreeMap<String, Vec<String>>,
+
+    // seconds between polls of an http(s):// path, or of followed files and
+    // spool folders where changes can't be watched for
+    pub poll_interval: Option<f64>,
+
+    // patterns painted in a style of their own, like --highlight
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub matchers: BTreeMap<String, Matcher>,
+
+    pub slo: Option<Slo>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<String>,
+
+    pub background: Option<Background>,
+
+    pub theme: Option<String>,
+
+    // colors for a kind of color blindness, under the theme
+    pub palette: Option<Vision>,
+
+    // themes defined in place, in the format of an imported theme file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, toml::Table>,
+
+    #[serde(default)]
+    pub redundant_styles: bool,
+
+    // most characters of a field to show, by "field" or "plugin.field"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub widths: BTreeMap<String, usize>,
+
+    // the ad-hoc mode's matchers: order = ["ip_addr", "number"] tries those
+    // first, disabled = ["number"] turns them off
+    #[serde(default, skip_serializing_if = "AdHoc::is_empty")]
+    pub adhoc: AdHoc,
+
+    // styles for the clf mode's fields and status classes, like
+    // status_5xx = "bold red"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub clf: BTreeMap<String, String>,
+
+    // line_numbers and show_source, for every mode or as "plugin.line_numbers"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prefix: BTreeMap<String, bool>,
+
+    // named sets of options picked with --profile, as [profile.NAME]
+    #[serde(default, rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+
+    // options for a mode, as [plugin.NAME]
+    #[serde(default, rename = "plugin", skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, toml::Table>,
+
+    // plugins turned off with splash plugins disable
+    #[serde(default, skip_serializing_if = "Plugins::is_empty")]
+    pub plugins: Plugins,
+}
+
+// What --profile NAME stands for: where to read, how to parse and what to
+// show. Options given on the command line win, and lists are added to.
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct Profile {
+    pub path: Option<String>,
+    pub input: Option<String>,
+    pub mode: Option<String>,
+    pub theme: Option<String>,
+    pub min_level: Option<Severity>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter: Vec<String>,
+
+    #[serde(default, rename = "where", skip_serializing_if = "Vec::is_empty")]
+    pub where_: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub emphasize: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlight: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct AdHoc {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+}
+
+impl AdHoc {
+    fn is_empty(&self) -> bool {
+        self.order.is_empty() && self.disabled.is_empty()
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct Plugins {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+}
+
+impl Plugins {
+    fn is_empty(&self) -> bool {
+        self.disabled.is_empty()
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Matcher {
+    pub pattern: String,
+    pub style: String,
+}
+
+impl Config {
+    pub fn profile(&self, name: &str) -> Result<&Profile, String> {
+        self.profiles.get(name).ok_or_else(|| {
+            match self.profiles.is_empty() {
+                true => { format!("unknown profile '{}', the config file has no [profile.NAME] sections", name) }
+                false => { format!("unknown profile '{}', expected one of {}", name, self.profiles.keys().cloned().collect::<Vec<String>>().join(", ")) }
+            }
+        })
+    }
+
+    // the modes a line is offered to in turn: a [chains] entry, or the ones
+    // in a list like "clf,ad-hoc"
+    pub fn chain(&self, mode: &str) -> Vec<String> {
+        match self.chains.get(mode) {
+            Some(modes) => { modes.clone() }
+            None => { mode.split(',').map(|m| m.trim().to_string()).collect() }
+        }
+    }
+
+    // the longest [modes] pattern matching the path, as the most specific
+    pub fn mode_for(&self, path: &str) -> Option<&str> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+
+        self.modes.iter()
+            .filter(|(pattern, _)| wildcard(pattern, if pattern.contains('/') { path } else { name }))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, mode)| mode.as_str())
+    }
+}
+
+// * for any run of characters, ? for any one
+fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                match star {
+                    Some((sp, st)) => {
+                        p = sp + 1;
+                        t = st + 1;
+                        star = Some((sp, st + 1));
+                    }
+                    None => { return false }
+                }
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    // an empty XDG_CONFIG_HOME is ignored, as the spec says
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => { PathBuf::from(dir) }
+        None => { PathBuf::from(env::var_os("HOME")?).join(".config") }
+    };
+
+    Some(base.join("splash"))
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+// imported themes, one TOML file per theme
+pub fn themes_dir() -> Option<PathBuf> {
+    Some(config_dir()?.join("themes"))
+}
+
+// an explicit path must exist, the default location is optional
+pub fn load(path: Option<&str>) -> Result<Config, String> {
+    let path = match path {
+        Some(p) => { PathBuf::from(p) }
+        None => {
+            match default_path() {
+                Some(p) if p.exists() => { p }
+                _ => { return Ok(Config::default()) }
+            }
+        }
+    };
+
+    let contents = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    toml::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Writes [plugins] disabled into the config file, creating it if need be,
+// and leaves the rest of the file as it was, comments included.
+pub fn save_disabled(path: Option<&str>, disabled: &[String]) -> Result<PathBuf, String> {
+    let path = match path {
+        Some(p) => { PathBuf::from(p) }
+        None => { default_path().ok_or("no home directory for the config file, pass --config".to_string())? }
+    };
+
+    let contents = match path.exists() {
+        true => { fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))? }
+        false => { "".to_string() }
+    };
+
+    let mut document = contents.parse::<toml_edit::DocumentMut>().map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    match disabled.is_empty() {
+        true => {
+            if let Some(plugins) = document.get_mut("plugins").and_then(|p| p.as_table_like_mut()) {
+                plugins.remove("disabled");
+
+                if plugins.is_empty() {
+                    // what came before the table stays, comments included
+                    let prefix = document["plugins"].as_table().and_then(|t| t.decor().prefix()).and_then(|p| p.as_str()).unwrap_or_default().to_string();
+                    let trailing = document.trailing().as_str().unwrap_or_default().to_string();
+
+                    document.remove("plugins");
+                    match prefix.trim().is_empty() {
+                        true => { document.set_trailing(trailing) }
+                        false => { document.set_trailing(format!("{}{}", prefix, trailing)) }
+                    }
+                }
+            }
+        }
+        false => {
+            // a new table goes at the end, after the comments closing the file
+            if document.get("plugins").is_none() {
+                let mut table = toml_edit::Table::new();
+                let trailing = document.trailing().as_str().unwrap_or_default().to_string();
+
+                if !trailing.is_empty() {
+                    table.decor_mut().set_prefix(format!("{}\n", trailing.trim_end()));
+                }
+
+                document.set_trailing("");
+                document.insert("plugins", toml_edit::Item::Table(table));
+            }
+
+            let names: toml_edit::Array = disabled.iter().map(String::as_str).collect();
+            document["plugins"]["disabled"] = toml_edit::value(names);
+        }
+    }
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+
+    fs::write(&path, document.to_string()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
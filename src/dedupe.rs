@@ -0,0 +1,60 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::plugins::Record;
+use crate::render::strip_ansi;
+
+// dates and times that tell otherwise identical lines apart
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?|\b\d{1,2}:\d{2}:\d{2}(?:[.,]\d+)?\b").unwrap()
+});
+
+// Holds back each record until the next one shows whether it repeats, so a
+// run of identical lines, ignoring their timestamps, comes out as its first
+// line and a count.
+pub struct Dedupe {
+    pending: Option<(String, Record, String)>,
+    repeats: usize,
+}
+
+impl Default for Dedupe {
+    fn default() -> Self {
+        Dedupe::new()
+    }
+}
+
+impl Dedupe {
+    pub fn new() -> Self {
+        Dedupe { pending: None, repeats: 0 }
+    }
+
+    // the previous run, once this record ends it
+    pub fn add(&mut self, text: String, record: Record) -> Option<(String, Record, usize)> {
+        let key = key(&record);
+
+        if self.pending.as_ref().is_some_and(|(_, _, k)| *k == key) {
+            self.repeats += 1;
+            return None;
+        }
+
+        let previous = self.take();
+        self.pending = Some((text, record, key));
+        self.repeats = 1;
+
+        previous
+    }
+
+    // the run so far, with how many lines it stands for
+    pub fn take(&mut self) -> Option<(String, Record, usize)> {
+        self.pending.take().map(|(text, record, _)| (text, record, self.repeats))
+    }
+}
+
+fn key(record: &Record) -> String {
+    let mut text = strip_ansi(&record.text);
+
+    if let Some(datetime) = record.field("datetime") {
+        text = text.replacen(datetime, "", 1);
+    }
+
+    TIMESTAMP.replace_all(&text, "").to_string()
+}
@@ -0,0 +1,67 @@
+use std::io::BufRead;
+use clap::ValueEnum;
+use crate::theme::Background;
+
+const PREFIX: &str = "#splash:";
+
+// Settings a program can put on the first line of what it pipes to splash:
+//
+//     #splash: mode=logfmt theme=dark
+//
+// The line itself is never shown. Options given on the command line win over
+// the directive. theme=dark and theme=light pick a background rather than a
+// theme, the same as background=dark.
+#[derive(Default)]
+pub struct Directive {
+    pub mode: Option<String>,
+    pub theme: Option<String>,
+    pub background: Option<Background>,
+}
+
+impl Directive {
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let settings = line.trim().strip_prefix(PREFIX)?;
+
+        Some(Directive::settings(settings).map_err(|e| format!("invalid directive '{}': {}", line.trim(), e)))
+    }
+
+    fn settings(settings: &str) -> Result<Self, String> {
+        let mut directive = Directive::default();
+
+        for setting in settings.split_whitespace() {
+            let (key, value) = setting.split_once('=').ok_or(format!("expected KEY=VALUE but found '{}'", setting))?;
+
+            let background = || Background::from_str(value, true).map_err(|_| format!("unknown background '{}'", value));
+
+            match key {
+                "mode" => { directive.mode = Some(value.to_string()) }
+                "theme" if value == "dark" || value == "light" => { directive.background = Some(background()?) }
+                "theme" => { directive.theme = Some(value.to_string()) }
+                "background" => { directive.background = Some(background()?) }
+                _ => { return Err(format!("unknown setting '{}'", key)) }
+            }
+        }
+
+        Ok(directive)
+    }
+}
+
+// Reads the first line of stdin. A directive is returned on its own; any other
+// line comes back to be shown ahead of the rest of the stream. Stdin is
+// buffered by the standard library, so the stdin input carries on from the
+// second line.
+pub fn read_stdin() -> Result<(Option<Directive>, Option<String>), String> {
+    let mut line: Vec<u8> = Vec::new();
+
+    if std::io::stdin().lock().read_until(b'\n', &mut line).map_err(|e| e.to_string())? == 0 {
+        return Ok((None, None));
+    }
+
+    let text = String::from_utf8_lossy(&line);
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+
+    match Directive::parse(text) {
+        Some(directive) => { Ok((Some(directive?), None)) }
+        None => { Ok((None, Some(text.to_string()))) }
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use colored::control::SHOULD_COLORIZE;
+use regex::Regex;
+use crate::config::Matcher;
+use crate::filter::build_regex;
+use crate::render::{merge_ranges, overlay, strip_ansi};
+use crate::theme::{paint, parse_style};
+
+const INVERSE_ON: &str = "\x1b[7m";
+const INVERSE_OFF: &str = "\x1b[27m";
+
+const RESET: &str = "\x1b[0m";
+
+// --highlight patterns without a style of their own
+const HIGHLIGHT: &str = "black on_yellow";
+
+// the escape sequence that starts a style
+fn style_on(style: &str) -> String {
+    paint("\0", style).split('\0').next().unwrap_or_default().to_string()
+}
+
+struct FuzzyTerm {
+    chars: Vec<char>,
+    ignore_case: bool,
+}
+
+// a --highlight pattern and the escape sequence that starts its style
+struct Highlight {
+    pattern: Regex,
+    on: String,
+}
+
+pub struct Emphasis {
+    terms: Vec<Regex>,
+    fuzzy: Vec<FuzzyTerm>,
+    highlights: Vec<Highlight>,
+    word: bool,
+}
+
+impl Emphasis {
+    pub fn new(terms: &[String], terms_i: &[String], word: bool, fuzzy: bool) -> Self {
+        let all = terms.iter().map(|t| (t, false)).chain(terms_i.iter().map(|t| (t, true)));
+
+        let mut emphasis = Emphasis { terms: Vec::new(), fuzzy: Vec::new(), highlights: Vec::new(), word };
+
+        for (term, ignore_case) in all {
+            if fuzzy {
+                emphasis.fuzzy.push(FuzzyTerm { chars: term.chars().collect(), ignore_case });
+            } else {
+                emphasis.terms.push(build_regex(&regex::escape(term), ignore_case, word).unwrap());
+            }
+        }
+
+        emphasis
+    }
+
+    // --highlight REGEX[:STYLE], painted over whatever the mode colored; a
+    // suffix that isn't a style is part of the pattern
+    pub fn with_highlights(mut self, specs: &[String]) -> Result<Self, String> {
+        for spec in specs {
+            let (pattern, style) = match spec.rsplit_once(':') {
+                Some((p, style)) if !style.trim().is_empty() && parse_style(style).is_ok() => { (p, style) }
+                _ => { (spec.as_str(), HIGHLIGHT) }
+            };
+
+            let pattern = Regex::new(pattern).map_err(|e| format!("invalid highlight '{}': {}", spec, e))?;
+            self.highlights.push(Highlight { pattern, on: style_on(style) });
+        }
+
+        Ok(self)
+    }
+
+    // the config file's [matchers], painted the same way as --highlight
+    pub fn with_matchers(mut self, matchers: &BTreeMap<String, Matcher>) -> Result<Self, String> {
+        for (name, matcher) in matchers {
+            parse_style(&matcher.style).map_err(|e| format!("matcher '{}': {}", name, e))?;
+            let pattern = Regex::new(&matcher.pattern).map_err(|e| format!("matcher '{}': {}", name, e))?;
+
+            self.highlights.push(Highlight { pattern, on: style_on(&matcher.style) });
+        }
+
+        Ok(self)
+    }
+
+    pub fn apply(&self, s: &str) -> String {
+        if (self.terms.is_empty() && self.fuzzy.is_empty() && self.highlights.is_empty()) || !SHOULD_COLORIZE.should_colorize() {
+            return s.to_string();
+        }
+
+        let mut s = s.to_string();
+
+        for highlight in &self.highlights {
+            let plain = strip_ansi(&s);
+            let ranges: Vec<(usize, usize)> = highlight.pattern.find_iter(&plain).map(|m| (m.start(), m.end())).filter(|(a, b)| a < b).collect();
+
+            s = overlay(&s, &merge_ranges(ranges), &highlight.on, RESET);
+        }
+
+        let plain = strip_ansi(&s);
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+        for re in &self.terms {
+            for m in re.find_iter(&plain) {
+                ranges.push((m.start(), m.end()));
+            }
+        }
+
+        for term in &self.fuzzy {
+            ranges.extend(self.fuzzy_ranges(&plain, term));
+        }
+
+        overlay(&s, &merge_ranges(ranges), INVERSE_ON, INVERSE_OFF)
+    }
+
+    // substrings within an edit distance of 1 from the term, longest first
+    fn fuzzy_ranges(&self, plain: &str, term: &FuzzyTerm) -> Vec<(usize, usize)> {
+        let chars: Vec<(usize, char)> = plain.char_indices().collect();
+        let n = term.chars.len();
+        let mut ranges = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let mut found = None;
+
+            for len in [n + 1, n, n.saturating_sub(1)] {
+                if len == 0 || i + len > chars.len() {
+                    continue;
+                }
+
+                let candidate: Vec<char> = chars[i..i + len].iter().map(|(_, c)| *c).collect();
+                if is_padded(&candidate, &term.chars) {
+                    continue;
+                }
+                if !within_one_edit(&candidate, &term.chars, term.ignore_case) {
+                    continue;
+                }
+
+                let start = chars[i].0;
+                let end = chars.get(i + len).map(|(b, _)| *b).unwrap_or(plain.len());
+                if self.word && !is_word_bounded(plain, start, end) {
+                    continue;
+                }
+
+                found = Some((start, end, len));
+                break;
+            }
+
+            match found {
+                Some((start, end, len)) => {
+                    ranges.push((start, end));
+                    i += len;
+                }
+                None => { i += 1 }
+            }
+        }
+
+        ranges
+    }
+}
+
+fn chars_eq(a: char, b: char, ignore_case: bool) -> bool {
+    match ignore_case {
+        true => { a.to_lowercase().eq(b.to_lowercase()) }
+        false => { a == b }
+    }
+}
+
+fn within_one_edit(a: &[char], b: &[char], ignore_case: bool) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    let prefix = short.iter().zip(long).take_while(|(x, y)| chars_eq(**x, **y, ignore_case)).count();
+
+    if prefix == short.len() {
+        return true;
+    }
+
+    let skip = if short.len() == long.len() { 1 } else { 0 };
+
+    short[prefix + skip..]
+        .iter()
+        .zip(&long[prefix + 1..])
+        .all(|(x, y)| chars_eq(*x, *y, ignore_case))
+}
+
+// don't let an inserted character be surrounding whitespace
+fn is_padded(candidate: &[char], term: &[char]) -> bool {
+    let first = candidate[0].is_whitespace() && !term.first().is_some_and(|c| c.is_whitespace());
+    let last = candidate[candidate.len() - 1].is_whitespace() && !term.last().is_some_and(|c| c.is_whitespace());
+
+    first || last
+}
+
+fn is_word_bounded(plain: &str, start: usize, end: usize) -> bool {
+    let before = plain[..start].chars().next_back();
+    let after = plain[end..].chars().next();
+
+    !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
@@ -0,0 +1,216 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+use chrono::{DateTime, FixedOffset};
+use regex::{Regex, RegexBuilder};
+use crate::plugins::Record;
+use crate::query::Query;
+use crate::severity::{self, Severity};
+use crate::time::{parse_bound, parse_timestamp};
+
+// status=500, method!=GET, client~=^10\. or path!~=^/health
+static FIELD_FILTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([\w.\-]+)(!~=|~=|!=|=)(.*)$").unwrap()
+});
+
+pub fn build_regex(pattern: &str, ignore_case: bool, word: bool) -> Result<Regex, regex::Error> {
+    let pattern = match word {
+        true => { format!(r"\b(?:{})\b", pattern) }
+        false => { pattern.to_string() }
+    };
+
+    RegexBuilder::new(&pattern).case_insensitive(ignore_case).build()
+}
+
+pub struct Filter {
+    include: Vec<Regex>,
+    invert: bool,
+    fields: Vec<FieldFilter>,
+    queries: Vec<Query>,
+    min_level: Option<Severity>,
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+    client_nets: Vec<Network>,
+    not_client_nets: Vec<Network>,
+}
+
+// an address block like 10.0.0.0/8 or 2001:db8::/32, or a single address
+struct Network {
+    addr: IpAddr,
+    prefix: u32,
+}
+
+enum Test {
+    Equals(String),
+    NotEquals(String),
+    Matches(Regex),
+    NotMatches(Regex),
+}
+
+// a test on one of a record's fields; a record without the field only
+// passes the negative tests
+struct FieldFilter {
+    field: String,
+    test: Test,
+}
+
+impl FieldFilter {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let caps = FIELD_FILTER.captures(spec)
+            .ok_or(format!("invalid filter '{}', expected FIELD=VALUE, FIELD!=VALUE, FIELD~=REGEX or FIELD!~=REGEX", spec))?;
+
+        let regex = |p: &str| Regex::new(p).map_err(|e| format!("invalid filter '{}': {}", spec, e));
+
+        let test = match &caps[2] {
+            "=" => { Test::Equals(caps[3].to_string()) }
+            "!=" => { Test::NotEquals(caps[3].to_string()) }
+            "~=" => { Test::Matches(regex(&caps[3])?) }
+            _ => { Test::NotMatches(regex(&caps[3])?) }
+        };
+
+        Ok(FieldFilter { field: caps[1].to_string(), test })
+    }
+
+    fn matches(&self, record: &Record) -> bool {
+        let value = record.field(&self.field);
+
+        match &self.test {
+            Test::Equals(v) => { value == Some(v.as_str()) }
+            Test::NotEquals(v) => { value != Some(v.as_str()) }
+            Test::Matches(re) => { value.is_some_and(|v| re.is_match(v)) }
+            Test::NotMatches(re) => { !value.is_some_and(|v| re.is_match(v)) }
+        }
+    }
+}
+
+impl Network {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid network '{}', expected an address or CIDR block like 10.0.0.0/8", spec);
+
+        let (addr, prefix) = match spec.split_once('/') {
+            Some((a, p)) => { (a, Some(p.parse::<u32>().map_err(|_| invalid())?)) }
+            None => { (spec, None) }
+        };
+
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let bits = if addr.is_ipv4() { 32 } else { 128 };
+
+        match prefix {
+            Some(p) if p > bits => { Err(invalid()) }
+            Some(p) => { Ok(Network { addr, prefix: p }) }
+            None => { Ok(Network { addr, prefix: bits }) }
+        }
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => { false }
+        }
+    }
+}
+
+// the client address of a request, which some servers log with its port
+fn client_ip(record: &Record) -> Option<IpAddr> {
+    let client = record.field("client")?;
+
+    client.parse::<IpAddr>().ok().or(client.parse::<SocketAddr>().ok().map(|a| a.ip()))
+}
+
+impl Filter {
+    pub fn new(include: &[String], include_i: &[String], word: bool) -> Result<Self, regex::Error> {
+        let mut patterns = Vec::new();
+
+        for p in include {
+            patterns.push(build_regex(p, false, word)?);
+        }
+        for p in include_i {
+            patterns.push(build_regex(p, true, word)?);
+        }
+
+        Ok(Filter { include: patterns, invert: false, fields: Vec::new(), queries: Vec::new(), min_level: None, since: None, until: None, client_nets: Vec::new(), not_client_nets: Vec::new() })
+    }
+
+    // --invert-match shows the lines no --include pattern matches
+    pub fn with_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    // --filter tests on parsed fields, which must all pass
+    pub fn with_fields(mut self, specs: &[String]) -> Result<Self, String> {
+        for spec in specs {
+            self.fields.push(FieldFilter::parse(spec)?);
+        }
+
+        Ok(self)
+    }
+
+    // --where expressions, which must all hold
+    pub fn with_where(mut self, expressions: &[String]) -> Result<Self, String> {
+        for e in expressions {
+            self.queries.push(Query::parse(e)?);
+        }
+
+        Ok(self)
+    }
+
+    // --min-level drops records below a severity, records without one are kept
+    pub fn with_min_level(mut self, level: Option<Severity>) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    // --since and --until drop records timestamped outside the window,
+    // records without a timestamp are kept
+    pub fn with_window(mut self, since: Option<&str>, until: Option<&str>) -> Result<Self, String> {
+        self.since = since.map(parse_bound).transpose()?;
+        self.until = until.map(parse_bound).transpose()?;
+
+        Ok(self)
+    }
+
+    // --client-net keeps requests from any of the networks, --not-client-net
+    // drops those from any of them; clients that aren't addresses are in none
+    pub fn with_client_nets(mut self, include: &[String], exclude: &[String]) -> Result<Self, String> {
+        self.client_nets = include.iter().map(|n| Network::parse(n)).collect::<Result<_, _>>()?;
+        self.not_client_nets = exclude.iter().map(|n| Network::parse(n)).collect::<Result<_, _>>()?;
+
+        Ok(self)
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        self.include.is_empty() || self.include.iter().any(|re| re.is_match(line)) != self.invert
+    }
+
+    pub fn matches_record(&self, record: &Record) -> bool {
+        let level = match (self.min_level, severity::of(record)) {
+            (Some(min), Some(severity)) => { severity >= min }
+            _ => { true }
+        };
+
+        let window = match record.field("datetime").and_then(parse_timestamp) {
+            Some(t) => { self.since.is_none_or(|s| t >= s) && self.until.is_none_or(|u| t < u) }
+            None => { true }
+        };
+
+        let client = match (self.client_nets.is_empty() && self.not_client_nets.is_empty(), client_ip(record)) {
+            (true, _) => { true }
+            (false, Some(ip)) => {
+                (self.client_nets.is_empty() || self.client_nets.iter().any(|n| n.contains(ip)))
+                    && !self.not_client_nets.iter().any(|n| n.contains(ip))
+            }
+            (false, None) => { self.client_nets.is_empty() }
+        };
+
+        level && window && client
+            && self.fields.iter().all(|f| f.matches(record))
+            && self.queries.iter().all(|q| q.matches(record))
+    }
+}
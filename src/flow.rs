@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, FixedOffset};
+use colored::Colorize;
+use regex::{Captures, Regex};
+use crate::plugins::Record;
+use crate::time::{format_duration, parse_timestamp};
+
+struct Start {
+    line: String,
+    seen: Instant,
+    logged: Option<DateTime<FixedOffset>>,
+}
+
+// Pairs request start and completion lines by the `id` capture group of two
+// user supplied patterns. An optional `time` group (or the record's datetime
+// field) lets durations come from the log itself instead of the wall clock.
+pub struct Flow {
+    start: Regex,
+    end: Regex,
+    timeout: Duration,
+    pending: HashMap<String, Start>,
+    latest: Option<DateTime<FixedOffset>>,
+}
+
+impl Flow {
+    pub fn new(start: &str, end: &str, timeout: Duration) -> Result<Self, String> {
+        let start = Regex::new(start).map_err(|e| e.to_string())?;
+        let end = Regex::new(end).map_err(|e| e.to_string())?;
+
+        for re in [&start, &end] {
+            if !re.capture_names().any(|n| n == Some("id")) {
+                return Err(format!("pattern '{}' has no (?P<id>...) group", re.as_str()));
+            }
+        }
+
+        Ok(Flow { start, end, timeout, pending: HashMap::new(), latest: None })
+    }
+
+    // returns how long the request took for completion lines
+    pub fn observe(&mut self, line: &str, record: &Record) -> Option<Duration> {
+        if let Some(caps) = self.end.captures(line) {
+            let logged = self.logged_at(&caps, record);
+            let start = self.pending.remove(&caps["id"])?;
+
+            return match (start.logged, logged) {
+                (Some(a), Some(b)) if b >= a => { (b - a).to_std().ok() }
+                _ => { Some(start.seen.elapsed()) }
+            };
+        }
+
+        if let Some(caps) = self.start.captures(line) {
+            let logged = self.logged_at(&caps, record);
+            self.pending.insert(caps["id"].to_string(), Start { line: line.to_string(), seen: Instant::now(), logged });
+        }
+
+        None
+    }
+
+    // warnings for starts that have gone unanswered for longer than the timeout
+    pub fn expire(&mut self) -> Vec<String> {
+        let expired: Vec<String> = self.pending
+            .iter()
+            .filter(|(_, start)| self.waited(start) > self.timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        expired.iter().map(|id| {
+            let start = self.pending.remove(id).unwrap();
+            hang(id, &format!("no completion after {}", format_duration(self.timeout)), &start)
+        }).collect()
+    }
+
+    // warnings for every start still waiting at the end of the input
+    pub fn finish(&mut self) -> Vec<String> {
+        let mut pending: Vec<(String, Start)> = self.pending.drain().collect();
+        pending.sort_by_key(|(_, start)| start.seen);
+
+        pending
+            .iter()
+            .map(|(id, start)| hang(id, "no completion before end of input", start))
+            .collect()
+    }
+
+    fn logged_at(&mut self, caps: &Captures, record: &Record) -> Option<DateTime<FixedOffset>> {
+        let text = caps.name("time").map(|m| m.as_str()).or(record.field("datetime"))?;
+        let t = parse_timestamp(text)?;
+
+        if self.latest.is_none_or(|latest| t > latest) {
+            self.latest = Some(t);
+        }
+
+        Some(t)
+    }
+
+    fn waited(&self, start: &Start) -> Duration {
+        let logged = match (start.logged, self.latest) {
+            (Some(a), Some(b)) if b >= a => { (b - a).to_std().unwrap_or_default() }
+            _ => { Duration::ZERO }
+        };
+
+        logged.max(start.seen.elapsed())
+    }
+}
+
+fn hang(id: &str, reason: &str, start: &Start) -> String {
+    format!(
+        "{} {} {}",
+        format!("probable hang: {} ({})", id, reason).bright_red().bold(),
+        "<-".dimmed(),
+        start.line.dimmed(),
+    )
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+use chrono::{DateTime, FixedOffset};
+use colored::Colorize;
+use crate::plugins::Record;
+use crate::terminal;
+use crate::time::{format_duration, parse_timestamp};
+
+// Watches a field that should only ever go up, a sequence number or a
+// timestamp, and describes lines that were skipped or arrived out of order.
+pub struct Gaps {
+    field: String,
+    sequence: Option<u64>,
+    time: Option<(DateTime<FixedOffset>, String)>,
+}
+
+impl Gaps {
+    pub fn new(field: &str) -> Self {
+        Gaps { field: field.to_string(), sequence: None, time: None }
+    }
+
+    // a warning to show before the record, if it broke the order
+    pub fn observe(&mut self, record: &Record) -> Option<String> {
+        let value = record.field(&self.field)?;
+
+        if let Ok(n) = value.trim().parse::<u64>() {
+            let previous = self.sequence.replace(n)?;
+
+            return match n {
+                _ if previous.checked_add(1) == Some(n) => { None }
+                _ if n > previous => {
+                    let missing = n - previous - 1;
+                    let range = match missing {
+                        1 => { format!("{}", previous + 1) }
+                        _ => { format!("{}-{}", previous + 1, n - 1) }
+                    };
+                    Some(warning(&format!("gap: {} {} missing ({} {})", missing, lines(missing), self.field, range)))
+                }
+                _ if n == previous => { Some(warning(&format!("gap: {} {} repeated", self.field, n))) }
+                _ => { Some(warning(&format!("gap: {} went back from {} to {}", self.field, previous, n))) }
+            };
+        }
+
+        let t = parse_timestamp(value)?;
+        let (previous, logged) = self.time.replace((t, value.to_string()))?;
+
+        match t < previous {
+            true => {
+                let jump = (previous - t).to_std().unwrap_or_default();
+                Some(warning(&format!("gap: clock went back {} ({} after {})", format_duration(jump), value, logged)))
+            }
+            false => { None }
+        }
+    }
+}
+
+// Marks quiet periods for --mark-gaps with a rule saying how long the log was
+// silent, wherever consecutive timestamps are further apart than a limit.
+pub struct Pauses {
+    limit: Duration,
+    last: Option<DateTime<FixedOffset>>,
+}
+
+impl Pauses {
+    pub fn new(limit: Duration) -> Self {
+        Pauses { limit, last: None }
+    }
+
+    // a rule to show before the record, if it came after a pause
+    pub fn observe(&mut self, record: &Record) -> Option<String> {
+        let t = parse_timestamp(record.field("datetime")?)?;
+        let previous = self.last.replace(t)?;
+
+        let pause = (t - previous).to_std().ok().filter(|p| *p > self.limit)?;
+        let label = format!(" {} later ", format_duration(pause));
+        let width = terminal::width().unwrap_or(80);
+
+        Some(format!("──{}{}", label, "─".repeat(width.saturating_sub(label.chars().count() + 2))))
+    }
+}
+
+fn lines(n: u64) -> &'static str {
+    match n {
+        1 => { "line" }
+        _ => { "lines" }
+    }
+}
+
+fn warning(text: &str) -> String {
+    text.bright_red().bold().to_string()
+}
@@ -0,0 +1,45 @@
+use colored::{Color, Colorize};
+use crate::plugins::Record;
+
+const MAX_LANES: usize = 8;
+
+const PALETTE: [Color; 8] = [
+    Color::BrightBlue,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::Blue,
+    Color::Green,
+    Color::Magenta,
+];
+
+// Gives each thread (or PID) a lane in order of first appearance, drawn as a
+// colored gutter marker indented by the lane number.
+#[derive(Default)]
+pub struct Lanes {
+    threads: Vec<String>,
+}
+
+impl Lanes {
+    pub fn new() -> Self {
+        Lanes::default()
+    }
+
+    pub fn prefix(&mut self, record: &Record) -> String {
+        let thread = match record.field("thread").or(record.field("pid")) {
+            Some(t) => { t }
+            None => { return "".to_string() }
+        };
+
+        let lane = match self.threads.iter().position(|t| t == thread) {
+            Some(i) => { i }
+            None => {
+                self.threads.push(thread.to_string());
+                self.threads.len() - 1
+            }
+        };
+
+        format!("{}{} ", "  ".repeat(lane % MAX_LANES), "▌".color(PALETTE[lane % PALETTE.len()]))
+    }
+}
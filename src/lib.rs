@@ -0,0 +1,35 @@
+pub mod align;
+pub mod buckets;
+pub mod chain;
+pub mod chart;
+pub mod colors;
+pub mod commands;
+pub mod config;
+pub mod dedupe;
+pub mod directive;
+pub mod emphasis;
+pub mod filter;
+pub mod flow;
+pub mod gaps;
+pub mod interrupt;
+pub mod lanes;
+pub mod numbers;
+pub mod origin;
+pub mod pager;
+pub mod pipeline;
+pub mod plugins;
+pub mod query;
+pub mod reload;
+pub mod render;
+pub mod replay;
+pub mod sample;
+pub mod severity;
+pub mod slo;
+pub mod stats;
+pub mod template;
+pub mod terminal;
+pub mod theme;
+pub mod time;
+pub mod title;
+pub mod verify;
+pub mod watchdog;
@@ -1,290 +1,1212 @@
 
-use std::collections::HashMap;
-use clap::Parser;
-use colored::{Colorize, ColoredString};
-use notify::{Config, RecommendedWatcher, Watcher, RecursiveMode};
-use std::fs::{self, File};
-use std::io::{Read, Seek, SeekFrom};
-use std::path::Path;
-use std::sync::{LazyLock, mpsc};
-use std::time::Duration;
+use splash::align::Align;
+use splash::buckets::{BucketFormat, Buckets, Metric};
+use splash::chain::HashChain;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
+use splash::dedupe::Dedupe;
+use splash::directive;
+use splash::emphasis::Emphasis;
+use splash::filter::Filter;
+use splash::flow::Flow;
+use splash::gaps::{Gaps, Pauses};
+use splash::lanes::Lanes;
+use splash::numbers;
+use splash::origin::Origin;
+use splash::pager::{self, Pager};
+use splash::pipeline::Pipeline;
+use splash::reload::{Reload, Settings};
+use splash::replay::Replay;
+use splash::sample::Sampler;
+use splash::severity::{Severity, Tint};
+use splash::stats::Stats;
+use splash::template::Template;
+use splash::terminal::Fit;
+use splash::plugins::{self, FileSource, Input, PluginRegistry, Sink, Source};
+use splash::verify::Verifier;
+use splash::watchdog::Watchdog;
+use splash::theme::{self, Background, Theme, Vision};
+use splash::title::Title;
+use splash::time::TimeFormat;
+use splash::config::{Config, Profile};
+use splash::{commands, config, interrupt, time};
 
-static MATCHERS: LazyLock<HashMap<&'static str, Regex>> = LazyLock::new(|| {
-    let mut m = HashMap::new();
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+   #[command(subcommand)]
+   command: Option<Command>,
 
-    // words
-    m.insert("ip_addr", Regex::new(r".*(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}).*").unwrap());
-    m.insert("http_verb", Regex::new(r"(.*)(GET|POST|PUT|PATCH|DELETE|HEAD|CONNECT|OPTIONS|TRACE)(.*)").unwrap());
-    m.insert("http_version", Regex::new(r"HTTP/1.0").unwrap());
-    m.insert("number", Regex::new(r"^\d+$").unwrap());
-    m.insert("datetime", Regex::new(r"\d{2}/[[:alpha:]]{3}/\d{4}:\d{2}:\d{2}:\d{2}").unwrap());
-    m.insert("tz_offset", Regex::new(r"[-]?\d{4}").unwrap());
+   /// Log files to follow, like splash tail FILE...
+   #[arg(value_name = "FILE", conflicts_with_all = ["path", "input", "spool"])]
+   files: Vec<String>,
 
-    // characters
-    m.insert("quote", Regex::new("\"").unwrap());
-    m.insert("square_bracket", Regex::new(r"\[|\]").unwrap());
+   /// Log Parsing Mode (clf, ad-hoc, serilog, auth, auditd, firewall, tomcat, winevent, asa, gelf, logcat, journald), several to try in turn like clf,ad-hoc, or list to show them all
+   #[arg(short, long, global = true)]
+   mode: Option<String>,
 
-    m
-});
+   /// Print plain digits in summaries instead of 1,234,567 and 1.2M, for scripts
+   #[arg(long, global = true)]
+   raw_numbers: bool,
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+   /// Path to the config file (default ~/.config/splash/config.toml)
+   #[arg(short, long, global = true)]
+   config: Option<String>,
+
+   /// Use a [profile.NAME] from the config file: its path or input, mode, theme, filters and highlights, under what the command line gives
+   #[arg(long, value_name = "NAME", global = true)]
+   profile: Option<String>,
+
+   /// When to color output: auto (when stdout is a terminal, following NO_COLOR and CLICOLOR_FORCE), always or never
+   #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto, global = true)]
+   color: ColorChoice,
+
+   #[command(flatten)]
+   args: Args,
+}
+
+// what to show of a log and how, for a bare splash, tail and cat
+#[derive(clap::Args, Debug)]
 struct Args {
-   /// Log Parsing Mode (clf, ad-hoc)
+   /// Path to the log file, ssh://[user@]host:/path to follow one on another machine, or an http(s):// URL to poll
    #[arg(short, long)]
-   mode: Option<String>,
+   path: Option<String>,
+
+   /// Read from an input: stdin, file:PATH, tcp:ADDR, gelf:ADDR, journald[:UNITS], docker:CONTAINER, ssh:HOST:PATH, spool:DIR, http(s)://URL or a discovered source (default stdin)
+   #[arg(long, conflicts_with = "path")]
+   input: Option<String>,
+
+   /// Seconds between polls of an http(s):// path (default 5), or of followed files and spool folders where changes can't be watched for (default 2)
+   #[arg(long)]
+   poll_interval: Option<f64>,
+
+   /// Watch a drop folder and read each file that appears in it once (same as --input spool:DIR)
+   #[arg(long, value_name = "DIR", conflicts_with_all = ["path", "input"])]
+   spool: Option<String>,
+
+   /// Move files read from --spool to DIR once done, instead of leaving them in place
+   #[arg(long, value_name = "DIR", requires = "spool")]
+   done: Option<String>,
 
-   /// Path to the log file
+   /// Only follow this systemd unit with --input journald (repeatable)
+   #[arg(long)]
+   unit: Vec<String>,
+
+   /// Highlight every occurrence of a term (repeatable)
    #[arg(short, long)]
-   path: Option<String>,
+   emphasize: Vec<String>,
+
+   /// Like --emphasize, ignoring case
+   #[arg(long)]
+   emphasize_i: Vec<String>,
+
+   /// Paint matches of a regex in a style, on top of the mode's colors, e.g. 'user=alice:magenta' (repeatable, black on yellow by default)
+   #[arg(long, value_name = "REGEX[:STYLE]")]
+   highlight: Vec<String>,
+
+   /// Only show lines matching a pattern (repeatable)
+   #[arg(short, long)]
+   include: Vec<String>,
+
+   /// Like --include, ignoring case
+   #[arg(long)]
+   include_i: Vec<String>,
+
+   /// Only show lines that no --include pattern matches
+   #[arg(long)]
+   invert_match: bool,
+
+   /// Stop after showing N records
+   #[arg(long, value_name = "N")]
+   max_count: Option<u64>,
+
+   /// Stop at the first line matching a pattern, exiting with status 1 if the input ends without one
+   #[arg(long, value_name = "REGEX")]
+   until_match: Option<String>,
+
+   /// Ring the terminal bell when --until-match stops
+   #[arg(long, requires = "until_match")]
+   bell: bool,
+
+   /// Exit status when --until-match stops (default 0)
+   #[arg(long, value_name = "N", default_value_t = 0, requires = "until_match")]
+   match_exit_code: i32,
+
+   /// When the input ends or on Ctrl-C, print how many lines were read, how many matched the filters and how many were errors
+   #[arg(long)]
+   summary: bool,
+
+   /// Show nothing, exit with status 0 if any record matched the filters and 1 if none did, like grep -q (cat and stdin only)
+   #[arg(short, long, conflicts_with = "count")]
+   quiet: bool,
+
+   /// Only print how many records matched the filters, exiting with status 1 if none did (cat and stdin only)
+   #[arg(long)]
+   count: bool,
+
+   /// Only show records whose field passes a test: FIELD=VALUE, FIELD!=VALUE, FIELD~=REGEX or FIELD!~=REGEX (repeatable, all must pass)
+   #[arg(long, value_name = "TEST")]
+   filter: Vec<String>,
+
+   /// Hide records below a severity: debug, info, warn or error (records without one are kept)
+   #[arg(long, value_enum, value_name = "LEVEL")]
+   min_level: Option<Severity>,
+
+   /// Only show records for which an expression on their fields holds, e.g. 'status >= 500 && method == "POST"' (repeatable)
+   #[arg(long = "where", value_name = "EXPR")]
+   where_: Vec<String>,
+
+   /// Hide records timestamped before TIME: a timestamp like "2024-05-01 12:00" or a duration back from now like 15m
+   #[arg(long, value_name = "TIME")]
+   since: Option<String>,
+
+   /// Hide records timestamped at or after TIME, in the same forms as --since
+   #[arg(long, value_name = "TIME")]
+   until: Option<String>,
+
+   /// Only show requests from clients in a network, e.g. 10.0.0.0/8 or 2001:db8::/32 (repeatable)
+   #[arg(long, value_name = "CIDR")]
+   client_net: Vec<String>,
+
+   /// Hide requests from clients in a network (repeatable)
+   #[arg(long, value_name = "CIDR")]
+   not_client_net: Vec<String>,
+
+   /// Only match whole words with --include and --emphasize
+   #[arg(short, long)]
+   word: bool,
+
+   /// Let --emphasize terms match with one character added, removed or changed
+   #[arg(long)]
+   fuzzy: bool,
+
+   /// Start each line with its line number in the input
+   #[arg(long)]
+   line_numbers: bool,
+
+   /// Start each line with the name of the file or input it came from
+   #[arg(long)]
+   show_source: bool,
+
+   /// Give each thread or PID its own color and indent level
+   #[arg(long)]
+   lanes: bool,
+
+   /// Collapse runs of identical lines, ignoring their timestamps, into the first one and a count
+   #[arg(long)]
+   dedupe: bool,
+
+   /// Only show M of every N records, e.g. 1/100, marking how many were skipped
+   #[arg(long, value_name = "M/N", conflicts_with = "rate_limit")]
+   sample: Option<String>,
+
+   /// Show at most N records per second, minute or hour, e.g. 500/s, marking how many were skipped
+   #[arg(long, value_name = "N/UNIT")]
+   rate_limit: Option<String>,
+
+   /// Record a rolling SHA-256 hash of every line read to FILE, for verify-chain to check the log against later
+   #[arg(long, value_name = "FILE")]
+   hash_chain: Option<String>,
+
+   /// Pattern with an (?P<id>...) group marking the start of a request
+   #[arg(long, requires = "pair_end")]
+   pair_start: Option<String>,
+
+   /// Pattern with an (?P<id>...) group marking the completion of a request
+   #[arg(long, requires = "pair_start")]
+   pair_end: Option<String>,
+
+   /// Seconds before an unpaired start is flagged as a probable hang
+   #[arg(long, default_value_t = 30)]
+   pair_timeout: u64,
+
+   /// Flag missing or out of order values of a sequence number or timestamp field
+   #[arg(long, value_name = "FIELD")]
+   detect_gaps: Option<String>,
+
+   /// Draw a rule with the length of the pause wherever consecutive timestamps are further apart than this, e.g. 30s or 5m
+   #[arg(long, value_name = "DURATION")]
+   mark_gaps: Option<String>,
+
+   /// Print lines with the delays between their timestamps, as they were logged
+   #[arg(long)]
+   replay: bool,
+
+   /// Speed up (or slow down, below 1) --replay
+   #[arg(long, default_value_t = 1.0, requires = "replay")]
+   speed: f64,
+
+   /// Alert when the input has been quiet for this long, e.g. 60s or 5m
+   #[arg(long, value_name = "DURATION")]
+   expect_activity: Option<String>,
+
+   /// Shell command to run when --expect-activity sees the input stall
+   #[arg(long, requires = "expect_activity")]
+   on_stall: Option<String>,
+
+   /// Show live line rate and error counts in the terminal title
+   #[arg(long)]
+   title: bool,
+
+   /// Print the modes, inputs, sinks, themes and options this binary supports as JSON
+   #[arg(long)]
+   dump_capabilities: bool,
+
+   /// Lay out the fields of each record with a template, e.g. '{client} {status} {request}', keeping the mode's colors
+   #[arg(long, value_name = "TEMPLATE")]
+   format: Option<String>,
+
+   /// Pad fields into columns, as wide as their widest value so far
+   #[arg(long)]
+   align: bool,
+
+   /// Cut lines longer than COLS (default the terminal's width), ending them with …
+   #[arg(long, value_name = "COLS", num_args = 0..=1, default_missing_value = "0", conflicts_with = "wrap")]
+   truncate: Option<usize>,
+
+   /// Wrap lines longer than the terminal is wide, indenting what carries on
+   #[arg(long)]
+   wrap: bool,
+
+   /// Show timestamps as logged (original), relative to now like -3m12s, as ISO 8601 in the local timezone, or as Unix seconds
+   #[arg(long, value_enum, value_name = "FORMAT", default_value_t = TimeFormat::Original)]
+   timefmt: TimeFormat,
+
+   /// Show at most N characters of a field, cutting out the middle, e.g. user_agent=40 (repeatable)
+   #[arg(long, value_name = "FIELD=N")]
+   max_width: Vec<String>,
+
+   /// Check the input against the [slo] budgets in the config file
+   #[arg(long)]
+   slo: bool,
+
+   /// Exit with status 2 when an SLO budget is broken (implies --slo)
+   #[arg(long)]
+   slo_exit_code: bool,
+
+   /// Aggregate records into time buckets (e.g. 1m, 1h) instead of printing them
+   #[arg(long)]
+   bucket: Option<String>,
+
+   /// Output format for --bucket
+   #[arg(long, value_enum, default_value_t = BucketFormat::Table)]
+   bucket_format: BucketFormat,
+
+   /// Chart metrics over time in the terminal (buckets default to 1m)
+   #[arg(long, value_enum, value_delimiter = ',')]
+   chart: Vec<Metric>,
+
+   /// Page the output through $PAGER (default less), as happens on a terminal when stdin is a file
+   #[arg(long, conflicts_with = "no_pager")]
+   pager: bool,
+
+   /// Print straight to the terminal even when stdin is a file
+   #[arg(long)]
+   no_pager: bool,
+
+   /// Print plain text without color, with timestamps normalized to RFC 3339
+   #[arg(long)]
+   plain: bool,
+
+   /// Check that every line parses and serializes back to the same text, reporting lines that lose information
+   #[arg(long)]
+   verify: bool,
+
+   /// Terminal background the colors should suit (default auto: ask the terminal)
+   #[arg(long, value_enum)]
+   background: Option<Background>,
+
+   /// Color theme: default, deuteranopia, protanopia, tritanopia, solarized-dark, solarized-light, monochrome, high-contrast, or one defined in the config file or imported
+   #[arg(long)]
+   theme: Option<String>,
+
+   /// Colors for color blindness under any theme, with severity also marked as by --redundant-styles
+   #[arg(long, value_enum, value_name = "VISION")]
+   palette: Option<Vision>,
+
+   /// Also mark severity with symbols, bold and underline instead of color alone
+   #[arg(long)]
+   redundant_styles: bool,
+
+   /// Turn off ad-hoc matchers: number, ip_addr, datetime, tz_offset, http_version, http_verb, quote, square_bracket
+   #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+   no_matcher: Vec<String>,
+
+   /// Dim debug lines and color whole warning and error lines, in yellow and red (fg) or behind the text (band)
+   #[arg(long, value_enum, value_name = "STYLE", num_args = 0..=1, default_missing_value = "fg")]
+   tint_lines: Option<Tint>,
+
+   /// Send output to a sink: terminal, file:PATH, html:PATH, quickfix[:PATH], elasticsearch:URL, loki:URL, syslog:HOST[:PORT], webhook:URL (repeatable)
+   #[arg(long)]
+   sink: Vec<String>,
+
+   /// Also write a plain copy of the output to PATH, replacing what was there
+   #[arg(long, value_name = "PATH")]
+   tee: Option<String>,
+
+   /// Also send each line, without color, to a remote collector: syslog://HOST[:PORT] (repeatable)
+   #[arg(long, value_name = "URL")]
+   forward: Vec<String>,
+
+   /// Print as text, or only the source locations found, for vim's :cfile (same as --sink quickfix)
+   #[arg(long, value_enum, default_value_t = Output::Text, conflicts_with = "sink")]
+   output: Output,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+   /// Follow a log file, showing lines as they are appended (the same as a bare splash FILE)
+   Tail {
+      /// Paths to the log files (default stdin)
+      #[arg(value_name = "FILE", conflicts_with_all = ["path", "input", "spool"])]
+      files: Vec<String>,
+
+      #[command(flatten)]
+      args: Box<Args>,
+   },
+
+   /// Show a whole log file and exit
+   Cat {
+      /// Paths to the log files (default stdin)
+      #[arg(value_name = "FILE", conflicts_with_all = ["path", "input", "spool"])]
+      files: Vec<String>,
+
+      #[command(flatten)]
+      args: Box<Args>,
+   },
+
+   /// List the modes, sinks and inputs, built in and discovered, install them or turn them on and off
+   Plugins {
+      #[command(subcommand)]
+      command: Option<PluginsCommand>,
+   },
+
+   /// Count the lines of a log the mode parses and the ones it fails to, showing the first failures
+   Check {
+      /// Path to the log file (default stdin)
+      path: Option<String>,
+
+      /// Number of failing lines to show
+      #[arg(long, value_name = "N", default_value_t = 5)]
+      show: usize,
+   },
+
+   /// Show how a line is parsed and colored: its fields and their theme roles, the matchers that fired and the colors applied
+   Explain {
+      /// The log line (default stdin)
+      line: Option<String>,
+   },
+
+   /// Rank the modes by how much of a sample of a log file each one recognizes
+   Detect {
+      /// Path to the log file
+      path: String,
+   },
+
+   /// Measure how many lines and MB a second a mode parses and colors a log file at, with and without color
+   Bench {
+      /// Path to the log file
+      path: String,
+
+      /// Seconds to spend on each measurement
+      #[arg(long, value_name = "N", default_value_t = 1)]
+      seconds: u64,
+   },
+
+   /// Summarize a log file, or compare two time windows of it
+   Stats {
+      /// Path to the log file
+      path: String,
+
+      /// Compare two time of day windows, e.g. 10:00-10:30 11:00-11:30
+      #[arg(long, num_args = 2, value_names = ["WINDOW_A", "WINDOW_B"])]
+      compare: Vec<String>,
+   },
+
+   /// Report each parsed field's cardinality, missing rate, range and top values
+   Profile {
+      /// Path to the log file
+      path: String,
+   },
+
+   /// Write a commented config file, or show the configuration in effect
+   Config {
+      #[command(subcommand)]
+      command: ConfigCommand,
+   },
+
+   /// List the color themes, or import one
+   #[command(visible_alias = "theme")]
+   Themes {
+      #[command(subcommand)]
+      command: Option<ThemeCommand>,
+   },
+
+   /// Follow several files side by side, one tmux (or WezTerm) pane each
+   TmuxSplit {
+      /// Paths to the log files
+      #[arg(required = true)]
+      files: Vec<String>,
+   },
+
+   /// Write synthetic logs in a mode's format, for tests and benchmarks
+   GenFixtures {
+      /// Number of entries
+      #[arg(long, default_value_t = 1000)]
+      lines: u64,
+
+      /// Share of entries that are errors, from 0 to 1
+      #[arg(long, default_value_t = 0.05)]
+      error_rate: f64,
+
+      /// IPv4 block client addresses are drawn from
+      #[arg(long, default_value = "203.0.113.0/24")]
+      ip_pool: String,
+
+      /// Number of distinct client addresses
+      #[arg(long, default_value_t = 50)]
+      clients: u64,
+
+      /// Request paths to choose from, comma separated
+      #[arg(long, value_delimiter = ',')]
+      paths: Vec<String>,
+
+      /// Statuses of successful requests to choose from, comma separated (errors are 5xx)
+      #[arg(long, value_delimiter = ',')]
+      statuses: Vec<u16>,
+
+      /// Timestamp of the first entry, or a duration back from now like 1h (default: so the last is about now)
+      #[arg(long, value_name = "TIME")]
+      start: Option<String>,
+
+      /// Average entries per second, spaced randomly
+      #[arg(long, default_value_t = 5.0)]
+      rate: f64,
+
+      /// Seed for reproducible output
+      #[arg(long)]
+      seed: Option<u64>,
+   },
+
+   /// Check a log file against a chain recorded with --hash-chain
+   VerifyChain {
+      /// Path to the log file
+      path: String,
+
+      /// Path to the chain file
+      chain: String,
+   },
+
+   /// Run multi-source pipelines defined in a file
+   Pipeline {
+      #[command(subcommand)]
+      command: PipelineCommand,
+   },
+
+   /// Print a completion script for a shell, e.g. splash completions bash > /etc/bash_completion.d/splash
+   Completions {
+      /// Shell to complete in
+      #[arg(value_enum)]
+      shell: Shell,
+   },
 }
 
-struct Log<'a> {
-    client: &'a str,
-    user_identifier: &'a str,
-    userid: &'a str,
-    datetime: &'a str,
-    method: &'a str,
-    request: &'a str,
-    protocol: &'a str,
-    status: &'a str,
-    size: &'a str,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+   Auto,
+   Always,
+   Never,
 }
 
-fn main() {
-    let args = Args::parse();
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Output {
+   Text,
+   Quickfix,
+}
 
-    let mode: String = match args.mode {
-        Some(m) => { m }
-        _ => { "ad-hoc".to_string() }
-    };
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+   /// Write a config file with every setting commented out (to --config, or the default location)
+   Init {
+      /// Replace an existing file
+      #[arg(long)]
+      force: bool,
+   },
 
-    let path: Option<String> = match args.path {
-        Some(p) => { Some(p) },
-        _ => { None }
-    };
+   /// Print the configuration in effect: the config file with command line options applied, and what the environment decides
+   Dump,
+}
 
-    match path {
-        Some(p) => {
-            if let Err(e) = watch(p, &mode) {
-                eprintln!("Error: {:?}", e);
-                std::process::exit(1);
-            }
-        }
-        None => {
-            for line in std::io::stdin().lines() {
-                print_contents(&line.unwrap(), &mode);
-            }
-        }
-    }
+#[derive(Subcommand, Debug)]
+enum ThemeCommand {
+   /// Import a base16 YAML scheme, iTerm2 .itermcolors file or VS Code color theme
+   Import {
+      /// Path to the color scheme
+      file: String,
+
+      /// Name to save the theme as (default: the file name)
+      #[arg(long)]
+      name: Option<String>,
+   },
 }
 
-fn watch<P: AsRef<Path>>(path: P, mode: &str) -> notify::Result<()> {
-    let (tx, rx) = mpsc::channel();
+#[derive(Subcommand, Debug)]
+enum PluginsCommand {
+   /// List every mode, sink and input with its state, version and where it comes from (the default)
+   List,
 
-    let config = Config::default()
-                    .with_poll_interval(Duration::from_secs(2))
-                    .with_compare_contents(true);
+   /// Show a plugin's kind, state, version, author, path, fields and styles
+   Info {
+      /// Name of the plugin
+      name: String,
+   },
 
-    let mut watcher = RecommendedWatcher::new(tx, config)?;
+   /// Turn a disabled plugin back on
+   Enable {
+      /// Name of the plugin
+      name: String,
+   },
 
-    watcher.watch(path.as_ref(), RecursiveMode::NonRecursive)?;
-    
-    let mut contents = fs::read_to_string(&path).unwrap();
-    let mut pos = contents.len() as u64;
+   /// Turn a plugin off in the config file, as if it wasn't installed
+   Disable {
+      /// Name of the plugin
+      name: String,
+   },
 
-    loop {
-        match rx.recv() {
-            Ok(_) => {
-                let mut f = File::open(&path).unwrap();
-                f.seek(SeekFrom::Start(pos)).unwrap();
+   /// Print the directories plugins are discovered in
+   Path,
 
-                pos = f.metadata().unwrap().len();
+   /// Copy a plugin file into ~/.splash/plugins from a path or an http(s) URL, once it loads
+   Install {
+      /// Path or URL of a splash-format-NAME, splash-sink-NAME or splash-source-NAME executable, a NAME.toml or a NAME.rhai
+      source: String,
+   },
 
-                contents.clear();
-                f.read_to_string(&mut contents).unwrap();
+   /// Delete an installed plugin from ~/.splash/plugins
+   Remove {
+      /// Name of the plugin
+      name: String,
+   },
 
-                print_contents(&contents, mode);
-            }
-            Err(e) => {
-                eprintln!("Error: {:?}", e);
-                std::process::exit(1);
-            }
+   /// Install plugins again from where they were installed from
+   Update {
+      /// Name of the plugin (default every installed one)
+      name: Option<String>,
+   },
+}
+
+#[derive(Subcommand, Debug)]
+enum PipelineCommand {
+   /// Run the sources, transforms and sinks described in a TOML file
+   Run {
+      /// Path to the pipeline file
+      path: String,
+   },
+}
+
+fn or_exit<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(v) => { v }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
     }
 }
 
-fn print_contents(contents: &str, mode: &str) {
-    match mode {
-        "clf" => { print_clf(contents) },
-        _ => { print_adhoc(contents) }
+// the config file with what the command line overrides, for config dump
+fn effective(mut config: Config, args: &Args, mode: &str, theme: Option<String>) -> Config {
+    config.mode = Some(mode.to_string());
+    config.theme = theme.or(config.theme);
+    config.background = args.background.or(config.background);
+    config.palette = args.palette.or(config.palette);
+    config.poll_interval = args.poll_interval.or(config.poll_interval);
+    config.redundant_styles |= args.redundant_styles;
+    config.adhoc.disabled.extend(args.no_matcher.iter().cloned());
+
+    if !args.sink.is_empty() {
+        config.sinks = args.sink.clone();
     }
-}
 
-fn print_adhoc(contents: &str) {
-    let mut lines = contents.lines();
+    for (field, width) in args.max_width.iter().filter_map(|w| parse_width(w).ok()) {
+        config.widths.insert(field, width);
+    }
 
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            continue;
+    for (option, set) in [("line_numbers", args.line_numbers), ("show_source", args.show_source)] {
+        if set {
+            config.prefix.insert(option.to_string(), true);
         }
+    }
+
+    config
+}
+
+// the first option of a bare splash given on the command line, for tail and
+// cat to refuse when it comes before them
+fn given_before(matches: &ArgMatches) -> Option<String> {
+    <Args as clap::Args>::augment_args(clap::Command::new("splash")).get_arguments()
+        .filter(|a| matches.value_source(a.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .map(|a| a.get_long().map(|l| format!("--{}", l)).unwrap_or(a.get_id().to_string()))
+        .next()
+}
 
-        print_highlighted(line);
+// a --profile's options, where the command line doesn't give its own
+fn with_profile(mut args: Args, profile: &Profile) -> Args {
+    if args.path.is_none() && args.input.is_none() && args.spool.is_none() {
+        args.path = profile.path.clone();
+        args.input = profile.input.clone();
+    }
+
+    args.theme = args.theme.or(profile.theme.clone());
+    args.min_level = args.min_level.or(profile.min_level);
+
+    args.include.extend(profile.include.iter().cloned());
+    args.filter.extend(profile.filter.iter().cloned());
+    args.where_.extend(profile.where_.iter().cloned());
+    args.emphasize.extend(profile.emphasize.iter().cloned());
+    args.highlight.extend(profile.highlight.iter().cloned());
+
+    args
+}
+
+// the theme from --theme or the #splash: directive, or else the config file's
+fn build_theme(name: Option<&str>, background: Option<Background>, palette: Option<Vision>, config: &Config, clf_fields: &[&str]) -> Result<Theme, String> {
+    let background = background.or(config.background).unwrap_or(Background::Auto);
+    let name = name.or(config.theme.as_deref()).unwrap_or("default");
+    let theme = Theme::named(name, background, &config.themes)?.with_overrides("clf", clf_fields, &config.clf)?;
+
+    match palette.or(config.palette) {
+        Some(vision) => { Ok(theme.with_vision(vision, background)) }
+        None => { Ok(theme) }
     }
 }
 
-fn print_highlighted(line: &str) {
-    let mut final_str: String = "".to_owned();
-    let hcs: String = highlight_chars(line).to_string();
+// the theme's widths, then the config file's, then --max-width
+fn field_widths(theme: &Theme, plugin_name: &str, config_widths: &BTreeMap<String, usize>, cli_widths: &[(String, usize)]) -> Vec<(String, usize)> {
+    let mut widths = theme.widths(plugin_name);
+    let config_widths: Vec<(String, usize)> = config_widths.iter().map(|(f, w)| (f.clone(), *w)).collect();
 
-    for word in hcs.split_whitespace() {
-        final_str.push_str(&highlight_word(word).to_string());
-        final_str.push_str(" ");
+    for (field, width) in theme::plugin_widths(&config_widths, plugin_name).into_iter().chain(cli_widths.iter().cloned()) {
+        widths.retain(|(f, _)| *f != field);
+        widths.push((field, width));
     }
 
-    println!("{}", final_str.trim());
+    widths
 }
 
-fn matcher(name: &str) -> &Regex {
-    MATCHERS.get(name).unwrap()
+// --max-width FIELD=N
+fn parse_width(spec: &str) -> Result<(String, usize), String> {
+    spec.split_once('=')
+        .and_then(|(field, n)| Some((field.to_string(), n.parse::<usize>().ok().filter(|n| *n > 0)?)))
+        .ok_or(format!("invalid width '{}', expected FIELD=N like user_agent=40", spec))
 }
 
-fn highlight_word(word: &str) -> ColoredString {
-    let mut re: &Regex;
+fn main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    re = matcher("number");
-    if re.is_match(word) {
-        return word.bright_blue();
+    // tail and cat take their own options, so ones before them would be lost
+    if let (Some(Command::Tail { .. } | Command::Cat { .. }), Some(option)) = (&cli.command, given_before(&matches)) {
+        let subcommand = matches.subcommand_name().unwrap_or_default();
+        eprintln!("Error: {} goes after {}, like splash {} {} ...", option, subcommand, subcommand, option);
+        std::process::exit(1);
     }
 
-    re = matcher("ip_addr");
-    if re.is_match(word) {
-        return word.bright_red();
+    // tail and cat are a bare splash, following files or reading all of them
+    let (args, files, command, once) = match cli.command {
+        Some(Command::Tail { files, args }) => { (*args, files, None, false) }
+        Some(Command::Cat { files, args }) => { (*args, files, None, true) }
+        command => { (cli.args, cli.files, command, false) }
+    };
+
+    // config init writes the file the rest reads, which needn't exist yet
+    if let Some(Command::Config { command: ConfigCommand::Init { force } }) = &command {
+        or_exit(commands::config::init(cli.config.as_deref(), *force));
+        return;
     }
 
-    re = matcher("datetime");
-    if re.is_match(word) {
-        return word.cyan();
+    let config = or_exit(config::load(cli.config.as_deref()));
+
+    // the first file is read as --path is, the others are followed with it
+    let args = Args { path: files.first().cloned().or(args.path), ..args };
+
+    let profile = cli.profile.as_deref().map(|name| or_exit(config.profile(name)).clone());
+    let args = match &profile {
+        Some(profile) => { with_profile(args, profile) }
+        None => { args }
+    };
+
+    let files: Vec<String> = args.path.iter().cloned().chain(files.into_iter().skip(1)).collect();
+
+    if files.len() > 1 && files.iter().any(|f| f.contains("://")) {
+        eprintln!("Error: only local files can be read together");
+        std::process::exit(1);
     }
 
-    re = matcher("tz_offset");
-    if re.is_match(word) {
-        return word.cyan();
+    let input = match (&args.input, &args.path, args.unit.is_empty()) {
+        (Some(spec), _, false) if spec == "journald" => { format!("journald:{}", args.unit.join(",")) }
+        (Some(spec), _, _) => { spec.clone() }
+        (None, Some(p), _) if p.starts_with("http://") || p.starts_with("https://") => { p.clone() }
+        (None, Some(p), _) if p.starts_with("ssh://") => { format!("ssh:{}", p.trim_start_matches("ssh://")) }
+        (None, Some(p), _) if once => { format!("file:{}#once", p) }
+        (None, Some(p), _) => { format!("file:{}", p) }
+        (None, None, _) => { "stdin".to_string() }
+    };
+
+    let input = match (&args.spool, &args.done) {
+        (Some(dir), Some(done)) => { format!("spool:{}#done={}", dir, done) }
+        (Some(dir), None) => { format!("spool:{}", dir) }
+        _ => { input }
+    };
+
+    // a count or a match status is only known once the input ends
+    if (args.quiet || args.count) && command.is_none() && !once && input != "stdin" {
+        eprintln!("Error: --{} needs input that ends, use splash cat to read a file through", if args.quiet { "quiet" } else { "count" });
+        std::process::exit(1);
     }
 
-    re = matcher("http_version");
-    if re.is_match(word) {
-        return word.cyan();
+    // auto leaves it to colored, which looks at stdout and the environment
+    match (args.plain, cli.color) {
+        (true, _) | (_, ColorChoice::Never) => { colored::control::set_override(false) }
+        (false, ColorChoice::Always) => { colored::control::set_override(true) }
+        (false, ColorChoice::Auto) => {}
     }
 
-    re = matcher("http_verb");
-    if re.is_match(word) {
-        let caps = re.captures(word).unwrap();
+    numbers::set_raw(cli.raw_numbers);
+
+    let disabled: Vec<String> = config.adhoc.disabled.iter().chain(&args.no_matcher).cloned().collect();
+    or_exit(plugins::set_adhoc_matchers(&config.adhoc.order, &disabled));
+
+    // how often what's followed is polled, where it has to be
+    let poll = args.poll_interval.or(config.poll_interval);
+    let input = match poll {
+        Some(secs) if ["http", "file:", "spool:"].iter().any(|p| input.starts_with(p)) && !input.ends_with("#once") => { format!("{}#poll={}", input, secs) }
+        _ => { input }
+    };
 
-        let mut s: String = "".to_owned();
-        s.push_str(caps.get(1).unwrap().as_str());
-        s.push_str(&caps.get(2).unwrap().as_str().bright_green().to_string());
-        s.push_str(caps.get(3).unwrap().as_str());
+    let mut registry = PluginRegistry::new();
+    registry.disable(&config.plugins.disabled);
 
-        return s.normal();
+    if cli.mode.as_deref() == Some("list") {
+        or_exit(commands::plugins::modes(&registry));
+        return;
     }
 
-    word.normal()
-}
+    // before the mode is looked up, so a disabled one can be enabled again
+    match &command {
+        Some(Command::Plugins { command: None | Some(PluginsCommand::List) }) => {
+            or_exit(commands::plugins::list(&registry));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Info { name }) }) => {
+            or_exit(commands::plugins::info(&registry, name));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Enable { name }) }) => {
+            or_exit(commands::plugins::enable(&registry, cli.config.as_deref(), &config, name, true));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Disable { name }) }) => {
+            or_exit(commands::plugins::enable(&registry, cli.config.as_deref(), &config, name, false));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Path) }) => {
+            commands::plugins::path();
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Install { source }) }) => {
+            or_exit(commands::plugins::install(&registry, source));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Remove { name }) }) => {
+            or_exit(commands::plugins::remove(&registry, name));
+            return;
+        }
+        Some(Command::Plugins { command: Some(PluginsCommand::Update { name }) }) => {
+            or_exit(commands::plugins::update(&registry, name.as_deref()));
+            return;
+        }
+        _ => {}
+    }
+
+    // options from [plugin.NAME], before any line is parsed
+    for (name, options) in &config.options {
+        if !registry.is_disabled(name) {
+            or_exit(registry.mode(name).and_then(|p| p.configure(options)).map_err(|e| format!("[plugin.{}]: {}", name, e)));
+        }
+    }
+
+    if args.dump_capabilities {
+        or_exit(commands::capabilities::dump(&registry, &Cli::command()));
+        return;
+    }
+
+    // piped input can configure splash with a #splash: first line
+    let (directive, first_line) = match command.is_none() && input == "stdin" {
+        true => { or_exit(directive::read_stdin()) }
+        false => { (None, None) }
+    };
+
+    let directed = directive.is_some();
+    let directive = directive.unwrap_or_default();
+
+    // the journal is read as JSON, which only the journald mode understands
+    let mode: String = match cli.mode.or(profile.and_then(|p| p.mode)).or(directive.mode).or(args.path.as_deref().and_then(|p| config.mode_for(p)).map(String::from)) {
+        Some(m) => { m }
+        None if input.starts_with("journald") => { "journald".to_string() }
+        None => { config.mode.clone().unwrap_or("ad-hoc".to_string()) }
+    };
+
+    let chain = or_exit(registry.chain(&mode, &config.chain(&mode)));
+    let plugin = chain.plugin();
+
+    let theme_name = args.theme.clone().or(directive.theme);
+    let background = args.background.or(directive.background);
+    let clf_fields = registry.get_or_default("clf").fields();
+    theme::init(or_exit(build_theme(theme_name.as_deref(), background, args.palette, &config, &clf_fields)));
+
+    match &command {
+        Some(Command::Stats { path, compare }) => {
+            or_exit(commands::stats::run(path, plugin, compare));
+            return;
+        }
+        Some(Command::Profile { path }) => {
+            or_exit(commands::profile::run(path, plugin));
+            return;
+        }
+        Some(Command::Config { command: ConfigCommand::Dump }) => {
+            let config = effective(config, &args, &mode, theme_name);
+            let color = match (args.plain, cli.color) {
+                (true, _) => { Some("--plain") }
+                (false, ColorChoice::Auto) => { None }
+                (false, _) => { Some("--color") }
+            };
+
+            or_exit(commands::config::dump(cli.config.as_deref(), &config, color));
+            return;
+        }
+        Some(Command::Check { path, show }) => {
+            if !or_exit(commands::check::run(path.as_deref(), plugin, *show)) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Explain { line }) => {
+            or_exit(commands::explain::run(line.as_deref(), plugin, &config.matchers));
+            return;
+        }
+        Some(Command::Bench { path, seconds }) => {
+            or_exit(commands::bench::run(path, plugin, &config.matchers, *seconds));
+            return;
+        }
+        Some(Command::Detect { path }) => {
+            or_exit(commands::detect::run(path, &registry));
+            return;
+        }
+        Some(Command::Themes { command: None }) => {
+            commands::theme::list(&config.themes);
+            return;
+        }
+        Some(Command::Themes { command: Some(ThemeCommand::Import { file, name }) }) => {
+            or_exit(commands::theme::import(file, name.as_deref()));
+            return;
+        }
+        Some(Command::TmuxSplit { files }) => {
+            or_exit(commands::tmux::split(files, &mode));
+            return;
+        }
+        Some(Command::GenFixtures { lines, error_rate, ip_pool, clients, paths, statuses, start, rate, seed }) => {
+            let options = commands::fixtures::Options {
+                lines: *lines,
+                error_rate: *error_rate,
+                ip_pool: ip_pool.clone(),
+                clients: *clients,
+                paths: paths.clone(),
+                statuses: statuses.clone(),
+                start: start.clone(),
+                rate: *rate,
+                seed: *seed,
+            };
 
-fn highlight_chars(line: &str) -> ColoredString {
-    let mut final_str: String = "".to_owned();
+            or_exit(commands::fixtures::generate(&mode, &options));
+            return;
+        }
+        Some(Command::VerifyChain { path, chain }) => {
+            if !or_exit(commands::chain::verify(path, chain)) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Pipeline { command: PipelineCommand::Run { path } }) => {
+            or_exit(commands::pipeline::run(path, &registry));
+            return;
+        }
+        Some(Command::Completions { shell }) => {
+            commands::completions::generate(*shell, Cli::command(), &registry, &config);
+            return;
+        }
+        // plugins was handled before the mode was looked up
+        Some(Command::Plugins { .. }) => {}
+        // config init was handled before the config file was loaded
+        Some(Command::Config { command: ConfigCommand::Init { .. } }) => {}
+        Some(Command::Tail { .. }) | Some(Command::Cat { .. }) | None => {}
+    }
 
-    for c in line.chars() {
-        let c_str = c.to_string();
+    // files are checked once rather than followed
+    if args.verify {
+        let mut verifier = Verifier::new(plugin);
+
+        match (&args.input, &args.path) {
+            (None, Some(_)) => {
+                for p in &files {
+                    verifier.contents(&or_exit(fs::read_to_string(p).map_err(|e| format!("{}: {}", p, e))));
+                }
+            }
+            _ => {
+                if let Some(line) = &first_line {
+                    verifier.contents(line);
+                }
 
-        if matcher("quote").is_match(&c_str) {
-            final_str.push_str(&c_str.bright_white().to_string());
-        } else if matcher("square_bracket").is_match(&c_str) {
-            final_str.push_str(&c_str.bright_white().to_string());
-        } else {
-            final_str.push_str(&c_str);
+                let mut source = or_exit(registry.source(&input));
+                or_exit(source.run(&mut verifier));
+            }
+        }
+
+        if !verifier.report() {
+            std::process::exit(1);
         }
+        return;
     }
 
-    final_str.normal()
-}
+    let slo = match (args.slo || args.slo_exit_code, config.slo) {
+        (true, Some(slo)) => { Some(slo) }
+        (true, None) => {
+            eprintln!("Error: --slo needs an [slo] section in the config file");
+            std::process::exit(1);
+        }
+        (false, _) => { None }
+    };
+
+    let bucket = match (args.bucket, args.chart.is_empty()) {
+        (Some(b), _) => { Some(b) }
+        (None, false) => { Some("1m".to_string()) }
+        (None, true) => { None }
+    };
+
+    let buckets = bucket.map(|b| (Buckets::new(or_exit(time::parse_period(&b))), args.bucket_format));
+
+    let filter = or_exit(Filter::new(&args.include, &args.include_i, args.word)).with_invert(args.invert_match);
+    let filter = or_exit(filter.with_fields(&args.filter)).with_min_level(args.min_level);
+    let filter = or_exit(filter.with_where(&args.where_));
+    let filter = or_exit(filter.with_window(args.since.as_deref(), args.until.as_deref()));
+    let filter = or_exit(filter.with_client_nets(&args.client_net, &args.not_client_net));
+
+    let sampler = match (&args.sample, &args.rate_limit) {
+        (Some(spec), _) => { Some(or_exit(Sampler::sample(spec))) }
+        (None, Some(spec)) => { Some(or_exit(Sampler::rate_limit(spec))) }
+        (None, None) => { None }
+    };
+
+    let flow = match (args.pair_start, args.pair_end) {
+        (Some(start), Some(end)) => { Some(or_exit(Flow::new(&start, &end, Duration::from_secs(args.pair_timeout)))) }
+        _ => { None }
+    };
+
+    let sink_specs = match (args.sink.is_empty(), config.sinks.is_empty()) {
+        _ if args.quiet || args.count => { Vec::new() }
+        _ if args.output == Output::Quickfix => { vec!["quickfix".to_string()] }
+        (false, _) => { args.sink }
+        (true, false) => { config.sinks }
+        (true, true) => { vec!["terminal".to_string()] }
+    };
+
+    let mut sinks: Vec<Box<dyn Sink>> = sink_specs.iter().map(|spec| or_exit(registry.sink(spec))).collect();
+
+    if let Some(path) = &args.tee {
+        sinks.push(or_exit(registry.sink(&format!("file:{}#truncate", path))));
+    }
+
+    for url in &args.forward {
+        let forward = match url.starts_with("syslog://") {
+            true => { registry.sink(url) }
+            false => { Err(format!("unsupported --forward '{}', expected syslog://HOST[:PORT]", url)) }
+        };
+
+        sinks.push(or_exit(forward));
+    }
+
+    let plugin_name = plugin.metadata().name;
+
+    let cli_widths: Vec<(String, usize)> = args.max_width.iter().map(|w| or_exit(parse_width(w))).collect();
+    let widths = field_widths(theme::current(), &plugin_name, &config.widths, &cli_widths);
 
-fn print_clf(contents: &str) {
-    // common log format
-    let re = Regex::new(
-        r#"(?x)
-        ([\d]{1,3}\.[\d]{1,3}\.[\d]{1,3}\.[\d]{1,3}) # client
-        \s
-        (\S+)                                        # user_identifier
-        \s
-        (\S+)                                        # userid
-        \s
-        (?:(\[.*?\]))                                # datetime
-        \s
-        "([A-Z]+)\s(\S+)\s(\S+)"                     # method, request, protocol
-        \s
-        (\d{3})                                      # status
-        \s
-        (\d+|-)                                      # size
-        "#
-    ).unwrap();
-
-    let mut lines = contents.lines();
-
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            continue;
-        }
-
-        let fields = re.captures_iter(line).filter_map(|cap| {
-            let groups = (
-                cap.get(1),
-                cap.get(2),
-                cap.get(3),
-                cap.get(4),
-                cap.get(5),
-                cap.get(6),
-                cap.get(7),
-                cap.get(8),
-                cap.get(9),
-            );
-            match groups {
-                (
-                    Some(client),
-                    Some(user_identifier),
-                    Some(userid),
-                    Some(datetime),
-                    Some(method),
-                    Some(request),
-                    Some(protocol),
-                    Some(status),
-                    Some(size),
-                ) => Some(Log {
-                    client: client.as_str(),
-                    user_identifier: user_identifier.as_str(),
-                    userid: userid.as_str(),
-                    datetime: datetime.as_str(),
-                    method: method.as_str(),
-                    request: request.as_str(),
-                    protocol: protocol.as_str(),
-                    status: status.as_str(),
-                    size: size.as_str(),
-                }),
-                _ => None,
+    // the config file is read again when it changes, for what can change
+    // without starting over
+    let reload = cli.config.as_ref().map(PathBuf::from).or(config::default_path()).map(|path| {
+        let config_path = cli.config.clone();
+        let (emphasize, emphasize_i, word, fuzzy) = (args.emphasize.clone(), args.emphasize_i.clone(), args.word, args.fuzzy);
+        let (highlight, no_matcher, plugin_name, palette) = (args.highlight.clone(), args.no_matcher.clone(), plugin_name.clone(), args.palette);
+
+        Reload::watch(&path, Box::new(move || {
+            let config = config::load(config_path.as_deref())?;
+            let theme = build_theme(theme_name.as_deref(), background, palette, &config, &clf_fields)?;
+            let emphasis = Emphasis::new(&emphasize, &emphasize_i, word, fuzzy).with_highlights(&highlight)?.with_matchers(&config.matchers)?;
+            let widths = field_widths(&theme, &plugin_name, &config.widths, &cli_widths);
+
+            let disabled: Vec<String> = config.adhoc.disabled.iter().chain(&no_matcher).cloned().collect();
+            plugins::set_adhoc_matchers(&config.adhoc.order, &disabled)?;
+            theme::init(theme);
+
+            Ok(Settings { emphasis, widths })
+        }))
+    });
+
+    // --line-numbers and --show-source, or the config file's [prefix] for the mode
+    let prefixed = |option: &str| config.prefix.get(&format!("{}.{}", plugin_name, option)).or(config.prefix.get(option)).copied().unwrap_or(false);
+    let line_numbers = args.line_numbers || prefixed("line_numbers");
+    let show_source = args.show_source || prefixed("show_source") || files.len() > 1;
+
+    let origin = match (line_numbers || show_source, &args.path) {
+        (false, _) => { None }
+        (true, Some(path)) if input.starts_with("file:") && !once => {
+            let origin = files.iter().fold(Origin::new(line_numbers, show_source, path), |mut origin, file| {
+                origin.source(file);
+                origin.after(fs::read(file).map(|b| b.iter().filter(|c| **c == b'\n').count() as u64).unwrap_or(0))
+            });
+
+            Some(origin)
+        }
+        (true, Some(path)) => { Some(Origin::new(line_numbers, show_source, path)) }
+        (true, None) => { Some(Origin::new(line_numbers, show_source, &input)) }
+    };
+
+    // a file read whole, by cat or redirected to stdin, is paged on a
+    // terminal, followed input isn't
+    let page = match (args.pager, args.no_pager) {
+        (_, true) => { false }
+        (true, _) => { true }
+        _ if args.quiet || args.count => { false }
+        _ => { io::stdout().is_terminal() && (once || (input == "stdin" && pager::stdin_is_file())) }
+    };
+
+    let pager = match page {
+        true => {
+            if cli.color == ColorChoice::Auto && !args.plain {
+                colored::control::set_override(true);
+            }
+            Some(or_exit(Pager::start()))
+        }
+        false => { None }
+    };
+
+    let mut pipeline = Pipeline {
+        plugin,
+        filter,
+        sampler,
+        // one match is all --quiet needs to know
+        remaining: args.max_count.or(args.quiet.then_some(1)),
+        until_match: args.until_match.as_deref().map(|p| or_exit(Regex::new(p).map_err(|e| format!("invalid --until-match '{}': {}", p, e)))),
+        matched: false,
+        emphasis: or_exit(Emphasis::new(&args.emphasize, &args.emphasize_i, args.word, args.fuzzy).with_highlights(&args.highlight).and_then(|e| e.with_matchers(&config.matchers))),
+        widths,
+        template: args.format.as_deref().map(|f| or_exit(Template::parse(f))),
+        origin,
+        align: if args.align { Some(Align::new()) } else { None },
+        fit: match (args.truncate, args.wrap) {
+            (Some(columns), _) => { Some(Fit::Truncate(Some(columns).filter(|c| *c > 0))) }
+            (None, true) => { Some(Fit::Wrap) }
+            (None, false) => { None }
+        },
+        timefmt: args.timefmt,
+        lanes: if args.lanes { Some(Lanes::new()) } else { None },
+        flow,
+        dedupe: if args.dedupe { Some(Dedupe::new()) } else { None },
+        gaps: args.detect_gaps.as_deref().map(Gaps::new),
+        pauses: args.mark_gaps.map(|d| Pauses::new(or_exit(time::parse_period(&d)))),
+        replay: if args.replay { Some(or_exit(Replay::new(args.speed))) } else { None },
+        watchdog: args.expect_activity.map(|w| Watchdog::new(or_exit(time::parse_period(&w)), args.on_stall.as_deref())),
+        title: if args.title { Some(Title::new(Vec::new())) } else { None },
+        chain: args.hash_chain.as_deref().map(|p| or_exit(HashChain::create(p))),
+        stats: Stats::new(),
+        lines: 0,
+        slo,
+        buckets,
+        chart: args.chart,
+        sinks,
+        plain: args.plain,
+        redundant_styles: args.redundant_styles || config.redundant_styles || args.palette.or(config.palette).is_some(),
+        tint: args.tint_lines,
+        reload,
+    };
+
+    match &first_line {
+        Some(line) => { pipeline.print_contents(line) }
+        // a #splash: directive was still line 1
+        None if directed => {
+            if let Some(origin) = &mut pipeline.origin {
+                origin.count();
             }
-        });
-
-        for field in fields {
-            print!("{} ", field.client.bright_red());
-            print!("{} ", field.user_identifier.white());
-            print!("{} ", field.userid.white().bold());
-            print!("{} ", field.datetime.bright_magenta());
-            print!("\"{} {} {}\" ", field.method.bright_cyan(), field.request.cyan(), field.protocol.cyan());
-            print!("{} ", field.status.bright_yellow());
-            print!("{}",  field.size.bright_green());
-            println!();
         }
+        None => {}
+    }
+
+    let mut source: Box<dyn Source> = match files.len() > 1 {
+        true => { Box::new(FileSource::files(&files, once, or_exit(poll.map(plugins::poll_interval).transpose()))) }
+        false => { or_exit(registry.source(&input)) }
+    };
+    interrupt::catch();
+    let result = source.run(&mut pipeline).map_err(|e| format!("input {}: {}", source.metadata().name, e));
+
+    let status = match result {
+        Ok(()) => {
+            let held = pipeline.finish();
+
+            if args.count {
+                println!("{}", pipeline.stats.records);
+            }
+
+            match (&pipeline.until_match, pipeline.matched) {
+                _ if !held && args.slo_exit_code => { 2 }
+                // Ctrl-C is how following ends
+                _ if interrupt::interrupted() => { 0 }
+                _ if (args.quiet || args.count) && pipeline.stats.records == 0 => { 1 }
+                (Some(_), true) => {
+                    if args.bell {
+                        eprint!("\x07");
+                    }
+                    args.match_exit_code
+                }
+                (Some(_), false) => { 1 }
+                (None, _) => { 0 }
+            }
+        }
+        // as grep does, so an error isn't taken for no match
+        Err(e) if args.quiet || args.count => {
+            eprintln!("Error: {}", e);
+            2
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    };
+
+    if let Some(pager) = pager {
+        pager.wait();
+    }
+
+    if args.summary {
+        pipeline.summary();
+    }
+
+    if status != 0 {
+        std::process::exit(status);
     }
 }
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static RAW: AtomicBool = AtomicBool::new(false);
+
+// --raw-numbers: plain digits in summaries, for scripts that read them
+pub fn set_raw(raw: bool) {
+    RAW.store(raw, Ordering::Relaxed);
+}
+
+fn raw() -> bool {
+    RAW.load(Ordering::Relaxed)
+}
+
+// 1234567 as 1,234,567
+pub fn count(n: u64) -> String {
+    let digits = n.to_string();
+
+    if raw() {
+        return digits;
+    }
+
+    let mut s: String = "".to_owned();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            s.push(',');
+        }
+        s.push(c);
+    }
+
+    s
+}
+
+// 950, 1.2k, 3.4M, 5.6G
+pub fn short(n: f64) -> String {
+    match n {
+        _ if raw() && n.fract() == 0.0 => { format!("{:.0}", n) }
+        _ if raw() => { format!("{:.2}", n) }
+        _ if n >= 1_000_000_000.0 => { format!("{:.1}G", n / 1_000_000_000.0) }
+        _ if n >= 1_000_000.0 => { format!("{:.1}M", n / 1_000_000.0) }
+        _ if n >= 1_000.0 => { format!("{:.1}k", n / 1_000.0) }
+        _ if n >= 10.0 || n == 0.0 => { format!("{:.0}", n) }
+        _ => { format!("{:.1}", n) }
+    }
+}
+
+// a count with its abbreviation once it is long enough to be hard to read,
+// 12,345,678 (12.3M)
+pub fn total(n: u64) -> String {
+    match n {
+        _ if raw() || n < 1_000_000 => { count(n) }
+        _ => { format!("{} ({})", count(n), short(n as f64)) }
+    }
+}
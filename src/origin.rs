@@ -0,0 +1,49 @@
+use colored::Colorize;
+
+// Where a shown line came from, for --line-numbers and --show-source: the
+// name of its input and its line number there, like "app.log:42". Lines are
+// counted for each input separately.
+pub struct Origin {
+    line_numbers: bool,
+    show_source: bool,
+    source: String,
+    lines: Vec<(String, u64)>,
+}
+
+impl Origin {
+    pub fn new(line_numbers: bool, show_source: bool, source: &str) -> Self {
+        Origin { line_numbers, show_source, source: source.to_string(), lines: Vec::new() }
+    }
+
+    // a followed file is read from its end, after the lines already in it
+    pub fn after(mut self, lines: u64) -> Self {
+        self.lines.push((self.source.clone(), lines));
+        self
+    }
+
+    // lines that follow come from another input
+    pub fn source(&mut self, name: &str) {
+        self.source = name.to_string();
+    }
+
+    // called for every line read, shown or not
+    pub fn count(&mut self) {
+        match self.lines.iter_mut().find(|(s, _)| *s == self.source) {
+            Some((_, n)) => { *n += 1 }
+            None => { self.lines.push((self.source.clone(), 1)) }
+        }
+    }
+
+    pub fn prefix(&self) -> String {
+        let line = self.lines.iter().find(|(s, _)| *s == self.source).map(|(_, n)| *n).unwrap_or(0);
+
+        let origin = match (self.show_source, self.line_numbers) {
+            (true, true) => { format!("{}:{}", self.source, line) }
+            (true, false) => { self.source.clone() }
+            (false, true) => { format!("{:>6}", line) }
+            (false, false) => { return "".to_string() }
+        };
+
+        format!("{} ", origin.dimmed())
+    }
+}
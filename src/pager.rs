@@ -0,0 +1,49 @@
+use std::env;
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
+use std::process::{Child, Command, Stdio};
+
+// Sends everything printed through $PAGER (less by default) by pointing
+// stdout at its input. Like git, LESS defaults to FRX so colors come through
+// and output that fits on one screen is printed as it is.
+pub struct Pager {
+    child: Child,
+}
+
+impl Pager {
+    pub fn start() -> Result<Self, String> {
+        let command = env::var("PAGER").ok().filter(|p| !p.trim().is_empty()).unwrap_or("less".to_string());
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .env("LESS", env::var("LESS").unwrap_or("FRX".to_string()))
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("pager {}: {}", command, e))?;
+
+        let stdin = child.stdin.take().ok_or(format!("pager {}: no stdin", command))?;
+
+        if unsafe { libc::dup2(stdin.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            return Err(format!("pager {}: {}", command, io::Error::last_os_error()));
+        }
+
+        Ok(Pager { child })
+    }
+
+    // the pager sees the end of the output once stdout is closed, and splash
+    // stays until it's quit so the shell doesn't come back underneath it
+    pub fn wait(mut self) {
+        let _ = io::stdout().flush();
+        unsafe { libc::close(libc::STDOUT_FILENO) };
+
+        let _ = self.child.wait();
+    }
+}
+
+// a file redirected to stdin ends, unlike a pipe from a running program
+pub fn stdin_is_file() -> bool {
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+
+    unsafe { libc::fstat(libc::STDIN_FILENO, &mut stat) == 0 && (stat.st_mode & libc::S_IFMT) == libc::S_IFREG }
+}
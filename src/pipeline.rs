@@ -0,0 +1,485 @@
+use chrono::SecondsFormat;
+use colored::Colorize;
+use colored::control::SHOULD_COLORIZE;
+use regex::Regex;
+use crate::align::Align;
+use crate::buckets::{BucketFormat, Buckets, Metric};
+use crate::chain::HashChain;
+use crate::dedupe::Dedupe;
+use crate::emphasis::Emphasis;
+use crate::filter::Filter;
+use crate::interrupt;
+use crate::flow::Flow;
+use crate::gaps::{Gaps, Pauses};
+use crate::lanes::Lanes;
+use crate::numbers::count;
+use crate::origin::Origin;
+use crate::plugins::{Input, ParseResult, Plugin, Record, Sink};
+use crate::render::{ellipsize, overlay, strip_ansi, wrap};
+use crate::reload::Reload;
+use crate::replay::Replay;
+use crate::sample::Sampler;
+use crate::severity::{self, Severity, Tint};
+use crate::slo::Slo;
+use crate::stats::Stats;
+use crate::template::Template;
+use crate::terminal::{self, Fit, NARROW};
+use crate::theme;
+use crate::time::{format_duration, parse_timestamp, TimeFormat};
+use crate::title::Title;
+use crate::watchdog::Watchdog;
+
+pub struct Pipeline<'a> {
+    pub plugin: &'a dyn Plugin,
+    pub filter: Filter,
+    pub sampler: Option<Sampler>,
+    // --max-count: how many more records to show before stopping
+    pub remaining: Option<u64>,
+    // --until-match: the line to stop at, and whether it has been seen
+    pub until_match: Option<Regex>,
+    pub matched: bool,
+    pub emphasis: Emphasis,
+    pub widths: Vec<(String, usize)>,
+    pub template: Option<Template>,
+    pub align: Option<Align>,
+    pub fit: Option<Fit>,
+    pub timefmt: TimeFormat,
+    pub origin: Option<Origin>,
+    pub lanes: Option<Lanes>,
+    pub flow: Option<Flow>,
+    pub dedupe: Option<Dedupe>,
+    pub gaps: Option<Gaps>,
+    pub pauses: Option<Pauses>,
+    pub replay: Option<Replay>,
+    pub watchdog: Option<Watchdog>,
+    pub title: Option<Title>,
+    pub chain: Option<HashChain>,
+    pub stats: Stats,
+    // lines read, shown or not, for --summary
+    pub lines: u64,
+    pub slo: Option<Slo>,
+    pub buckets: Option<(Buckets, BucketFormat)>,
+    pub chart: Vec<Metric>,
+    pub sinks: Vec<Box<dyn Sink>>,
+    pub plain: bool,
+    pub redundant_styles: bool,
+    pub tint: Option<Tint>,
+    pub reload: Option<Reload>,
+}
+
+impl Pipeline<'_> {
+    pub fn print_contents(&mut self, contents: &str) {
+        self.reload();
+        self.print_stream(contents, false);
+    }
+
+    fn reload(&mut self) {
+        if let Some(settings) = self.reload.as_mut().and_then(|r| r.poll()) {
+            self.emphasis = settings.emphasis;
+            self.widths = settings.widths;
+        }
+    }
+
+    // stderr lines are marked with a red gutter
+    fn print_stream(&mut self, contents: &str, stderr: bool) {
+        if let Some(notice) = self.watchdog.as_mut().and_then(|w| w.activity()) {
+            self.emit(&notice, None);
+        }
+
+        for line in contents.lines() {
+            if self.done() {
+                break;
+            }
+
+            self.lines += 1;
+
+            if let Some(origin) = &mut self.origin {
+                origin.count();
+            }
+
+            if let Some(chain) = &mut self.chain {
+                if let Err(e) = chain.add(line) {
+                    eprintln!("Error: hash chain {}", e);
+                }
+            }
+
+            // the matching line is still shown, the next one isn't
+            if self.until_match.as_ref().is_some_and(|re| re.is_match(line)) {
+                self.matched = true;
+            }
+
+            // blank lines still reach the plugin, they can end a multi-line record
+            if !line.is_empty() && !self.filter.matches(line) {
+                continue;
+            }
+
+            // a line the mode gave up on is shown as it is
+            let parsed = match self.plugin.parse_line(line) {
+                ParseResult::NotMatched if self.plugin.failed() => { ParseResult::Parsed(Record::new(line.to_string())) }
+                parsed => { parsed }
+            };
+
+            match parsed {
+                ParseResult::Parsed(record) if record.text.is_empty() => {}
+                ParseResult::Parsed(record) if !self.filter.matches_record(&record) => {}
+                ParseResult::Parsed(_) if self.sampler.as_mut().is_some_and(|s| !s.keep()) => {}
+                ParseResult::Parsed(record) => {
+                    self.skipped();
+
+                    if let Some(remaining) = &mut self.remaining {
+                        *remaining -= 1;
+                    }
+
+                    let prefix = match &mut self.lanes {
+                        Some(lanes) => { lanes.prefix(&record) }
+                        None => { "".to_string() }
+                    };
+
+                    let prefix = match &self.origin {
+                        Some(origin) => { format!("{}{}", origin.prefix(), prefix) }
+                        None => { prefix }
+                    };
+
+                    let took = match &mut self.flow {
+                        Some(flow) => { flow.observe(line, &record) }
+                        None => { None }
+                    };
+
+                    self.stats.add(&record, took);
+
+                    if let Some(title) = &mut self.title {
+                        title.add(&record);
+                    }
+
+                    if let Some(warning) = self.gaps.as_mut().and_then(|g| g.observe(&record)) {
+                        if self.buckets.is_none() {
+                            self.emit(&warning, None);
+                        }
+                    }
+
+                    if let Some(rule) = self.pauses.as_mut().and_then(|p| p.observe(&record)) {
+                        match (self.buckets.is_some(), self.plain) {
+                            (true, _) => {}
+                            (false, true) => { self.emit(&rule, None) }
+                            (false, false) => { self.emit(&rule.dimmed().to_string(), None) }
+                        }
+                    }
+
+                    if let Some((buckets, _)) = &mut self.buckets {
+                        buckets.add(&record, took);
+                        continue;
+                    }
+
+                    // the theme's [roles] restyle fields whichever mode colored them
+                    let record = theme::current().paint_roles(self.plugin, record);
+
+                    let shown = match (self.plain, terminal::width()) {
+                        _ if self.template.is_some() && !record.fields.is_empty() => {
+                            self.template.as_ref().map(|t| t.render(&record, &self.widths)).unwrap_or_default()
+                        }
+                        (false, Some(columns)) if columns < NARROW && !record.fields.is_empty() => { stack(&record, &self.widths, columns) }
+                        _ => {
+                            let shown = shorten(&record, &self.widths);
+
+                            match &mut self.align {
+                                Some(align) => { align.apply(&shown, &record, &self.widths) }
+                                None => { shown }
+                            }
+                        }
+                    };
+
+                    let shown = match record.field("datetime").and_then(|d| Some((d, self.timefmt.apply(d)?))) {
+                        Some((datetime, shown_as)) => { shown.replacen(datetime, &shown_as, 1) }
+                        None => { shown }
+                    };
+
+                    let shown = match self.tint {
+                        Some(tint) => { tint_line(&shown, &record, tint) }
+                        None => { shown }
+                    };
+
+                    let text = match took {
+                        Some(d) => {
+                            let annotation = format!("[took {}]", format_duration(d)).bright_yellow();
+                            format!("{}{} {}", prefix, self.emphasis.apply(&shown), annotation)
+                        }
+                        None => { format!("{}{}", prefix, self.emphasis.apply(&shown)) }
+                    };
+
+                    let text = match self.redundant_styles {
+                        true => { redundant_styles(&text, &record) }
+                        false => { text }
+                    };
+
+                    let text = match stderr {
+                        true => { format!("{} {}", "▌".bright_red(), text) }
+                        false => { text }
+                    };
+
+                    let text = match self.plain {
+                        true => { plain(&text, &record) }
+                        false => { text }
+                    };
+
+                    let text = match &self.fit {
+                        Some(fit) => { fit.apply(&text) }
+                        None => { text }
+                    };
+
+                    if let Some(replay) = &mut self.replay {
+                        replay.wait(&record);
+                    }
+
+                    let released = match &mut self.dedupe {
+                        Some(dedupe) => { dedupe.add(text, record) }
+                        None => { Some((text, record, 1)) }
+                    };
+
+                    if let Some((text, record, repeats)) = released {
+                        self.emit_repeated(&text, &record, repeats);
+                    }
+                }
+                ParseResult::NotMatched | ParseResult::Incomplete => {}
+            }
+
+            self.expire();
+        }
+    }
+
+    fn emit(&mut self, text: &str, record: Option<&Record>) {
+        // a held back record came before anything else that is shown
+        if record.is_none() {
+            self.release();
+        }
+
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.write(text, record) {
+                eprintln!("Error: sink {}: {}", sink.metadata().name, e);
+            }
+        }
+    }
+
+    fn emit_repeated(&mut self, text: &str, record: &Record, repeats: usize) {
+        let annotation = format!("×{} repeated", repeats);
+
+        match (repeats, self.plain) {
+            (1, _) => { self.emit(text, Some(record)) }
+            (_, true) => { self.emit(&format!("{} {}", text, annotation), Some(record)) }
+            (_, false) => { self.emit(&format!("{} {}", text, annotation.dimmed()), Some(record)) }
+        }
+    }
+
+    // a marker where --sample or --rate-limit left lines out
+    fn skipped(&mut self) {
+        let skipped = match self.sampler.as_mut().map(|s| s.skipped()) {
+            Some(n) if n > 0 => { n }
+            _ => { return }
+        };
+
+        let marker = match skipped {
+            1 => { "… 1 line skipped".to_string() }
+            _ => { format!("… {} lines skipped", skipped) }
+        };
+
+        match self.plain {
+            true => { self.emit(&marker, None) }
+            false => { self.emit(&marker.dimmed().to_string(), None) }
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some((text, record, repeats)) = self.dedupe.as_mut().and_then(|d| d.take()) {
+            self.emit_repeated(&text, &record, repeats);
+        }
+    }
+
+    fn expire(&mut self) {
+        let warnings = match &mut self.flow {
+            Some(flow) => { flow.expire() }
+            None => { return }
+        };
+
+        for warning in warnings {
+            self.emit(&warning, None);
+        }
+    }
+
+    // called periodically while following a file that has gone quiet
+    pub fn tick(&mut self) {
+        self.release();
+        self.skipped();
+        self.expire();
+
+        if let Some(alert) = self.watchdog.as_mut().and_then(|w| w.check()) {
+            self.emit(&alert, None);
+        }
+
+        if let Some(title) = &mut self.title {
+            title.tick();
+        }
+
+        if let Err(e) = self.chain.as_mut().map_or(Ok(()), |c| c.flush()) {
+            eprintln!("Error: hash chain {}", e);
+        }
+
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.flush() {
+                eprintln!("Error: sink {}: {}", sink.metadata().name, e);
+            }
+        }
+    }
+
+    // --summary, on stderr to keep it out of what's piped on
+    pub fn summary(&self) {
+        eprintln!("{} {} lines read, {} matched, {} errors", "summary".bold(), count(self.lines), count(self.stats.records), count(self.stats.error_records));
+    }
+
+    // returns false when the input broke an SLO budget
+    pub fn finish(&mut self) -> bool {
+        self.release();
+        self.skipped();
+
+        let warnings = match &mut self.flow {
+            Some(flow) => { flow.finish() }
+            None => { Vec::new() }
+        };
+
+        for warning in warnings {
+            self.emit(&warning, None);
+        }
+
+        if let Some(title) = &mut self.title {
+            title.finish();
+        }
+
+        if let Err(e) = self.chain.as_mut().map_or(Ok(()), |c| c.flush()) {
+            eprintln!("Error: hash chain {}", e);
+        }
+
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.close() {
+                eprintln!("Error: sink {}: {}", sink.metadata().name, e);
+            }
+        }
+
+        if let Some((buckets, format)) = &self.buckets {
+            match self.chart.is_empty() {
+                true => { buckets.print(*format, self.slo.as_ref()) }
+                false => { buckets.print_chart(&self.chart) }
+            }
+        }
+
+        match &self.slo {
+            Some(slo) => { slo.report(&self.stats) }
+            None => { true }
+        }
+    }
+}
+
+// Fields longer than their width are shown with their middle cut out; the
+// record itself keeps the whole value for sinks and stats.
+fn shorten(record: &Record, widths: &[(String, usize)]) -> String {
+    let mut text = record.text.clone();
+
+    for (field, width) in widths {
+        if let Some(value) = record.field(field).filter(|v| v.chars().count() > *width) {
+            text = text.replacen(value, &ellipsize(value, *width), 1);
+        }
+    }
+
+    text
+}
+
+// A terminal too narrow for whole lines gets a record's fields one to a
+// line, under a rule, with values that don't fit carried on below.
+fn stack(record: &Record, widths: &[(String, usize)], columns: usize) -> String {
+    let key_width = record.fields.iter().map(|(k, _)| k.chars().count() + 1).max().unwrap_or(0).min(columns / 3);
+    let value_width = columns.saturating_sub(key_width + 1).max(8);
+
+    let mut lines = vec!["─".repeat(columns).dimmed().to_string()];
+
+    for (key, value) in &record.fields {
+        let value = match widths.iter().find(|(f, _)| f == key) {
+            Some((_, width)) => { ellipsize(value, *width) }
+            None => { value.clone() }
+        };
+
+        for (i, part) in wrap(&value, value_width).iter().enumerate() {
+            let label = match i {
+                0 => { format!("{}:", key) }
+                _ => { "".to_string() }
+            };
+
+            lines.push(format!("{} {}", format!("{:<width$}", label, width = key_width).dimmed(), part));
+        }
+    }
+
+    lines.join("\n")
+}
+
+// --redundant-styles: severity as a symbol, plus bold errors and underlined
+// warnings, so it never depends on telling colors apart
+fn redundant_styles(text: &str, record: &Record) -> String {
+    let severity = match severity::of(record) {
+        Some(s) => { s }
+        None => { return text.to_string() }
+    };
+
+    let text = match (severity, SHOULD_COLORIZE.should_colorize()) {
+        (Severity::Error, true) => { overlay(text, &[(0, strip_ansi(text).len())], "\x1b[1m", "\x1b[22m") }
+        (Severity::Warning, true) => { overlay(text, &[(0, strip_ansi(text).len())], "\x1b[4m", "\x1b[24m") }
+        _ => { text.to_string() }
+    };
+
+    format!("{} {}", severity.symbol(), text)
+}
+
+// --tint-lines, under --emphasize and --highlight
+fn tint_line(text: &str, record: &Record, tint: Tint) -> String {
+    match severity::of(record).and_then(|s| tint.of(s)) {
+        Some((on, off)) if SHOULD_COLORIZE.should_colorize() => { overlay(text, &[(0, strip_ansi(text).len())], on, off) }
+        _ => { text.to_string() }
+    }
+}
+
+// --plain output: no escapes, the record's timestamp as RFC 3339 and runs of
+// whitespace collapsed
+fn plain(text: &str, record: &Record) -> String {
+    let mut text = strip_ansi(text);
+
+    if let Some(datetime) = record.field("datetime") {
+        if let Some(t) = parse_timestamp(datetime) {
+            text = text.replacen(datetime, &t.to_rfc3339_opts(SecondsFormat::AutoSi, false), 1);
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+impl Input for Pipeline<'_> {
+    fn contents(&mut self, contents: &str) {
+        self.print_contents(contents);
+    }
+
+    fn stderr(&mut self, contents: &str) {
+        self.print_stream(contents, true);
+    }
+
+    fn idle(&mut self) {
+        self.reload();
+        self.tick();
+    }
+
+    fn source(&mut self, name: &str) {
+        if let Some(origin) = &mut self.origin {
+            origin.source(name);
+        }
+    }
+
+    fn done(&self) -> bool {
+        // every sink has lost its reader, so there's no one left to show lines to
+        let closed = !self.sinks.is_empty() && self.sinks.iter().all(|s| s.closed());
+
+        self.remaining == Some(0) || self.matched || interrupt::interrupted() || closed
+    }
+}
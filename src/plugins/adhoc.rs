@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use colored::{Colorize, ColoredString};
+use regex::Regex;
+use crate::render::strip_ansi;
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+static MATCHERS: LazyLock<HashMap<&'static str, Regex>> = LazyLock::new(|| {
+    let mut m = HashMap::new();
+
+    // words
+    m.insert("ip_addr", Regex::new(r".*(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}).*").unwrap());
+    m.insert("http_verb", Regex::new(r"(.*)(GET|POST|PUT|PATCH|DELETE|HEAD|CONNECT|OPTIONS|TRACE)(.*)").unwrap());
+    m.insert("http_version", Regex::new(r"HTTP/1.0").unwrap());
+    m.insert("number", Regex::new(r"^\d+$").unwrap());
+    m.insert("datetime", Regex::new(r"\d{2}/[[:alpha:]]{3}/\d{4}:\d{2}:\d{2}:\d{2}").unwrap());
+    m.insert("tz_offset", Regex::new(r"[-]?\d{4}").unwrap());
+
+    // characters
+    m.insert("quote", Regex::new("\"").unwrap());
+    m.insert("square_bracket", Regex::new(r"\[|\]").unwrap());
+
+    m
+});
+
+// the matchers that color whole words; the first to match a word colors it
+const WORD_MATCHERS: [&str; 6] = ["number", "ip_addr", "datetime", "tz_offset", "http_version", "http_verb"];
+
+const CHAR_MATCHERS: [&str; 2] = ["quote", "square_bracket"];
+
+// in the order they're tried when nothing changes it
+const ALL: [&str; 8] = ["number", "ip_addr", "datetime", "tz_offset", "http_version", "http_verb", "quote", "square_bracket"];
+
+// replaced when the config file is reloaded, leaking the old list
+static ENABLED: RwLock<Option<&'static [&'static str]>> = RwLock::new(None);
+
+// --no-matcher and the config file's [adhoc] table: matchers named in order
+// are tried first, in that order, then the rest as usual
+pub fn set_adhoc_matchers(order: &[String], disabled: &[String]) -> Result<(), String> {
+    let known = |name: &String| -> Result<&'static str, String> {
+        ALL.iter().find(|m| **m == name).copied().ok_or(format!("unknown matcher '{}', expected one of {}", name, ALL.join(", ")))
+    };
+
+    let mut enabled: Vec<&'static str> = Vec::new();
+
+    for name in order {
+        let name = known(name)?;
+        if !enabled.contains(&name) {
+            enabled.push(name);
+        }
+    }
+
+    for name in &ALL {
+        if !enabled.contains(name) {
+            enabled.push(name);
+        }
+    }
+
+    for name in disabled {
+        let name = known(name)?;
+        enabled.retain(|m| *m != name);
+    }
+
+    *ENABLED.write().unwrap() = Some(enabled.leak());
+    Ok(())
+}
+
+fn enabled() -> &'static [&'static str] {
+    match *ENABLED.read().unwrap() {
+        Some(enabled) => { enabled }
+        None => { &ALL }
+    }
+}
+
+pub struct AdHoc;
+
+impl Plugin for AdHoc {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "ad-hoc".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        ParseResult::Parsed(Record::new(highlight_line(line)))
+    }
+}
+
+fn highlight_line(line: &str) -> String {
+    let mut final_str: String = "".to_owned();
+    let hcs: String = highlight_chars(line).to_string();
+
+    for word in hcs.split_whitespace() {
+        final_str.push_str(&highlight_word(word).to_string());
+        final_str.push(' ');
+    }
+
+    final_str.trim().to_string()
+}
+
+// Each word the matchers color and the matcher that colors it, then each
+// character, for splash explain.
+pub fn fired_matchers(line: &str) -> Vec<(String, &'static str)> {
+    let mut fired: Vec<(String, &'static str)> = Vec::new();
+
+    for word in highlight_chars(line).to_string().split_whitespace() {
+        let word = strip_ansi(word);
+
+        if let Some(name) = enabled().iter().find(|m| WORD_MATCHERS.contains(m) && matcher(m).is_match(&word)) {
+            fired.push((word, name));
+        }
+    }
+
+    for c in line.chars() {
+        let c = c.to_string();
+
+        if let Some(name) = CHAR_MATCHERS.iter().find(|m| enabled().contains(m) && matcher(m).is_match(&c)) {
+            if !fired.iter().any(|(s, _)| *s == c) {
+                fired.push((c, name));
+            }
+        }
+    }
+
+    fired
+}
+
+fn matcher(name: &str) -> &Regex {
+    MATCHERS.get(name).unwrap()
+}
+
+fn highlight_word(word: &str) -> ColoredString {
+    for name in enabled() {
+        let re = matcher(name);
+        if !WORD_MATCHERS.contains(name) || !re.is_match(word) {
+            continue;
+        }
+
+        return match *name {
+            "number" => { word.bright_blue() }
+            "ip_addr" => { word.bright_red() }
+            "http_verb" => {
+                let caps = re.captures(word).unwrap();
+
+                let mut s: String = "".to_owned();
+                s.push_str(caps.get(1).unwrap().as_str());
+                s.push_str(&caps.get(2).unwrap().as_str().bright_green().to_string());
+                s.push_str(caps.get(3).unwrap().as_str());
+
+                s.normal()
+            }
+            // datetime, tz_offset and http_version
+            _ => { word.cyan() }
+        };
+    }
+
+    word.normal()
+}
+
+fn highlight_chars(line: &str) -> ColoredString {
+    let mut final_str: String = "".to_owned();
+
+    for c in line.chars() {
+        let c_str = c.to_string();
+
+        if CHAR_MATCHERS.iter().any(|m| enabled().contains(m) && matcher(m).is_match(&c_str)) {
+            final_str.push_str(&c_str.bright_white().to_string());
+        } else {
+            final_str.push_str(&c_str);
+        }
+    }
+
+    final_str.normal()
+}
@@ -0,0 +1,136 @@
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::{Captures, Regex};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// Oct 11 22:14:15 fw01 %ASA-6-302013: Built inbound TCP connection 42 for outside:1.2.3.4/443 ...
+static ASA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.*?)%((?:ASA|FTD|PIX|FWSM)(?:-\w+)?)-([0-7])-(\d{6}):\s*(.*)$").unwrap()
+});
+
+static DATETIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:<\d+>)?([A-Z][a-z]{2}\s+\d{1,2}(?:\s\d{4})?\s\d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\S+?):?(?:\s|$)").unwrap()
+});
+
+const ADDRESS: &str = r"\d{1,3}(?:\.\d{1,3}){3}|[0-9a-fA-F:]*::[0-9a-fA-F:]*|(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}";
+
+// interface:address/port, or a translated address in parentheses: (1.2.3.4/443)
+static ENDPOINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?P<mapped>\((?:{ADDRESS})/\d+\))|(?:\b(?P<interface>[A-Za-z][\w\-]*):)?\b(?P<address>{ADDRESS})(?:/(?P<port>\d+))?"
+    )).unwrap()
+});
+
+static VERB: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(Built|Teardown|Deny|Denied|Dropping|Discard|Discarded)\b").unwrap()
+});
+
+pub struct Asa;
+
+impl Plugin for Asa {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "asa".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let caps = match ASA.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        let mut s: String = "".to_owned();
+
+        if !caps[1].is_empty() {
+            s.push_str(&format!("{}", caps[1].cyan()));
+        }
+        s.push_str(&format!(
+            "{}{}{}{}{}{} ",
+            "%".dimmed(),
+            caps[2].bright_white(),
+            "-".dimmed(),
+            severity(&caps[3]),
+            "-".dimmed(),
+            format!("{}:", &caps[4]).bright_blue(),
+        ));
+
+        let mut endpoints: Vec<String> = Vec::new();
+        let message = ENDPOINT.replace_all(&caps[5], |c: &Captures| {
+            if c.name("mapped").is_some() {
+                return c[0].dimmed().to_string();
+            }
+
+            let address = match c.name("port") {
+                Some(port) => { format!("{}/{}", &c["address"], port.as_str()) }
+                None => { c["address"].to_string() }
+            };
+            let colored = match endpoints.len() {
+                0 => { address.bright_cyan() }
+                _ => { address.bright_magenta() }
+            };
+            endpoints.push(address);
+
+            match c.name("interface") {
+                Some(i) => { format!("{}{}{}", i.as_str().white(), ":".dimmed(), colored) }
+                None => { colored.to_string() }
+            }
+        }).to_string();
+
+        let verb = VERB.captures(&caps[5]).map(|c| c[1].to_string());
+        let message = match &verb {
+            Some(v) => { format!("{}{}", paint_verb(v), &message[v.len()..]) }
+            None => { message }
+        };
+        s.push_str(&message);
+
+        let mut record = Record::new(s);
+
+        if let Some(d) = DATETIME.captures(&caps[1]) {
+            record = record.with_field("datetime", &d[1]);
+        }
+
+        record = record
+            .with_typed("severity", &caps[3], FieldType::Int)
+            .with_field("message_id", &caps[4]);
+
+        if let Some(v) = &verb {
+            record = record.with_field("action", &v.to_lowercase());
+        }
+
+        let mut endpoints = endpoints.into_iter();
+        if let Some(src) = endpoints.next() {
+            record = record.with_field("src", &src);
+        }
+        if let Some(dst) = endpoints.next() {
+            record = record.with_field("dst", &dst);
+        }
+
+        ParseResult::Parsed(record.with_field("message", &caps[5]))
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "severity", "message_id", "action", "src", "dst", "message"]
+    }
+}
+
+// 0 emergencies .. 7 debugging
+fn severity(level: &str) -> ColoredString {
+    match level {
+        "0" | "1" | "2" => { level.white().on_red().bold() }
+        "3" => { level.bright_red().bold() }
+        "4" => { level.bright_yellow().bold() }
+        "5" => { level.bright_cyan().bold() }
+        "6" => { level.bright_green().bold() }
+        _ => { level.dimmed() }
+    }
+}
+
+fn paint_verb(verb: &str) -> ColoredString {
+    match verb {
+        "Built" => { verb.bright_green().bold() }
+        "Teardown" => { verb.yellow().bold() }
+        _ => { verb.bright_red().bold() }
+    }
+}
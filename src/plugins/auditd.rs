@@ -0,0 +1,124 @@
+use std::sync::LazyLock;
+use chrono::{Local, TimeZone};
+use colored::{Colorize, ColoredString};
+use regex::{Captures, Regex};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// type=SYSCALL msg=audit(1364481363.243:24287): key=value ...
+static AUDIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:node=\S+\s)?)type=(\S+)\s+msg=audit\((\d+)\.(\d+):(\d+)\):\s?(.*)$").unwrap()
+});
+
+static KEY_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([\w-]+)=("[^"]*"|'[^']*'|\S+)"#).unwrap()
+});
+
+const ID_FIELDS: [&str; 9] = ["uid", "auid", "euid", "suid", "fsuid", "gid", "egid", "sgid", "fsgid"];
+
+// auid is -1 when no login session is associated with the event
+const UNSET_ID: &str = "4294967295";
+
+pub struct Auditd;
+
+impl Plugin for Auditd {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "auditd".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let caps = match AUDIT.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::NotMatched }
+        };
+
+        let datetime = decode_timestamp(&caps[3], &caps[4]);
+
+        let s = format!(
+            "{}{} {} {} {}",
+            &caps[1],
+            record_type(&caps[2]),
+            datetime.cyan(),
+            format!(":{}", &caps[5]).dimmed(),
+            highlight_fields(&caps[6]),
+        );
+
+        let record = Record::new(s)
+            .with_field("type", &caps[2])
+            .with_field("datetime", &datetime)
+            .with_typed("serial", &caps[5], FieldType::Int);
+
+        ParseResult::Parsed(collect_fields(record, &caps[6]))
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "serial", "type"]
+    }
+}
+
+// msg='...' on user space records holds a nested key=value list
+fn nested(value: &str) -> Option<&str> {
+    value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')).filter(|v| v.contains('='))
+}
+
+fn collect_fields(mut record: Record, fields: &str) -> Record {
+    for kv in KEY_VALUE.captures_iter(fields) {
+        if let Some(inner) = nested(&kv[2]) {
+            record = collect_fields(record, inner);
+        } else if record.field(&kv[1]).is_none() {
+            record = record.with_field(&kv[1], kv[2].trim_matches('"'));
+        }
+    }
+
+    record
+}
+
+fn decode_timestamp(secs: &str, millis: &str) -> String {
+    let secs: i64 = secs.parse().unwrap_or(0);
+    let nanos: u32 = format!("{:0<9}", millis).parse().unwrap_or(0);
+
+    match Local.timestamp_opt(secs, nanos).single() {
+        Some(t) => { t.format("%Y-%m-%d %H:%M:%S%.3f").to_string() }
+        None => { format!("{}.{}", secs, millis) }
+    }
+}
+
+fn record_type(t: &str) -> ColoredString {
+    if t.starts_with("AVC") || t.starts_with("ANOM_") || t.contains("FAIL") {
+        return t.bright_red().bold();
+    }
+
+    if t.starts_with("USER_") || t.contains("AUTH") || t.starts_with("CRED_") || t == "LOGIN" {
+        return t.bright_yellow().bold();
+    }
+
+    t.bright_magenta().bold()
+}
+
+fn highlight_fields(fields: &str) -> String {
+    KEY_VALUE.replace_all(fields, |c: &Captures| {
+        let key = &c[1];
+        let value = &c[2];
+
+        if let Some(inner) = nested(value) {
+            return format!("{}{}'{}'", key.dimmed(), "=".dimmed(), highlight_fields(inner));
+        }
+
+        let value = match key {
+            "success" | "res" | "result" => {
+                match value.trim_matches('\'') {
+                    "yes" | "success" | "1" => { value.bright_green().bold() }
+                    _ => { value.bright_red().bold() }
+                }
+            }
+            "key" => { value.bright_cyan() }
+            _ if ID_FIELDS.contains(&key) && value == UNSET_ID => { value.dimmed() }
+            _ if ID_FIELDS.contains(&key) => { value.bright_yellow() }
+            _ => { value.normal() }
+        };
+
+        format!("{}{}{}", key.dimmed(), "=".dimmed(), value)
+    }).to_string()
+}
@@ -0,0 +1,115 @@
+use std::sync::LazyLock;
+use colored::Colorize;
+use regex::{Captures, Regex};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// Oct 11 22:14:15 host sshd[1234]: message
+static SYSLOG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?x)
+        ^(\p{Lu}\p{Ll}{2,4}\.?\s+\d{1,2}\s\d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\S+) # datetime
+        \s
+        (\S+)                                                                    # host
+        \s
+        ([^\s\[:]+)(?:\[(\d+)\])?:                                               # process, pid
+        \s?
+        (.*)$                                                                    # message
+        "#
+    ).unwrap()
+});
+
+static FAILURE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Failed password|Failed publickey|Invalid user|invalid user|authentication failure|incorrect password attempts|NOT in sudoers|Connection closed by authenticating user|Disconnected from invalid user|maximum authentication attempts exceeded|BREAK-IN ATTEMPT").unwrap()
+});
+
+static SUCCESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Accepted publickey|Accepted password|Accepted keyboard-interactive(?:/pam)?|session opened").unwrap()
+});
+
+static USER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(for (?:invalid )?user |for |user[= ])([\w.\-]+)").unwrap()
+});
+
+static IP_ADDR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b|\b[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{0,4}){2,7}\b").unwrap()
+});
+
+// sudo:    alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/ls
+static SUDO: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*)(\S+)(\s:\s.*COMMAND=)(.*)$").unwrap()
+});
+
+pub struct Auth;
+
+impl Plugin for Auth {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "auth".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let caps = match SYSLOG.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        let mut s: String = "".to_owned();
+
+        s.push_str(&format!("{} ", caps[1].cyan()));
+        s.push_str(&format!("{} ", caps[2].white()));
+        s.push_str(&format!("{}", caps[3].bright_magenta()));
+        if let Some(pid) = caps.get(4) {
+            s.push_str(&format!("{}{}{}", "[".bright_white(), pid.as_str().bright_blue(), "]".bright_white()));
+        }
+        s.push_str(": ");
+        s.push_str(&highlight_message(&caps[3], &caps[5]));
+
+        let mut record = Record::new(s)
+            .with_field("datetime", &caps[1])
+            .with_field("host", &caps[2])
+            .with_field("process", &caps[3]);
+
+        if let Some(pid) = caps.get(4) {
+            record = record.with_typed("pid", pid.as_str(), FieldType::Int);
+        }
+
+        ParseResult::Parsed(record.with_field("message", &caps[5]))
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "host", "process", "pid", "message"]
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        let pid = match record.field("pid") {
+            Some(pid) => { format!("[{}]", pid) }
+            None => { "".to_string() }
+        };
+
+        Some(format!(
+            "{} {} {}{}: {}",
+            record.field("datetime")?,
+            record.field("host")?,
+            record.field("process")?,
+            pid,
+            record.field("message")?,
+        ))
+    }
+}
+
+fn highlight_message(process: &str, message: &str) -> String {
+    if process == "sudo" {
+        if let Some(caps) = SUDO.captures(message) {
+            return format!("{}{}{}{}", &caps[1], caps[2].bright_yellow().bold(), &caps[3], caps[4].bright_yellow());
+        }
+    }
+
+    let mut s = USER.replace_all(message, |c: &Captures| format!("{}{}", &c[1], c[2].bold())).to_string();
+    s = FAILURE.replace_all(&s, |c: &Captures| c[0].bright_red().bold().to_string()).to_string();
+    s = SUCCESS.replace_all(&s, |c: &Captures| c[0].bright_green().bold().to_string()).to_string();
+    s = IP_ADDR.replace_all(&s, |c: &Captures| c[0].bright_red().to_string()).to_string();
+
+    s
+}
@@ -0,0 +1,186 @@
+use std::cell::Cell;
+use std::sync::LazyLock;
+use colored::Colorize;
+use regex::Regex;
+use super::{paint_status, FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// common log format
+static CLF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?x)
+        ([\d]{1,3}\.[\d]{1,3}\.[\d]{1,3}\.[\d]{1,3}  # client, IPv4
+        |[\da-fA-F]*:[\da-fA-F:.]+)                  # or IPv6
+        \s
+        (\S+)                                        # user_identifier
+        \s
+        (\S+)                                        # userid
+        \s
+        (?:(\[.*?\]))                                # datetime
+        \s
+        "([A-Z]+)\s(\S+)\s(\S+)"                     # method, request, protocol
+        \s
+        (\d{3})                                      # status
+        \s
+        (\d+|-)                                      # size
+        (?:\s"([^"]*)"\s"([^"]*)")?                   # referer, user_agent (combined)
+        (?:\s(\d+(?:\.\d+)?))?                         # response time
+        "#
+    ).unwrap()
+});
+
+struct Log<'a> {
+    client: &'a str,
+    user_identifier: &'a str,
+    userid: &'a str,
+    datetime: &'a str,
+    method: &'a str,
+    request: &'a str,
+    protocol: &'a str,
+    status: &'a str,
+    size: &'a str,
+    referer: Option<&'a str>,
+    user_agent: Option<&'a str>,
+    response_time: Option<&'a str>,
+}
+
+#[derive(Default)]
+pub struct Clf {
+    // [plugin.clf] slow_ms, response times from which on are painted red
+    slow_ms: Cell<Option<f64>>,
+}
+
+impl Plugin for Clf {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "clf".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let cap = match CLF.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::NotMatched }
+        };
+
+        let field = Log {
+            client: &cap[1],
+            user_identifier: &cap[2],
+            userid: &cap[3],
+            datetime: &cap[4],
+            method: &cap[5],
+            request: &cap[6],
+            protocol: &cap[7],
+            status: &cap[8],
+            size: &cap[9],
+            referer: cap.get(10).map(|m| m.as_str()),
+            user_agent: cap.get(11).map(|m| m.as_str()),
+            response_time: cap.get(12).map(|m| m.as_str()),
+        };
+
+        let mut record = Record::new(format_log(&field, self.slow_ms.get()))
+            .with_field("client", field.client)
+            .with_field("user_identifier", field.user_identifier)
+            .with_field("userid", field.userid)
+            .with_field("datetime", field.datetime.trim_matches(|c| c == '[' || c == ']'))
+            .with_field("method", field.method)
+            .with_field("request", field.request)
+            .with_field("protocol", field.protocol)
+            .with_typed("status", field.status, FieldType::Int)
+            .with_typed("size", field.size, FieldType::Int);
+
+        if let (Some(referer), Some(user_agent)) = (field.referer, field.user_agent) {
+            record = record
+                .with_field("referer", referer)
+                .with_field("user_agent", user_agent);
+        }
+
+        if let Some(t) = field.response_time {
+            record = record.with_typed("response_time", t, FieldType::Float);
+        }
+
+        if let Some(ms) = field.response_time.and_then(duration_ms) {
+            record = record.with_typed("duration_ms", &ms.to_string(), FieldType::Float);
+        }
+
+        ParseResult::Parsed(record)
+    }
+
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        for (key, value) in options {
+            match key.as_str() {
+                "slow_ms" => {
+                    let ms = value.as_float().or(value.as_integer().map(|n| n as f64)).ok_or("slow_ms must be a number")?;
+                    self.slow_ms.set(Some(ms));
+                }
+                _ => { return Err(format!("unknown option '{}', expected slow_ms", key)) }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["client", "user_identifier", "userid", "datetime", "method", "request", "protocol", "status", "size", "referer", "user_agent", "response_time", "duration_ms"]
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        let mut s = format!(
+            "{} {} {} [{}] \"{} {} {}\" {} {}",
+            record.field("client")?,
+            record.field("user_identifier")?,
+            record.field("userid")?,
+            record.field("datetime")?,
+            record.field("method")?,
+            record.field("request")?,
+            record.field("protocol")?,
+            record.field("status")?,
+            record.field("size")?,
+        );
+
+        if let (Some(referer), Some(user_agent)) = (record.field("referer"), record.field("user_agent")) {
+            s.push_str(&format!(" \"{}\" \"{}\"", referer, user_agent));
+        }
+
+        if let Some(t) = record.field("response_time") {
+            s.push_str(&format!(" {}", t));
+        }
+
+        Some(s)
+    }
+}
+
+fn format_log(field: &Log, slow_ms: Option<f64>) -> String {
+    let mut s: String = "".to_owned();
+
+    s.push_str(&format!("{} ", field.client.bright_red()));
+    s.push_str(&format!("{} ", field.user_identifier.white()));
+    s.push_str(&format!("{} ", field.userid.white().bold()));
+    s.push_str(&format!("{} ", field.datetime.bright_magenta()));
+    s.push_str(&format!("\"{} {} {}\" ", field.method.bright_cyan(), field.request.cyan(), field.protocol.cyan()));
+    s.push_str(&format!("{} ", paint_status(field.status)));
+    s.push_str(&format!("{}",  field.size.bright_green()));
+
+    if let (Some(referer), Some(user_agent)) = (field.referer, field.user_agent) {
+        s.push_str(&format!(" \"{}\" \"{}\"", referer.white(), user_agent.dimmed()));
+    }
+
+    if let Some(t) = field.response_time {
+        match (duration_ms(t), slow_ms) {
+            (Some(ms), Some(slow)) if ms >= slow => { s.push_str(&format!(" {}", t.bright_red().bold())) }
+            _ => { s.push_str(&format!(" {}", t.bright_blue())) }
+        }
+    }
+
+    s
+}
+
+// nginx logs $request_time in seconds with a fraction, apache logs %D in microseconds
+fn duration_ms(t: &str) -> Option<f64> {
+    let n: f64 = t.parse().ok()?;
+
+    match t.contains('.') {
+        true => { Some(n * 1000.0) }
+        false => { Some(n / 1000.0) }
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use regex::Regex;
+use crate::theme;
+use super::guarded;
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+struct Definition {
+    pattern: Regex,
+    detect: Option<Regex>,
+    styles: Vec<(String, String)>,
+    types: Vec<(String, FieldType)>,
+    // the group names, interned to be listed like the built-in modes' fields
+    fields: Vec<&'static str>,
+}
+
+// every group name a definition has had, kept for the session so that
+// loading a file again doesn't leak its names again
+static NAMES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+// A format declared in a NAME.toml in a plugin directory rather than written
+// as a program: a regex whose named groups become fields, styled by the
+// theme or the file's [styles] table.
+//
+//     name = "myapp"
+//     detect = '^\d{4}-\d{2}-\d{2} '
+//     pattern = '^(?P<datetime>\S+ \S+) (?P<level>[A-Z]+) (?P<message>.*)$'
+//
+//     [styles]
+//     datetime = "cyan"
+//     level = "bold yellow"
+//
+//     [types]
+//     status = "int"
+//
+// Lines the pattern doesn't match are shown as they are. A file that doesn't
+// load is reported when its mode is used, not whenever splash starts, as a
+// failure for the guard around it.
+pub struct Declared {
+    name: String,
+    definition: Result<Definition, String>,
+}
+
+impl Declared {
+    pub fn load(path: &Path) -> Self {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+
+        let table = fs::read_to_string(path).map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str::<toml::Table>(&contents).map_err(|e| e.to_string()));
+
+        let name = match &table {
+            Ok(t) => { t.get("name").and_then(|n| n.as_str()).map(String::from).unwrap_or(stem) }
+            Err(_) => { stem }
+        };
+
+        let definition = table.and_then(|t| definition(&t)).map_err(|e| format!("{}: {}", path.display(), e));
+
+        Declared { name, definition }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // why the file doesn't load, if it doesn't
+    pub fn error(&self) -> Option<&str> {
+        self.definition.as_ref().err().map(String::as_str)
+    }
+
+    fn definition(&self) -> &Definition {
+        match &self.definition {
+            Ok(d) => { d }
+            Err(e) => { guarded::broken(e.clone()) }
+        }
+    }
+}
+
+fn definition(table: &toml::Table) -> Result<Definition, String> {
+    let regex = |key: &str| -> Result<Option<Regex>, String> {
+        match table.get(key) {
+            Some(value) => {
+                let pattern = value.as_str().ok_or(format!("{} must be a string", key))?;
+                Regex::new(pattern).map(Some).map_err(|e| format!("invalid {}: {}", key, e))
+            }
+            None => { Ok(None) }
+        }
+    };
+
+    let pattern = regex("pattern")?.ok_or("no pattern, expected a regex with named groups like (?P<level>\\w+)")?;
+
+    if pattern.capture_names().flatten().next().is_none() {
+        return Err("the pattern has no named groups, e.g. (?P<level>\\w+)".to_string());
+    }
+
+    let fields = pattern.capture_names().flatten().map(intern).collect();
+
+    let types = match table.get("types") {
+        Some(toml::Value::Table(types)) => {
+            types.iter().map(|(field, kind)| {
+                let kind = kind.as_str().ok_or(format!("types.{} must be a string", field))?;
+                FieldType::parse(kind).map(|k| (field.clone(), k)).map_err(|e| format!("types.{}: {}", field, e))
+            }).collect::<Result<_, String>>()?
+        }
+        Some(_) => { return Err("types must be a table".to_string()) }
+        None => { Vec::new() }
+    };
+
+    Ok(Definition { pattern, detect: regex("detect")?, styles: theme::styles(table)?, types, fields })
+}
+
+impl Plugin for Declared {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: self.definition.as_ref().map(|d| d.styles.clone()).unwrap_or_default(),
+        }
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        self.definition.as_ref().map(|d| d.fields.clone()).unwrap_or_default()
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let definition = self.definition();
+
+        let caps = match definition.pattern.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        // the groups that took part, in the order they appear in the line;
+        // one nested in another is a field but takes the outer one's style
+        let mut groups: Vec<(&str, regex::Match)> = definition.pattern.capture_names().flatten()
+            .filter_map(|name| Some((name, caps.name(name)?)))
+            .collect();
+        groups.sort_by_key(|(_, m)| (m.start(), std::cmp::Reverse(m.end())));
+
+        let mut text: String = "".to_owned();
+        let mut pos = 0;
+
+        for (name, m) in &groups {
+            if m.start() < pos {
+                continue;
+            }
+
+            text.push_str(&line[pos..m.start()]);
+
+            match theme::current().style(&self.name, name, &definition.styles) {
+                Some(style) => { text.push_str(&theme::paint(m.as_str(), style)) }
+                None => { text.push_str(m.as_str()) }
+            }
+
+            pos = m.end();
+        }
+
+        text.push_str(&line[pos..]);
+
+        let record = groups.iter().fold(Record::new(text), |record, (name, m)| {
+            match definition.types.iter().find(|(field, _)| field == name) {
+                Some((_, kind)) => { record.with_typed(name, m.as_str(), *kind) }
+                None => { record.with_field(name, m.as_str()) }
+            }
+        });
+
+        ParseResult::Parsed(record)
+    }
+
+    // lines matching the detect pattern, or the pattern itself without one
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        let definition = match &self.definition {
+            Ok(d) => { d }
+            Err(_) => { return 0.0 }
+        };
+
+        let detect = definition.detect.as_ref().unwrap_or(&definition.pattern);
+        let sampled: Vec<&&str> = lines.iter().filter(|l| !l.trim().is_empty()).collect();
+
+        match sampled.len() {
+            0 => { 0.0 }
+            n => { sampled.iter().filter(|l| detect.is_match(l)).count() as f64 / n as f64 }
+        }
+    }
+}
+
+fn intern(name: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap();
+
+    match names.get(name) {
+        Some(&interned) => { interned }
+        None => {
+            let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
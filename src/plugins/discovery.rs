@@ -0,0 +1,180 @@
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use super::declared::Declared;
+use super::external::External;
+use super::guarded::Guarded;
+use super::scripted::Scripted;
+use super::sinks;
+use super::sources;
+use super::registry::Discovered;
+use super::watched::Watched;
+use super::{Plugin, PluginRegistry};
+
+pub const FORMAT_PREFIX: &str = "splash-format-";
+pub const SINK_PREFIX: &str = "splash-sink-";
+pub const SOURCE_PREFIX: &str = "splash-source-";
+
+// Finds external plugins, executables named splash-format-<name>,
+// splash-sink-<name> or splash-source-<name>, formats declared in
+// <name>.toml files and formats scripted in <name>.rhai files, in
+// $SPLASH_PLUGIN_PATH and ~/.splash/plugins.
+pub struct PluginDiscovery {
+    dirs: Vec<PathBuf>,
+}
+
+impl Default for PluginDiscovery {
+    fn default() -> Self {
+        PluginDiscovery::new()
+    }
+}
+
+impl PluginDiscovery {
+    pub fn new() -> Self {
+        let mut dirs: Vec<PathBuf> = match env::var_os("SPLASH_PLUGIN_PATH") {
+            Some(paths) => { env::split_paths(&paths).collect() }
+            None => { Vec::new() }
+        };
+
+        dirs.extend(PluginDiscovery::user_dir());
+
+        PluginDiscovery { dirs }
+    }
+
+    // ~/.splash/plugins, where splash plugins install puts what it installs
+    pub fn user_dir() -> Option<PathBuf> {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".splash").join("plugins"))
+    }
+
+    pub fn discover(&self, registry: &mut PluginRegistry) {
+        for (name, path) in self.executables() {
+            let (version, author) = about(&path.with_extension("toml"));
+
+            if let Some(mode) = name.strip_prefix(FORMAT_PREFIX) {
+                if registry.get(mode).is_none() {
+                    match External::new(mode, path.clone()) {
+                        Ok(external) => {
+                            registry.note_discovered(Discovered { kind: "mode", name: mode.to_string(), path, version, author });
+                            registry.register(Box::new(Guarded::new(Box::new(external))));
+                        }
+                        Err(e) => { eprintln!("Error: plugin {} not loaded: {}", mode, e) }
+                    }
+                }
+            } else if let Some(sink) = name.strip_prefix(SINK_PREFIX) {
+                let sink = sink.to_string();
+
+                if !registry.has_sink(&sink) {
+                    registry.note_discovered(Discovered { kind: "sink", name: sink.clone(), path: path.clone(), version, author });
+                    let name = sink.clone();
+                    registry.register_sink(&sink, Box::new(move |target| {
+                        Ok(Box::new(sinks::External::spawn(&name, &path, target)?))
+                    }));
+                }
+            } else if let Some(source) = name.strip_prefix(SOURCE_PREFIX) {
+                let source = source.to_string();
+
+                if !registry.has_source(&source) {
+                    registry.note_discovered(Discovered { kind: "input", name: source.clone(), path: path.clone(), version, author });
+                    let name = source.clone();
+                    registry.register_source(&source, Box::new(move |target| {
+                        Ok(Box::new(sources::External::spawn(&name, &path, target)?))
+                    }));
+                }
+            }
+        }
+
+        for path in self.definitions() {
+            let declared = Declared::load(&path);
+
+            if registry.get(declared.name()).is_none() {
+                let (version, author) = about(&path);
+                registry.note_discovered(Discovered { kind: "mode", name: declared.name().to_string(), path: path.clone(), version, author });
+                registry.register(Box::new(Guarded::new(Box::new(Watched::new(path, Box::new(declared), reload_declared)))));
+            }
+        }
+
+        for path in self.scripts() {
+            let scripted = Scripted::load(&path);
+
+            if registry.get(scripted.name()).is_none() {
+                let (version, author) = scripted.about();
+                registry.note_discovered(Discovered { kind: "mode", name: scripted.name().to_string(), path: path.clone(), version, author });
+                registry.register(Box::new(Guarded::new(Box::new(Watched::new(path, Box::new(scripted), reload_scripted)))));
+            }
+        }
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    fn executables(&self) -> Vec<(String, PathBuf)> {
+        let mut found = Vec::new();
+
+        for path in self.entries() {
+            let executable = fs::metadata(&path).map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0).unwrap_or(false);
+
+            if let (true, Some(name)) = (executable, path.file_name().and_then(|n| n.to_str())) {
+                found.push((name.to_string(), path.clone()));
+            }
+        }
+
+        found
+    }
+
+    // the .toml files that aren't the manifest of a splash-format-<name>
+    fn definitions(&self) -> Vec<PathBuf> {
+        self.entries().into_iter()
+            .filter(|p| p.extension().is_some_and(|e| e == "toml") && p.is_file())
+            .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| !n.starts_with("splash-")))
+            .collect()
+    }
+
+    fn scripts(&self) -> Vec<PathBuf> {
+        self.entries().into_iter().filter(|p| p.extension().is_some_and(|e| e == "rhai") && p.is_file()).collect()
+    }
+
+    fn entries(&self) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+
+        for dir in &self.dirs {
+            let entries = match fs::read_dir(dir) {
+                Ok(e) => { e }
+                Err(_) => { continue }
+            };
+
+            let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+            entries.sort();
+            found.extend(entries);
+        }
+
+        found
+    }
+}
+
+// the version and author a plugin's TOML file gives, if it has them
+pub fn about(path: &Path) -> (Option<String>, Option<String>) {
+    let table = fs::read_to_string(path).ok().and_then(|c| toml::from_str::<toml::Table>(&c).ok()).unwrap_or_default();
+    let text = |key: &str| table.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    (text("version"), text("author"))
+}
+
+fn reload_declared(path: &Path) -> Result<Box<dyn Plugin>, String> {
+    let declared = Declared::load(path);
+
+    match declared.error() {
+        Some(e) => { Err(e.to_string()) }
+        None => { Ok(Box::new(declared)) }
+    }
+}
+
+fn reload_scripted(path: &Path) -> Result<Box<dyn Plugin>, String> {
+    let scripted = Scripted::load(path);
+
+    match scripted.error() {
+        Some(e) => { Err(e.to_string()) }
+        None => { Ok(Box::new(scripted)) }
+    }
+}
@@ -0,0 +1,178 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+use regex::{Captures, Regex};
+use crate::theme;
+use super::guarded::{self, BUDGET};
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// how long it has to answer its first line, which starts it
+const STARTUP: Duration = Duration::from_secs(5);
+
+// {{tag:text}} marks a span of an answer as a semantic tag
+static TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{([a-z_][a-z0-9_]*):(.*?)\}\}").unwrap()
+});
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    // its answers, read as they come so that one too slow can be given up on
+    answers: Receiver<io::Result<String>>,
+    answered: bool,
+}
+
+// A discovered splash-format-<name> executable. Each raw line is written to
+// its stdin and it answers with one line of (colored) output, or an empty
+// line when it does not recognize the input. Started on first use.
+//
+// Spans of the answer marked {{tag:text}} become record fields and are
+// styled by the theme, or by the [styles] suggested in a
+// splash-format-<name>.toml manifest next to the executable. One whose
+// manifest has styles that don't parse isn't loaded.
+//
+// One that doesn't answer within the budget, or exits, is stopped and started
+// again for the next line, and the line left to the guard around it.
+//
+// The options of a [plugin.NAME] section are passed to it as JSON in
+// $SPLASH_PLUGIN_CONFIG.
+pub struct External {
+    name: String,
+    path: PathBuf,
+    styles: Vec<(String, String)>,
+    options: RefCell<Option<String>>,
+    process: RefCell<Option<Process>>,
+}
+
+impl External {
+    pub fn new(name: &str, path: PathBuf) -> Result<Self, String> {
+        let styles = manifest_styles(&path.with_extension("toml"))?;
+
+        Ok(External { name: name.to_string(), path, styles, options: RefCell::new(None), process: RefCell::new(None) })
+    }
+
+    fn spawn(&self) -> Result<Process, String> {
+        let mut child = Command::new(&self.path)
+            .envs(self.options.borrow().as_ref().map(|o| ("SPLASH_PLUGIN_CONFIG", o)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        let stdin = child.stdin.take().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let (tx, answers) = mpsc::channel();
+
+        thread::spawn(move || {
+            loop {
+                let mut answer: String = "".to_owned();
+                let read = stdout.read_line(&mut answer).map(|_| answer);
+                let done = !matches!(&read, Ok(a) if !a.is_empty());
+
+                if tx.send(read).is_err() || done {
+                    break;
+                }
+            }
+        });
+
+        Ok(Process { child, stdin, answers, answered: false })
+    }
+
+    fn exchange(&self, line: &str) -> Result<String, String> {
+        let mut process = self.process.borrow_mut();
+
+        if process.is_none() {
+            *process = Some(self.spawn()?);
+        }
+
+        let p = process.as_mut().unwrap();
+        let timeout = if p.answered { BUDGET } else { STARTUP };
+
+        let answer = writeln!(p.stdin, "{}", line).and_then(|_| p.stdin.flush()).map_err(|e| e.to_string())
+            .and_then(|_| {
+                match p.answers.recv_timeout(timeout) {
+                    Ok(Ok(answer)) if answer.is_empty() => { Err("exited".to_string()) }
+                    Ok(Ok(answer)) => { Ok(answer) }
+                    Ok(Err(e)) => { Err(e.to_string()) }
+                    Err(_) => { Err(format!("didn't answer within {}ms and was stopped", timeout.as_millis())) }
+                }
+            });
+
+        match answer {
+            Ok(answer) => {
+                p.answered = true;
+                Ok(answer.trim_end_matches(['\r', '\n']).to_string())
+            }
+            Err(e) => {
+                // started again for the next line, so answers stay in step
+                let _ = p.child.kill();
+                let _ = p.child.wait();
+                *process = None;
+                Err(e)
+            }
+        }
+    }
+
+    fn tagged(&self, answer: &str, styles: &[(String, String)]) -> Record {
+        let mut fields: Vec<(String, String)> = Vec::new();
+
+        let text = TAG.replace_all(answer, |c: &Captures| {
+            fields.push((c[1].to_string(), c[2].to_string()));
+
+            match theme::current().style(&self.name, &c[1], styles) {
+                Some(style) => { theme::paint(&c[2], style) }
+                None => { c[2].to_string() }
+            }
+        }).to_string();
+
+        let mut record = Record::new(text);
+        for (tag, value) in fields {
+            record = record.with_field(&tag, &value);
+        }
+
+        record
+    }
+}
+
+impl Plugin for External {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: self.styles.clone(),
+        }
+    }
+
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        let json = serde_json::to_string(options).map_err(|e| e.to_string())?;
+        self.options.replace(Some(json));
+
+        Ok(())
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        match self.exchange(line) {
+            Ok(answer) if answer.is_empty() => { ParseResult::NotMatched }
+            Ok(answer) => { ParseResult::Parsed(self.tagged(&answer, &self.styles)) }
+            Err(e) => { guarded::fail(e) }
+        }
+    }
+}
+
+// suggested styles from the plugin's manifest, if it has one
+fn manifest_styles(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => { c }
+        Err(_) => { return Ok(Vec::new()) }
+    };
+
+    toml::from_str::<toml::Table>(&contents)
+        .map_err(|e| e.to_string())
+        .and_then(|manifest| theme::styles(&manifest))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
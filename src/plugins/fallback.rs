@@ -0,0 +1,117 @@
+use std::cell::Cell;
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// A chain of modes, like --mode clf,ad-hoc or a [chains] entry, for files
+// that mix formats. Each line goes to the first mode whose record has fields.
+// One none of them has fields for, like a stack frame, is shown by the mode
+// of the record before it if that one parses it, or else by the last mode to
+// parse it, so a catch-all like ad-hoc goes at the end. A mode that starts a
+// multi-line record gets the lines that follow until it's whole.
+pub struct Fallback<'a> {
+    name: String,
+    plugins: Vec<&'a dyn Plugin>,
+    // the mode in the middle of a multi-line record
+    pending: Cell<Option<usize>>,
+    // the mode that parsed the last record
+    last: Cell<usize>,
+    // whether a mode gave up on the line and none took it
+    failed: Cell<bool>,
+}
+
+impl<'a> Fallback<'a> {
+    pub fn new(name: &str, plugins: Vec<&'a dyn Plugin>) -> Self {
+        Fallback { name: name.to_string(), plugins, pending: Cell::new(None), last: Cell::new(0), failed: Cell::new(false) }
+    }
+
+    // the mode itself when the chain has only one
+    pub fn plugin(&self) -> &dyn Plugin {
+        match self.plugins.as_slice() {
+            [one] => { *one }
+            _ => { self }
+        }
+    }
+}
+
+impl Plugin for Fallback<'_> {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: self.plugins.iter().flat_map(|p| p.metadata().styles).collect(),
+        }
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        let mut fields: Vec<&'static str> = Vec::new();
+
+        for field in self.plugins.iter().flat_map(|p| p.fields()) {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+
+        fields
+    }
+
+    fn failed(&self) -> bool {
+        self.failed.get()
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        self.failed.set(false);
+
+        if let Some(i) = self.pending.take() {
+            match self.plugins[i].parse_line(line) {
+                ParseResult::Parsed(record) => {
+                    self.last.set(i);
+                    return ParseResult::Parsed(record);
+                }
+                ParseResult::Incomplete => {
+                    self.pending.set(Some(i));
+                    return ParseResult::Incomplete;
+                }
+                ParseResult::NotMatched => {}
+            }
+        }
+
+        let mut shown: Option<(usize, Record)> = None;
+
+        for (i, plugin) in self.plugins.iter().enumerate() {
+            match plugin.parse_line(line) {
+                ParseResult::Parsed(record) if !record.fields.is_empty() => {
+                    self.last.set(i);
+                    return ParseResult::Parsed(record);
+                }
+                ParseResult::Parsed(record) => {
+                    if !matches!(shown, Some((s, _)) if s == self.last.get()) {
+                        shown = Some((i, record));
+                    }
+                }
+                ParseResult::Incomplete => {
+                    self.pending.set(Some(i));
+                    return ParseResult::Incomplete;
+                }
+                ParseResult::NotMatched => {
+                    if plugin.failed() {
+                        self.failed.set(true);
+                    }
+                }
+            }
+        }
+
+        match shown {
+            Some((i, record)) => {
+                self.last.set(i);
+                ParseResult::Parsed(record)
+            }
+            None => { ParseResult::NotMatched }
+        }
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        self.plugins[self.last.get()].serialize(record)
+    }
+
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        self.plugins.iter().map(|p| p.detect_format(lines)).fold(0.0, f64::max)
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::{Captures, Regex};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// ... kernel: [12345.678] [UFW BLOCK] IN=eth0 OUT= ... SRC=1.2.3.4 DST=5.6.7.8 ...
+static NETFILTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:.*?kernel:\s*)?(?:\[\s*\d+\.\d+\]\s*)?)(.*?)\s*(IN=\S*\s+OUT=.*)$").unwrap()
+});
+
+static KEY_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b([A-Z]+)=(\S*)").unwrap()
+});
+
+// the fields that are counts, ports and lengths
+const NUMBERS: [&str; 7] = ["len", "ttl", "id", "spt", "dpt", "window", "urgp"];
+
+static BLOCKED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)block|drop|reject|deny|invalid").unwrap()
+});
+
+static ALLOWED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)allow|accept|pass").unwrap()
+});
+
+#[derive(Clone, Copy)]
+enum Action {
+    Blocked,
+    Allowed,
+    Other,
+}
+
+pub struct Firewall;
+
+impl Plugin for Firewall {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "firewall".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let caps = match NETFILTER.captures(line) {
+            Some(c) => { c }
+            None => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        let prefix = caps[2].trim();
+        let action = if BLOCKED.is_match(prefix) {
+            Action::Blocked
+        } else if ALLOWED.is_match(prefix) {
+            Action::Allowed
+        } else {
+            Action::Other
+        };
+
+        let mut s: String = caps[1].dimmed().to_string();
+        if !prefix.is_empty() {
+            s.push_str(&format!("{} ", paint(prefix, action).bold()));
+        }
+        s.push_str(&highlight_fields(&caps[3], action));
+
+        let mut record = Record::new(s).with_field("action", prefix.trim_matches(|c| c == '[' || c == ']'));
+        for kv in KEY_VALUE.captures_iter(&caps[3]) {
+            if record.field(&kv[1].to_lowercase()).is_none() {
+                let name = kv[1].to_lowercase();
+
+                record = match NUMBERS.contains(&name.as_str()) {
+                    true => { record.with_typed(&name, &kv[2], FieldType::Int) }
+                    false => { record.with_field(&name, &kv[2]) }
+                };
+            }
+        }
+
+        ParseResult::Parsed(record)
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["action", "in", "out", "src", "dst", "proto", "spt", "dpt"]
+    }
+}
+
+fn paint(s: &str, action: Action) -> ColoredString {
+    match action {
+        Action::Blocked => { s.bright_red() }
+        Action::Allowed => { s.bright_green() }
+        Action::Other => { s.bright_yellow() }
+    }
+}
+
+fn highlight_fields(fields: &str, action: Action) -> String {
+    KEY_VALUE.replace_all(fields, |c: &Captures| {
+        let value = match &c[1] {
+            "SRC" | "DST" => { paint(&c[2], action) }
+            "SPT" | "DPT" => { c[2].bright_blue() }
+            "PROTO" => { c[2].bright_magenta() }
+            "IN" | "OUT" => { c[2].white() }
+            _ => { c[2].normal() }
+        };
+
+        let key = match &c[1] {
+            "SRC" | "DST" | "SPT" | "DPT" | "PROTO" => { c[1].white() }
+            _ => { c[1].dimmed() }
+        };
+
+        format!("{}{}{}", key, "=".dimmed(), value)
+    }).to_string()
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Local};
+use colored::{Colorize, ColoredString};
+use serde_json::{Map, Value};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// Graylog Extended Log Format, one JSON message per line:
+// {"version":"1.1","host":"web1","short_message":"...","timestamp":1700000000.5,"level":3,"_user":"alice"}
+pub struct Gelf;
+
+impl Plugin for Gelf {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "gelf".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let message: Map<String, Value> = match serde_json::from_str(line) {
+            Ok(Value::Object(m)) if m.contains_key("short_message") => { m }
+            _ => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        let mut s: String = "".to_owned();
+        let mut record_fields: Vec<(String, String, FieldType)> = Vec::new();
+
+        let datetime = message.get("timestamp")
+            .and_then(|t| t.as_f64())
+            .and_then(|t| DateTime::from_timestamp_micros((t * 1_000_000.0) as i64));
+
+        if let Some(t) = datetime {
+            s.push_str(&format!("{} ", t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string().cyan()));
+            record_fields.push(("datetime".to_string(), t.to_rfc3339(), FieldType::Text));
+        }
+
+        if let Some(host) = message.get("host").map(text) {
+            s.push_str(&format!("{} ", host.white()));
+            record_fields.push(("host".to_string(), host, FieldType::Text));
+        }
+
+        let level = message.get("level").and_then(|l| l.as_u64());
+        if let Some(level) = level {
+            let name = level_name(level);
+            s.push_str(&format!("{} ", paint_level(name, level)));
+            record_fields.push(("level".to_string(), name.to_string(), FieldType::Text));
+        }
+
+        let short_message = message.get("short_message").map(text).unwrap_or_default();
+        s.push_str(&match level {
+            Some(0..=3) => { short_message.bright_red().to_string() }
+            Some(4) => { short_message.bright_yellow().to_string() }
+            _ => { short_message.clone() }
+        });
+        record_fields.push(("message".to_string(), short_message, FieldType::Text));
+
+        for (key, value) in &message {
+            let name = match key.strip_prefix('_') {
+                Some(n) if n != "id" => { n }
+                _ => { continue }
+            };
+            let kind = kind(value);
+            let value = text(value);
+
+            s.push_str(&format!(" {}{}{}", name.dimmed(), "=".dimmed(), value.bright_blue()));
+            record_fields.push((name.to_string(), value, kind));
+        }
+
+        if let Some(full) = message.get("full_message").map(text) {
+            record_fields.push(("full_message".to_string(), full, FieldType::Text));
+        }
+
+        let mut record = Record::new(s);
+        for (name, value, kind) in record_fields {
+            record = record.with_typed(&name, &value, kind);
+        }
+
+        ParseResult::Parsed(record)
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "host", "level", "message", "full_message"]
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => { s.clone() }
+        other => { other.to_string() }
+    }
+}
+
+// additional fields keep the type they have in the JSON
+fn kind(value: &Value) -> FieldType {
+    match value {
+        Value::Number(n) if n.is_i64() => { FieldType::Int }
+        Value::Number(_) => { FieldType::Float }
+        Value::Bool(_) => { FieldType::Bool }
+        _ => { FieldType::Text }
+    }
+}
+
+fn level_name(level: u64) -> &'static str {
+    match level {
+        0 => { "EMERG" }
+        1 => { "ALERT" }
+        2 => { "CRIT" }
+        3 => { "ERROR" }
+        4 => { "WARN" }
+        5 => { "NOTICE" }
+        6 => { "INFO" }
+        _ => { "DEBUG" }
+    }
+}
+
+fn paint_level(name: &str, level: u64) -> ColoredString {
+    match level {
+        0..=2 => { name.white().on_red().bold() }
+        3 => { name.bright_red().bold() }
+        4 => { name.bright_yellow().bold() }
+        5 => { name.bright_cyan() }
+        6 => { name.bright_green() }
+        _ => { name.dimmed() }
+    }
+}
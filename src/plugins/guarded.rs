@@ -0,0 +1,155 @@
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// how long a discovered mode may take over a line
+pub const BUDGET: Duration = Duration::from_millis(250);
+
+// how many failures a mode gets before it's turned off
+const STRIKES: usize = 3;
+
+thread_local! {
+    // whether a guarded call is running, when a panic is the guard's to report
+    static GUARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+// a failure a plugin gives up a line with, for the guard to catch, and
+// whether it gives up on every line to come
+struct Failure(String, bool);
+
+// Gives up on the line being parsed, for the guard around the mode to report
+// instead of ending splash.
+pub fn fail(message: String) -> ! {
+    panic::panic_any(Failure(message, false))
+}
+
+// Gives up on the mode, one that didn't load, which the guard turns off
+// rather than have it fail the same way on every line.
+pub fn broken(message: String) -> ! {
+    panic::panic_any(Failure(message, true))
+}
+
+// A discovered mode, which splash can't vouch for, run so that it can't take
+// the stream down with it. A line it panics or fails on, or takes longer than
+// BUDGET over, is reported on stderr; a line it got no answer for is given
+// up on, for the next mode of a chain to take or to be shown as it is. After
+// STRIKES of those, or at once for a mode that didn't load, the mode is
+// turned off for the rest of the session and gives up on every line. The
+// first line doesn't count against the budget, since it starts an executable.
+pub struct Guarded {
+    name: String,
+    plugin: Box<dyn Plugin>,
+    strikes: Cell<usize>,
+    started: Cell<bool>,
+    failed: Cell<bool>,
+}
+
+impl Guarded {
+    pub fn new(plugin: Box<dyn Plugin>) -> Self {
+        Guarded { name: plugin.metadata().name, plugin, strikes: Cell::new(0), started: Cell::new(false), failed: Cell::new(false) }
+    }
+
+    fn disabled(&self) -> bool {
+        self.strikes.get() >= STRIKES
+    }
+
+    fn strike(&self, e: &str, out: bool) {
+        self.strikes.set(if out { STRIKES } else { self.strikes.get() + 1 });
+        eprintln!("Error: plugin {}: {}", self.name, e);
+
+        match (self.disabled(), out) {
+            (true, true) => { eprintln!("Error: plugin {} disabled", self.name) }
+            (true, false) => { eprintln!("Error: plugin {} disabled after {} failures", self.name, STRIKES) }
+            (false, _) => {}
+        }
+    }
+}
+
+impl Plugin for Guarded {
+    fn metadata(&self) -> PluginMetadata {
+        self.plugin.metadata()
+    }
+
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        self.plugin.configure(options)
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        self.plugin.fields()
+    }
+
+    fn failed(&self) -> bool {
+        self.failed.get()
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        self.failed.set(self.disabled());
+
+        if self.failed.get() {
+            return ParseResult::NotMatched;
+        }
+
+        let started = Instant::now();
+        let parsed = guard(|| self.plugin.parse_line(line));
+        let took = started.elapsed();
+
+        match parsed {
+            Ok(parsed) => {
+                if self.started.replace(true) && took > BUDGET {
+                    self.strike(&format!("took {}ms over a line, the budget is {}ms", took.as_millis(), BUDGET.as_millis()), false);
+                }
+
+                parsed
+            }
+            Err((e, out)) => {
+                self.started.set(true);
+                self.failed.set(true);
+                self.strike(&e, out);
+                ParseResult::NotMatched
+            }
+        }
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        guard(|| self.plugin.serialize(record)).ok().flatten()
+    }
+
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        guard(|| self.plugin.detect_format(lines)).unwrap_or(0.0)
+    }
+}
+
+// runs a plugin call, turning a panic in it into the reason it gave and
+// whether the mode is done for
+fn guard<T>(call: impl FnOnce() -> T) -> Result<T, (String, bool)> {
+    static QUIET: Once = Once::new();
+
+    // the guard reports what it catches, so the panic itself isn't printed
+    QUIET.call_once(|| {
+        let report = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !GUARDING.with(Cell::get) {
+                report(info);
+            }
+        }));
+    });
+
+    let outer = GUARDING.with(|g| g.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(call));
+    GUARDING.with(|g| g.set(outer));
+
+    result.map_err(|payload| {
+        match payload.downcast::<Failure>() {
+            Ok(failure) => { (failure.0, failure.1) }
+            Err(payload) => {
+                match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+                    (Some(s), _) => { (format!("panicked: {}", s), false) }
+                    (_, Some(s)) => { (format!("panicked: {}", s), false) }
+                    _ => { ("panicked".to_string(), false) }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use super::declared::Declared;
+use super::discovery::{self, PluginDiscovery, FORMAT_PREFIX, SINK_PREFIX, SOURCE_PREFIX};
+use super::scripted::Scripted;
+use super::PluginRegistry;
+
+// how long a format executable has to answer its first line
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// where each installed file came from, for splash plugins update
+const RECORD: &str = ".installed";
+
+// a plugin file put in the user plugin directory, once it's loaded
+pub struct Installed {
+    pub kind: &'static str,
+    pub name: String,
+    pub version: Option<String>,
+    pub path: PathBuf,
+    // false when the file is the same as the one it replaced
+    pub changed: bool,
+}
+
+// Installs a plugin file, an executable, a declared format or a script, from
+// a path or an http(s) URL into ~/.splash/plugins. It's loaded, or an
+// executable tried out, before it replaces anything there. An
+// executable's splash-*.toml manifest next to it comes along if there is one.
+pub fn install(registry: &PluginRegistry, source: &str) -> Result<Installed, String> {
+    let dir = user_dir()?;
+    let file = file_name(source)?;
+    let (kind, executable) = kind(&file)?;
+
+    let staging = dir.join(".installing");
+    fs::create_dir_all(&staging).map_err(|e| format!("{}: {}", staging.display(), e))?;
+
+    let staged = stage(registry, source, &file, kind, executable, &dir, &staging);
+    let _ = fs::remove_dir_all(&staging);
+    let staged = staged?;
+
+    // a local file is recorded by its full path, so update works from anywhere
+    let source = match remote(source) {
+        true => { source.to_string() }
+        false => { fs::canonicalize(source).map(|p| p.display().to_string()).unwrap_or(source.to_string()) }
+    };
+
+    let mut record = installed();
+    record.insert(file, source);
+    save(&dir, &record)?;
+
+    Ok(staged)
+}
+
+fn stage(registry: &PluginRegistry, source: &str, file: &str, kind: &'static str, executable: bool, dir: &Path, staging: &Path) -> Result<Installed, String> {
+    let staged = staging.join(file);
+    let bytes = fetch(source)?;
+    write(&staged, &bytes, executable)?;
+
+    // a manifest is optional, so one that can't be had is left out
+    let manifest = match executable {
+        true => { fetch(&manifest_of(source)).ok() }
+        false => { None }
+    };
+
+    if let Some(m) = &manifest {
+        write(&staged.with_extension("toml"), m, false)?;
+    }
+
+    // what's wrong is told about the file as it was given
+    let (name, version) = check(&staged, kind, executable).map_err(|e| e.replace(&staged.display().to_string(), source))?;
+    let path = dir.join(file);
+
+    match registry.discovered(kind, &name) {
+        Some(d) if d.path != path => {
+            return Err(format!("a {} called {} is already installed in {}", kind, name, d.path.display()));
+        }
+        None if registry.entries().iter().any(|(k, n)| *k == kind && *n == name) => {
+            return Err(format!("a {} called {} is built in", kind, name));
+        }
+        _ => {}
+    }
+
+    let changed = fs::read(&path).map(|old| old != bytes).unwrap_or(true);
+
+    fs::rename(&staged, &path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if manifest.is_some() {
+        let to = path.with_extension("toml");
+        fs::rename(staged.with_extension("toml"), &to).map_err(|e| format!("{}: {}", to.display(), e))?;
+    }
+
+    Ok(Installed { kind, name, version, path, changed })
+}
+
+// Deletes what splash plugins install put in place for a plugin, manifest
+// included, and returns the files removed. Plugins elsewhere are left alone.
+pub fn remove(registry: &PluginRegistry, name: &str) -> Result<Vec<PathBuf>, String> {
+    let dir = user_dir()?;
+    let paths: Vec<PathBuf> = registry.entries().into_iter()
+        .filter(|(_, n)| n == name)
+        .filter_map(|(kind, _)| registry.discovered(kind, name))
+        .map(|d| d.path.clone())
+        .collect();
+
+    if paths.is_empty() {
+        return Err(format!("{} is built in, splash plugins disable {} turns it off", name, name));
+    }
+
+    if let Some(elsewhere) = paths.iter().find(|p| p.parent() != Some(dir.as_path())) {
+        return Err(format!("{} is in {}, not in {} where plugins are installed, remove it from there", name, elsewhere.display(), dir.display()));
+    }
+
+    let mut record = installed();
+    let mut removed = Vec::new();
+
+    for path in paths {
+        let manifest = path.with_extension("toml");
+        let executable = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| kind(n).is_ok_and(|(_, e)| e));
+
+        fs::remove_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if let Some(file) = path.file_name().and_then(|n| n.to_str()) {
+            record.remove(file);
+        }
+        removed.push(path);
+
+        if executable && manifest.is_file() {
+            fs::remove_file(&manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+            removed.push(manifest);
+        }
+    }
+
+    save(&dir, &record)?;
+    Ok(removed)
+}
+
+// the files installed with splash plugins install and where each came from
+pub fn installed() -> BTreeMap<String, String> {
+    user_dir().ok()
+        .and_then(|dir| fs::read_to_string(dir.join(RECORD)).ok())
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(dir: &Path, record: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = dir.join(RECORD);
+
+    if record.is_empty() {
+        let _ = fs::remove_file(&path);
+        return Ok(());
+    }
+
+    let contents = toml::to_string(record).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn user_dir() -> Result<PathBuf, String> {
+    let dir = PluginDiscovery::user_dir().ok_or("no home directory to install plugins in".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+fn remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+// the last part of the path or URL, which says what the plugin is
+fn file_name(source: &str) -> Result<String, String> {
+    let path = match remote(source) {
+        true => { source.split(['?', '#']).next().unwrap_or_default() }
+        false => { source }
+    };
+
+    match path.rsplit('/').next().filter(|f| !f.is_empty()) {
+        Some(file) => { Ok(file.to_string()) }
+        None => { Err(format!("{}: no file name to install the plugin as", source)) }
+    }
+}
+
+// what a plugin file is by its name, and whether it's run as a program
+fn kind(file: &str) -> Result<(&'static str, bool), String> {
+    let executable = [(FORMAT_PREFIX, "mode"), (SINK_PREFIX, "sink"), (SOURCE_PREFIX, "input")].into_iter()
+        .find(|(prefix, _)| file.starts_with(prefix) && file.len() > prefix.len());
+
+    match executable {
+        Some(_) if file.ends_with(".toml") => {
+            Err(format!("{} is an executable plugin's manifest, install the executable and it comes along", file))
+        }
+        Some((_, kind)) => { Ok((kind, true)) }
+        None if !file.starts_with("splash-") && (file.ends_with(".toml") || file.ends_with(".rhai")) => { Ok(("mode", false)) }
+        None => {
+            Err(format!("{} isn't named like a plugin, expected splash-format-NAME, splash-sink-NAME, splash-source-NAME, NAME.toml or NAME.rhai", file))
+        }
+    }
+}
+
+// the splash-*.toml next to an executable, at the same place
+fn manifest_of(source: &str) -> String {
+    let (base, file) = source.rsplit_once('/').map(|(b, f)| (format!("{}/", b), f)).unwrap_or(("".to_string(), source));
+    let file = file.split(['?', '#']).next().unwrap_or_default();
+
+    format!("{}{}", base, Path::new(file).with_extension("toml").display())
+}
+
+fn fetch(source: &str) -> Result<Vec<u8>, String> {
+    match remote(source) {
+        true => {
+            let response = ureq::get(source).call().map_err(|e| e.to_string())?;
+            let mut body = Vec::new();
+            response.into_reader().read_to_end(&mut body).map_err(|e| format!("{}: {}", source, e))?;
+            Ok(body)
+        }
+        false => { fs::read(source).map_err(|e| format!("{}: {}", source, e)) }
+    }
+}
+
+fn write(path: &Path, bytes: &[u8], executable: bool) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if executable {
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+// loads the staged plugin the way discovery would, for its name and version
+fn check(path: &Path, kind: &str, executable: bool) -> Result<(String, Option<String>), String> {
+    let file = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+    if executable {
+        probe(path, kind)?;
+
+        let name = [FORMAT_PREFIX, SINK_PREFIX, SOURCE_PREFIX].iter().find_map(|p| file.strip_prefix(p)).unwrap_or(file);
+        let (version, _) = discovery::about(&path.with_extension("toml"));
+        return Ok((name.to_string(), version));
+    }
+
+    match file.ends_with(".rhai") {
+        true => {
+            let scripted = Scripted::load(path);
+            match scripted.error() {
+                Some(e) => { Err(e.to_string()) }
+                None => { Ok((scripted.name().to_string(), scripted.about().0)) }
+            }
+        }
+        false => {
+            let declared = Declared::load(path);
+            match declared.error() {
+                Some(e) => { Err(e.to_string()) }
+                None => { Ok((declared.name().to_string(), discovery::about(path).0)) }
+            }
+        }
+    }
+}
+
+// starts an executable plugin and has it do what splash will ask of it: a
+// format answers a line, a sink takes one and ends with its input, and an
+// input writes one or ends cleanly
+fn probe(path: &Path, kind: &str) -> Result<(), String> {
+    let mut child = Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let answered = match kind {
+        "mode" => {
+            let mut stdin = child.stdin.take().unwrap();
+            let stdout = child.stdout.take().unwrap();
+            let (tx, rx) = mpsc::channel();
+
+            thread::spawn(move || {
+                let mut answer: String = "".to_owned();
+                let read = writeln!(stdin, "splash plugins install").and_then(|_| stdin.flush())
+                    .and_then(|_| BufReader::new(stdout).read_line(&mut answer));
+                let _ = tx.send(read);
+            });
+
+            match rx.recv_timeout(TIMEOUT) {
+                Ok(Ok(0)) => { Err("exited without answering a line".to_string()) }
+                Ok(Ok(_)) => { Ok(()) }
+                Ok(Err(e)) => { Err(e.to_string()) }
+                Err(_) => { Err(format!("didn't answer a line within {}s", TIMEOUT.as_secs())) }
+            }
+        }
+        "sink" => {
+            // closing stdin ends the stream, as splash does when it's done
+            let mut stdin = child.stdin.take().unwrap();
+            let written = writeln!(stdin, "splash plugins install").and_then(|_| stdin.flush());
+            drop(stdin);
+
+            written.map_err(|e| e.to_string()).and_then(|_| ended(&mut child, "its input ending"))
+        }
+        _ => {
+            let stdout = child.stdout.take().unwrap();
+            let (tx, rx) = mpsc::channel();
+
+            thread::spawn(move || {
+                let mut line: String = "".to_owned();
+                let _ = tx.send(BufReader::new(stdout).read_line(&mut line));
+            });
+
+            match rx.recv_timeout(TIMEOUT) {
+                Ok(Ok(0)) => { ended(&mut child, "closing its output") }
+                Ok(Ok(_)) => { Ok(()) }
+                Ok(Err(e)) => { Err(e.to_string()) }
+                Err(_) => { Err(format!("didn't write a line within {}s", TIMEOUT.as_secs())) }
+            }
+        }
+    };
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    answered.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// waits, for at most TIMEOUT, for a plugin to exit by itself after what it
+// was last given, and says how it went
+fn ended(child: &mut Child, after: &str) -> Result<(), String> {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) if status.success() => { return Ok(()) }
+            Some(status) => { return Err(format!("exited with {}", status)) }
+            None if Instant::now() >= deadline => { return Err(format!("didn't exit within {}s of {}", TIMEOUT.as_secs(), after)) }
+            None => { thread::sleep(Duration::from_millis(50)) }
+        }
+    }
+}
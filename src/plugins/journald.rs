@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+use chrono::{DateTime, Local};
+use colored::{Colorize, ColoredString};
+use serde_json::{Map, Value};
+use crate::render::hash_color;
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// systemd journal entries from `journalctl -o json`, one object per line, or
+// `journalctl -o export`, KEY=VALUE lines with a blank line after each entry.
+// Binary export fields (a bare name followed by a length) are skipped.
+pub struct Journald {
+    entry: RefCell<Vec<(String, String)>>,
+}
+
+impl Journald {
+    pub fn new() -> Self {
+        Journald { entry: RefCell::new(Vec::new()) }
+    }
+}
+
+impl Plugin for Journald {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "journald".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let mut entry = self.entry.borrow_mut();
+
+        if line.is_empty() {
+            return match entry.is_empty() {
+                true => { ParseResult::Parsed(Record::new("".to_string())) }
+                false => { ParseResult::Parsed(format_entry(&std::mem::take(&mut *entry))) }
+            };
+        }
+
+        if line.starts_with('{') {
+            if let Ok(Value::Object(object)) = serde_json::from_str::<Value>(line) {
+                return ParseResult::Parsed(format_entry(&json_fields(object)));
+            }
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) if is_field_name(key) => {
+                entry.push((key.to_string(), value.to_string()));
+                ParseResult::Incomplete
+            }
+            _ if !entry.is_empty() => { ParseResult::Incomplete }
+            _ => { ParseResult::Parsed(Record::new(line.to_string())) }
+        }
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "host", "unit", "identifier", "pid", "priority", "message"]
+    }
+}
+
+fn is_field_name(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+// binary values come through as arrays of bytes
+fn json_fields(object: Map<String, Value>) -> Vec<(String, String)> {
+    object.into_iter().map(|(key, value)| {
+        let value = match value {
+            Value::String(s) => { s }
+            Value::Array(bytes) => {
+                let bytes: Vec<u8> = bytes.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect();
+                String::from_utf8_lossy(&bytes).to_string()
+            }
+            other => { other.to_string() }
+        };
+
+        (key, value)
+    }).collect()
+}
+
+fn priority_name(priority: &str) -> &'static str {
+    match priority {
+        "0" => { "emerg" }
+        "1" => { "alert" }
+        "2" => { "crit" }
+        "3" => { "err" }
+        "4" => { "warning" }
+        "5" => { "notice" }
+        "7" => { "debug" }
+        _ => { "info" }
+    }
+}
+
+// the same scheme journalctl uses
+fn paint_message(message: &str, priority: &str) -> ColoredString {
+    match priority {
+        "0" | "1" | "2" | "3" => { message.bright_red().bold() }
+        "4" => { message.bright_yellow().bold() }
+        "5" => { message.bold() }
+        "7" => { message.dimmed() }
+        _ => { message.normal() }
+    }
+}
+
+fn format_entry(fields: &[(String, String)]) -> Record {
+    let field = |name: &str| fields.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+
+    let datetime = field("__REALTIME_TIMESTAMP")
+        .and_then(|t| t.parse::<i64>().ok())
+        .and_then(DateTime::from_timestamp_micros);
+
+    let priority = field("PRIORITY").unwrap_or("6");
+    let message = field("MESSAGE").unwrap_or_default();
+    let identifier = field("SYSLOG_IDENTIFIER").or(field("_COMM")).unwrap_or("-");
+
+    let mut s: String = "".to_owned();
+    let mut record_fields: Vec<(&str, String)> = Vec::new();
+
+    if let Some(t) = datetime {
+        s.push_str(&format!("{} ", t.with_timezone(&Local).format("%b %d %H:%M:%S").to_string().cyan()));
+        record_fields.push(("datetime", t.to_rfc3339()));
+    }
+
+    if let Some(host) = field("_HOSTNAME") {
+        s.push_str(&format!("{} ", host.white()));
+        record_fields.push(("host", host.to_string()));
+    }
+
+    if let Some(unit) = field("_SYSTEMD_UNIT").or(field("UNIT")) {
+        s.push_str(&format!("{}{}{} ", "[".dimmed(), unit.color(hash_color(unit)), "]".dimmed()));
+        record_fields.push(("unit", unit.to_string()));
+    }
+
+    s.push_str(&format!("{}", identifier.bright_magenta()));
+    record_fields.push(("identifier", identifier.to_string()));
+
+    if let Some(pid) = field("_PID").or(field("SYSLOG_PID")) {
+        s.push_str(&format!("{}{}{}", "[".bright_white(), pid.bright_blue(), "]".bright_white()));
+        record_fields.push(("pid", pid.to_string()));
+    }
+
+    s.push_str(&format!(": {}", paint_message(message, priority)));
+    record_fields.push(("priority", priority_name(priority).to_string()));
+    record_fields.push(("message", message.to_string()));
+
+    let mut record = Record::new(s);
+    for (name, value) in record_fields {
+        record = match name {
+            "pid" => { record.with_typed(name, &value, FieldType::Int) }
+            _ => { record.with_field(name, &value) }
+        };
+    }
+
+    record
+}
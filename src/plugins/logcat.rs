@@ -0,0 +1,107 @@
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::Regex;
+use crate::render::hash_color;
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+// adb logcat -v threadtime
+// 03-17 16:13:43.123  1234  5678 I ActivityManager: Start proc com.example
+static THREADTIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?x)
+        ^((?:\d{4}-)?\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\.\d{3}) # datetime
+        \s+
+        (\d+)                                            # pid
+        \s+
+        (\d+)                                            # tid
+        \s+
+        ([VDIWEFA])                                      # level
+        \s+
+        (.*?)\s*:                                        # tag
+        \s?
+        (.*)$                                            # message
+        "#
+    ).unwrap()
+});
+
+pub struct Logcat;
+
+impl Plugin for Logcat {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "logcat".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let caps = match THREADTIME.captures(line) {
+            Some(c) => { c }
+            None if line.starts_with("--------- beginning of") => {
+                return ParseResult::Parsed(Record::new(line.dimmed().to_string()))
+            }
+            None => { return ParseResult::Parsed(Record::new(line.to_string())) }
+        };
+
+        let s = format!(
+            "{} {} {} {} {}{} {}",
+            caps[1].dimmed(),
+            format!("{:>5}", &caps[2]).bright_blue(),
+            format!("{:>5}", &caps[3]).blue(),
+            level(&caps[4]),
+            caps[5].color(hash_color(&caps[5])).bold(),
+            ":".dimmed(),
+            message(&caps[4], &caps[6]),
+        );
+
+        ParseResult::Parsed(
+            Record::new(s)
+                .with_field("datetime", &caps[1])
+                .with_typed("pid", &caps[2], FieldType::Int)
+                .with_field("thread", &caps[3])
+                .with_field("level", &caps[4])
+                .with_field("tag", &caps[5])
+                .with_field("message", &caps[6])
+        )
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "pid", "thread", "level", "tag", "message"]
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        Some(format!(
+            "{} {:>5} {:>5} {} {}: {}",
+            record.field("datetime")?,
+            record.field("pid")?,
+            record.field("thread")?,
+            record.field("level")?,
+            record.field("tag")?,
+            record.field("message")?,
+        ))
+    }
+}
+
+// pidcat style badges
+fn level(name: &str) -> ColoredString {
+    let badge = format!(" {} ", name);
+
+    match name {
+        "V" => { badge.white().on_black() }
+        "D" => { badge.black().on_blue() }
+        "I" => { badge.black().on_green() }
+        "W" => { badge.black().on_yellow() }
+        "E" | "F" | "A" => { badge.black().on_red() }
+        _ => { badge.normal() }
+    }
+}
+
+fn message(level: &str, message: &str) -> ColoredString {
+    match level {
+        "V" => { message.dimmed() }
+        "W" => { message.yellow() }
+        "E" => { message.red() }
+        "F" | "A" => { message.bright_red().bold() }
+        _ => { message.normal() }
+    }
+}
@@ -0,0 +1,277 @@
+mod adhoc;
+mod asa;
+mod auditd;
+mod auth;
+mod clf;
+mod declared;
+mod discovery;
+mod external;
+mod fallback;
+mod firewall;
+mod gelf;
+mod guarded;
+mod install;
+mod journald;
+mod logcat;
+mod registry;
+mod scripted;
+mod serilog;
+mod sinks;
+mod sources;
+mod tomcat;
+mod watched;
+mod winevent;
+
+pub use adhoc::{fired_matchers, set_adhoc_matchers};
+pub use discovery::PluginDiscovery;
+pub use fallback::Fallback;
+pub use install::{install, installed, remove, Installed};
+pub use registry::{Discovered, PluginRegistry};
+pub use sources::{poll_interval, FileSource};
+
+use std::fmt;
+use colored::{ColoredString, Colorize};
+
+pub struct PluginMetadata {
+    pub name: String,
+
+    // suggested styles for the plugin's semantic tags, e.g. ("level", "bright_red bold"),
+    // which a theme's [styles] can override
+    pub styles: Vec<(String, String)>,
+}
+
+pub struct Record {
+    pub text: String,
+    // the fields' values as they show in the line
+    pub fields: Vec<(String, String)>,
+    // the ones the mode read as numbers or flags, the rest are text
+    pub values: Vec<(String, FieldValue)>,
+}
+
+// what a field's value is, for sinks to export numbers as numbers and
+// --where to compare them as such
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldType {
+    Text,
+    Int,
+    Float,
+    Bool,
+}
+
+impl FieldType {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => { Ok(FieldType::Text) }
+            "int" => { Ok(FieldType::Int) }
+            "float" => { Ok(FieldType::Float) }
+            "bool" => { Ok(FieldType::Bool) }
+            _ => { Err(format!("unknown field type '{}', expected one of text, int, float, bool", name)) }
+        }
+    }
+
+    // the value text reads as, text when it doesn't read as this type, like
+    // a size of "-"
+    pub fn read(self, text: &str) -> FieldValue {
+        let value = match self {
+            FieldType::Text => { None }
+            FieldType::Int => { text.parse().ok().map(FieldValue::Int) }
+            FieldType::Float => { text.parse().ok().filter(|f: &f64| f.is_finite()).map(FieldValue::Float) }
+            FieldType::Bool => { text.parse().ok().map(FieldValue::Bool) }
+        };
+
+        value.unwrap_or(FieldValue::Text(text.to_string()))
+    }
+}
+
+impl FieldValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FieldValue::Text(_) => { "text" }
+            FieldValue::Int(_) => { "int" }
+            FieldValue::Float(_) => { "float" }
+            FieldValue::Bool(_) => { "bool" }
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldValue::Text(s) => { write!(f, "{}", s) }
+            FieldValue::Int(n) => { write!(f, "{}", n) }
+            FieldValue::Float(n) => { write!(f, "{}", n) }
+            FieldValue::Bool(b) => { write!(f, "{}", b) }
+        }
+    }
+}
+
+impl Record {
+    pub fn new(text: String) -> Self {
+        Record { text, fields: Vec::new(), values: Vec::new() }
+    }
+
+    pub fn with_field(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // a field the mode knows the type of
+    pub fn with_typed(mut self, name: &str, value: &str, kind: FieldType) -> Self {
+        if let typed @ (FieldValue::Int(_) | FieldValue::Float(_) | FieldValue::Bool(_)) = kind.read(value) {
+            self.values.push((name.to_string(), typed));
+        }
+
+        self.with_field(name, value)
+    }
+
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    // a field's value as the mode read it
+    pub fn value(&self, name: &str) -> Option<FieldValue> {
+        match self.values.iter().find(|(n, _)| n == name) {
+            Some((_, value)) => { Some(value.clone()) }
+            None => { self.field(name).map(|v| FieldValue::Text(v.to_string())) }
+        }
+    }
+
+    // every field with its value as the mode read it, in order
+    pub fn typed_fields(&self) -> Vec<(&str, FieldValue)> {
+        self.fields.iter().map(|(name, text)| {
+            match self.values.iter().find(|(n, _)| n == name) {
+                Some((_, value)) => { (name.as_str(), value.clone()) }
+                None => { (name.as_str(), FieldValue::Text(text.clone())) }
+            }
+        }).collect()
+    }
+}
+
+pub enum ParseResult {
+    Parsed(Record),
+    NotMatched,
+
+    // the line was buffered as part of a multi-line record
+    Incomplete,
+}
+
+pub trait Plugin {
+    fn metadata(&self) -> PluginMetadata;
+    fn parse_line(&self, line: &str) -> ParseResult;
+
+    // the options in the config file's [plugin.NAME] section, given before
+    // any line is parsed
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        match options.keys().next() {
+            Some(key) => { Err(format!("unknown option '{}', the {} mode takes none", key, self.metadata().name)) }
+            None => { Ok(()) }
+        }
+    }
+
+    // whether it gave up on the last line rather than leaving it out, for
+    // the line to be shown as it is
+    fn failed(&self) -> bool {
+        false
+    }
+
+    // the fields its records can have, for --dump-capabilities
+    fn fields(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    // rebuilds the original line from a parsed record's fields, for --verify
+    fn serialize(&self, _record: &Record) -> Option<String> {
+        None
+    }
+
+    // How sure the plugin is, from 0 to 1, that sample lines are in its
+    // format, for splash detect: the share of them that end up in records
+    // with fields, counting the lines of a multi-line record once it's whole.
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        let mut recognized = 0;
+        let mut pending = 0;
+
+        for line in lines {
+            let blank = line.trim().is_empty() as usize;
+
+            match self.parse_line(line) {
+                ParseResult::Parsed(r) if !r.fields.is_empty() => {
+                    recognized += pending + 1 - blank;
+                    pending = 0;
+                }
+                ParseResult::Incomplete => { pending += 1 - blank }
+                _ => { pending = 0 }
+            }
+        }
+
+        match lines.iter().filter(|l| !l.trim().is_empty()).count() {
+            0 => { 0.0 }
+            sampled => { recognized as f64 / sampled as f64 }
+        }
+    }
+}
+
+// an HTTP status in the color of its class, the same in every mode
+pub fn paint_status(status: &str) -> ColoredString {
+    match status.as_bytes().first() {
+        Some(b'2') => { status.green() }
+        Some(b'3') => { status.cyan() }
+        Some(b'4') => { status.yellow() }
+        Some(b'5') => { status.red().bold() }
+        _ => { status.bright_yellow() }
+    }
+}
+
+pub trait Sink {
+    fn metadata(&self) -> PluginMetadata;
+    fn write(&mut self, text: &str, record: Option<&Record>) -> Result<(), String>;
+
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        self.flush()
+    }
+
+    // nothing reads what it writes any more, like stdout piped to head
+    fn closed(&self) -> bool {
+        false
+    }
+}
+
+// what a source hands its input to
+pub trait Input {
+    fn contents(&mut self, contents: &str);
+
+    // lines from a source's error stream, like a container's stderr
+    fn stderr(&mut self, contents: &str) {
+        self.contents(contents);
+    }
+
+    // nothing has arrived for a while
+    fn idle(&mut self);
+
+    // lines that follow come from another of a source's files
+    fn source(&mut self, _name: &str) {}
+
+    // the input wants no more lines, sources stop reading when it does
+    fn done(&self) -> bool {
+        false
+    }
+}
+
+pub trait Source: Send {
+    fn metadata(&self) -> PluginMetadata;
+
+    // reads until the input ends; followed inputs never do
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String>;
+}
@@ -0,0 +1,301 @@
+use std::path::PathBuf;
+use super::adhoc::AdHoc;
+use super::asa::Asa;
+use super::auditd::Auditd;
+use super::auth::Auth;
+use super::clf::Clf;
+use super::discovery::PluginDiscovery;
+use super::fallback::Fallback;
+use super::firewall::Firewall;
+use super::gelf::Gelf;
+use super::journald::Journald;
+use super::logcat::Logcat;
+use super::serilog::Serilog;
+use super::sinks::{Elasticsearch, FileSink, Html, Loki, Terminal, Webhook};
+use super::sinks;
+use super::sources::{FileSource, Stdin, Tcp};
+use super::sources;
+use super::tomcat::Tomcat;
+use super::winevent::WinEvent;
+use super::{Plugin, Sink, Source};
+
+pub type SinkBuilder = Box<dyn Fn(Option<&str>) -> Result<Box<dyn Sink>, String>>;
+
+pub type SourceBuilder = Box<dyn Fn(Option<&str>) -> Result<Box<dyn Source>, String>>;
+
+struct SinkFactory {
+    name: String,
+    build: SinkBuilder,
+}
+
+struct SourceFactory {
+    name: String,
+    build: SourceBuilder,
+}
+
+// where a discovered plugin was found and what it says about itself
+pub struct Discovered {
+    pub kind: &'static str,
+    pub name: String,
+    pub path: PathBuf,
+    pub version: Option<String>,
+    pub author: Option<String>,
+}
+
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn Plugin>>,
+    sinks: Vec<SinkFactory>,
+    sources: Vec<SourceFactory>,
+    discovered: Vec<Discovered>,
+    // names turned off in the config file, of any kind
+    disabled: Vec<String>,
+}
+
+impl Default for PluginRegistry {
+    fn default() -> Self {
+        PluginRegistry::new()
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        let mut registry = PluginRegistry { plugins: Vec::new(), sinks: Vec::new(), sources: Vec::new(), discovered: Vec::new(), disabled: Vec::new() };
+
+        registry.register(Box::new(AdHoc));
+        registry.register(Box::new(Clf::default()));
+        registry.register(Box::new(Serilog));
+        registry.register(Box::new(Auth));
+        registry.register(Box::new(Auditd));
+        registry.register(Box::new(Firewall));
+        registry.register(Box::new(Tomcat));
+        registry.register(Box::new(WinEvent::new()));
+        registry.register(Box::new(Asa));
+        registry.register(Box::new(Gelf));
+        registry.register(Box::new(Logcat));
+        registry.register(Box::new(Journald::new()));
+
+        registry.register_sink("terminal", Box::new(|_| Ok(Box::new(Terminal::default()))));
+        registry.register_sink("file", Box::new(|target| {
+            Ok(Box::new(FileSink::open(sinks::require_target("file", target)?)?))
+        }));
+        registry.register_sink("html", Box::new(|target| {
+            Ok(Box::new(Html::create(sinks::require_target("html", target)?)?))
+        }));
+        registry.register_sink("elasticsearch", Box::new(|target| {
+            Ok(Box::new(Elasticsearch::new(sinks::require_target("elasticsearch", target)?)))
+        }));
+        registry.register_sink("loki", Box::new(|target| {
+            Ok(Box::new(Loki::new(sinks::require_target("loki", target)?)))
+        }));
+        registry.register_sink("quickfix", Box::new(|target| {
+            Ok(Box::new(sinks::Quickfix::open(target)?))
+        }));
+        registry.register_sink("syslog", Box::new(|target| {
+            Ok(Box::new(sinks::Syslog::connect(sinks::require_target("syslog", target)?)?))
+        }));
+        registry.register_sink("webhook", Box::new(|target| {
+            Ok(Box::new(Webhook::new(sinks::require_target("webhook", target)?)))
+        }));
+
+        registry.register_source("stdin", Box::new(|_| Ok(Box::new(Stdin))));
+        registry.register_source("file", Box::new(|target| {
+            Ok(Box::new(FileSource::new(sources::require_target("file", target)?)?))
+        }));
+        registry.register_source("tcp", Box::new(|target| {
+            Ok(Box::new(Tcp::bind(sources::require_target("tcp", target)?)?))
+        }));
+        registry.register_source("journald", Box::new(|target| {
+            Ok(Box::new(sources::Journald::spawn(target)?))
+        }));
+        registry.register_source("docker", Box::new(|target| {
+            Ok(Box::new(sources::Docker::new(sources::require_target("docker", target)?)?))
+        }));
+        registry.register_source("ssh", Box::new(|target| {
+            Ok(Box::new(sources::Ssh::spawn(sources::require_target("ssh", target)?)?))
+        }));
+        registry.register_source("http", Box::new(|target| {
+            Ok(Box::new(sources::Http::new(&format!("http:{}", sources::require_target("http", target)?))?))
+        }));
+        registry.register_source("https", Box::new(|target| {
+            Ok(Box::new(sources::Http::new(&format!("https:{}", sources::require_target("https", target)?))?))
+        }));
+        registry.register_source("spool", Box::new(|target| {
+            Ok(Box::new(sources::Spool::new(sources::require_target("spool", target)?)?))
+        }));
+        registry.register_source("gelf", Box::new(|target| {
+            Ok(Box::new(sources::Gelf::bind(sources::require_target("gelf", target)?)?))
+        }));
+
+        PluginDiscovery::new().discover(&mut registry);
+
+        registry
+    }
+
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) {
+        self.plugins.push(plugin);
+    }
+
+    pub fn register_sink(&mut self, name: &str, build: SinkBuilder) {
+        self.sinks.push(SinkFactory { name: name.to_string(), build });
+    }
+
+    pub fn register_source(&mut self, name: &str, build: SourceBuilder) {
+        self.sources.push(SourceFactory { name: name.to_string(), build });
+    }
+
+    pub fn note_discovered(&mut self, discovered: Discovered) {
+        self.discovered.push(discovered);
+    }
+
+    // where a plugin of a kind was discovered, None when it's built in
+    pub fn discovered(&self, kind: &str, name: &str) -> Option<&Discovered> {
+        self.discovered.iter().find(|d| d.kind == kind && d.name == name)
+    }
+
+    // the modes, sinks and inputs with the name are treated as if they
+    // weren't installed
+    pub fn disable(&mut self, names: &[String]) {
+        self.disabled.extend(names.iter().cloned());
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.disabled.iter().any(|d| d == name)
+    }
+
+    // every mode, sink and input, disabled ones included, as (kind, name)
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let modes = self.plugins.iter().map(|p| ("mode", p.metadata().name));
+        let sinks = self.sinks.iter().map(|s| ("sink", s.name.clone()));
+        let sources = self.sources.iter().map(|s| ("input", s.name.clone()));
+
+        modes.chain(sinks).chain(sources).collect()
+    }
+
+    pub fn plugins(&self) -> Vec<&dyn Plugin> {
+        self.plugins.iter().map(|p| p.as_ref()).filter(|p| !self.is_disabled(&p.metadata().name)).collect()
+    }
+
+    pub fn sink_names(&self) -> Vec<&str> {
+        self.sinks.iter().map(|s| s.name.as_str()).filter(|n| !self.is_disabled(n)).collect()
+    }
+
+    pub fn source_names(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.name.as_str()).filter(|n| !self.is_disabled(n)).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Plugin> {
+        self.plugins
+            .iter()
+            .find(|p| p.metadata().name == name)
+            .map(|p| p.as_ref())
+    }
+
+    pub fn mode_names(&self) -> Vec<String> {
+        self.plugins().iter().map(|p| p.metadata().name).collect()
+    }
+
+    // the plugin for --mode, suggesting the closest name for a typo
+    pub fn mode(&self, name: &str) -> Result<&dyn Plugin, String> {
+        if self.is_disabled(name) && self.get(name).is_some() {
+            return Err(disabled("mode", name));
+        }
+
+        if let Some(plugin) = self.get(name) {
+            return Ok(plugin);
+        }
+
+        let names = self.mode_names();
+        let closest = names.iter()
+            .map(|n| (edit_distance(&n.to_lowercase(), &name.to_lowercase()), n))
+            .filter(|(d, n)| *d <= (n.chars().count() / 3).max(2))
+            .min_by_key(|(d, _)| *d);
+
+        match closest {
+            Some((_, n)) => { Err(format!("unknown mode '{}', did you mean '{}'? expected one of {}", name, n, names.join(", "))) }
+            None => { Err(format!("unknown mode '{}', expected one of {}", name, names.join(", "))) }
+        }
+    }
+
+    // the modes a line is offered to in turn, as one
+    pub fn chain(&self, name: &str, modes: &[String]) -> Result<Fallback<'_>, String> {
+        if modes.is_empty() || modes.iter().any(|m| m.is_empty()) {
+            return Err(format!("the mode chain '{}' has an empty mode in it", name));
+        }
+
+        let plugins = modes.iter().map(|m| self.mode(m)).collect::<Result<Vec<&dyn Plugin>, String>>()?;
+
+        Ok(Fallback::new(name, plugins))
+    }
+
+    // unknown modes fall back to ad-hoc
+    pub fn get_or_default(&self, name: &str) -> &dyn Plugin {
+        match self.get(name) {
+            Some(p) => { p }
+            None => { self.get("ad-hoc").unwrap() }
+        }
+    }
+
+    pub fn has_sink(&self, name: &str) -> bool {
+        self.sinks.iter().any(|s| s.name == name)
+    }
+
+    // builds a sink from a spec like file:/tmp/out.log or loki:http://localhost:3100
+    pub fn sink(&self, spec: &str) -> Result<Box<dyn Sink>, String> {
+        let (name, target) = split_spec(spec);
+
+        match self.sinks.iter().find(|s| s.name == name) {
+            Some(_) if self.is_disabled(name) => { Err(disabled("sink", name)) }
+            Some(factory) => { (factory.build)(target) }
+            None => {
+                Err(format!("unknown sink '{}', expected one of {}", name, self.sink_names().join(", ")))
+            }
+        }
+    }
+
+    pub fn has_source(&self, name: &str) -> bool {
+        self.sources.iter().any(|s| s.name == name)
+    }
+
+    // builds a source from a spec like stdin, file:/var/log/app.log or tcp:0.0.0.0:5140
+    pub fn source(&self, spec: &str) -> Result<Box<dyn Source>, String> {
+        let (name, target) = split_spec(spec);
+
+        match self.sources.iter().find(|s| s.name == name) {
+            Some(_) if self.is_disabled(name) => { Err(disabled("input", name)) }
+            Some(factory) => { (factory.build)(target) }
+            None => {
+                Err(format!("unknown input '{}', expected one of {}", name, self.source_names().join(", ")))
+            }
+        }
+    }
+}
+
+// characters added, removed or changed to get from one to the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, y) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diagonal + (x != *y) as usize).min(row[j] + 1).min(above + 1);
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn disabled(kind: &str, name: &str) -> String {
+    format!("the {} {} is disabled, splash plugins enable {} turns it back on", kind, name, name)
+}
+
+fn split_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once(':') {
+        Some((name, target)) => { (name, Some(target)) }
+        None => { (spec, None) }
+    }
+}
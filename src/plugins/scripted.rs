@@ -0,0 +1,275 @@
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Instant;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use crate::render::locate;
+use crate::theme;
+use super::guarded::{self, BUDGET};
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+struct Script {
+    engine: Engine,
+    ast: AST,
+    // what the script keeps between lines, its functions' `this`
+    state: RefCell<Dynamic>,
+    // when the call running has to be done by
+    deadline: Rc<Cell<Option<Instant>>>,
+}
+
+// A format written as a Rhai script, NAME.rhai in a plugin directory, for
+// highlighting a regex can't express: state kept across lines, or styles
+// that depend on the values. parse(line) answers with the line's fields and
+// the styles to paint them in, () to show the line as it is, or false to
+// drop it:
+//
+//     fn parse(line) {
+//         let parts = line.split(" ", 3);
+//         if parts.len() < 3 { return (); }
+//
+//         this.errors = (this.errors ?? 0) + if parts[1] == "ERROR" { 1 } else { 0 };
+//
+//         #{
+//             fields: #{ datetime: parts[0], level: parts[1], message: parts[2] },
+//             styles: #{ level: if this.errors > 3 { "white on_red" } else { "yellow" } },
+//         }
+//     }
+//
+// `this` starts as an empty map and keeps what the functions put in it, with
+// the options of a [plugin.NAME] section as `this.config`. An optional
+// detect(line) says whether a line is in the format, for splash detect. A
+// script that doesn't compile, fails on a line or runs past the budget is
+// reported by the guard around it when its mode is used.
+pub struct Scripted {
+    name: String,
+    script: Result<Script, String>,
+}
+
+impl Scripted {
+    pub fn load(path: &Path) -> Self {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+
+        let script = fs::read_to_string(path).map_err(|e| e.to_string())
+            .and_then(|source| {
+                let deadline: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+                let mut engine = Engine::new();
+
+                // a loop that doesn't end is stopped rather than hanging the stream
+                let due = deadline.clone();
+                engine.on_progress(move |operations| {
+                    match due.get() {
+                        Some(at) if operations % 1024 == 0 && Instant::now() > at => { Some(Dynamic::UNIT) }
+                        _ => { None }
+                    }
+                });
+
+                let ast = engine.compile(&source).map_err(|e| e.to_string())?;
+
+                match defines(&ast, "parse") {
+                    true => { Ok(Script { engine, ast, state: RefCell::new(Dynamic::from_map(Map::new())), deadline }) }
+                    false => { Err("no parse(line) function".to_string()) }
+                }
+            })
+            .map_err(|e| format!("{}: {}", path.display(), e));
+
+        Scripted { name, script }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // the script's VERSION and AUTHOR constants, if it sets them
+    pub fn about(&self) -> (Option<String>, Option<String>) {
+        let constant = |name: &str| self.script.as_ref().ok()
+            .and_then(|s| s.ast.iter_literal_variables(true, false).find(|(n, _, _)| *n == name))
+            .map(|(_, _, value)| value.to_string());
+
+        (constant("VERSION"), constant("AUTHOR"))
+    }
+
+    // why the script doesn't load, if it doesn't
+    pub fn error(&self) -> Option<&str> {
+        self.script.as_ref().err().map(String::as_str)
+    }
+
+    fn script(&self) -> &Script {
+        match &self.script {
+            Ok(s) => { s }
+            Err(e) => { guarded::broken(e.clone()) }
+        }
+    }
+
+    fn call(&self, script: &Script, function: &str, line: &str) -> Result<Dynamic, String> {
+        let mut state = script.state.borrow_mut();
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut state);
+
+        script.deadline.set(Some(Instant::now() + BUDGET));
+        let answer = script.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &script.ast, function, (line.to_string(),));
+        script.deadline.set(None);
+
+        answer.map_err(|e| {
+            match *e {
+                EvalAltResult::ErrorTerminated(..) => { format!("{}() ran past the {}ms budget and was stopped", function, BUDGET.as_millis()) }
+                e => { format!("{}(): {}", function, e) }
+            }
+        })
+    }
+
+    // the fields and styles parse answered with, painted where the values
+    // show in the line
+    fn record(&self, line: &str, answer: Map) -> Result<Record, String> {
+        // numbers and flags stay typed, the rest is text
+        let entries = |key: &str| -> Result<Vec<(String, String, FieldType)>, String> {
+            match answer.get(key) {
+                Some(value) => {
+                    let map = value.clone().try_cast::<Map>().ok_or(format!("parse(): {} must be a map", key))?;
+                    Ok(map.into_iter().map(|(k, v)| (k.to_string(), v.to_string(), kind(&v))).collect())
+                }
+                None => { Ok(Vec::new()) }
+            }
+        };
+
+        let fields = entries("fields")?;
+        let styles: Vec<(String, String)> = entries("styles")?.into_iter().map(|(k, v, _)| (k, v)).collect();
+
+        for (_, spec) in &styles {
+            theme::parse_style(spec).map_err(|e| format!("parse(): {}", e))?;
+        }
+
+        let values: Vec<&str> = fields.iter().map(|(_, v, _)| v.as_str()).collect();
+        let offsets = locate(line, &values);
+
+        let mut fields: Vec<(Option<usize>, String, String, FieldType)> = fields.into_iter().zip(offsets)
+            .map(|((name, value, kind), offset)| (offset, name, value, kind))
+            .collect();
+
+        // fields in the order they show in the line, those it hasn't last
+        fields.sort_by_key(|(offset, _, _, _)| offset.unwrap_or(usize::MAX));
+
+        let mut text: String = "".to_owned();
+        let mut pos = 0;
+
+        for (offset, name, value, _) in &fields {
+            let start = match offset {
+                Some(start) if *start >= pos => { *start }
+                _ => { continue }
+            };
+
+            text.push_str(&line[pos..start]);
+
+            match theme::current().style(&self.name, name, &styles) {
+                Some(style) => { text.push_str(&theme::paint(value, style)) }
+                None => { text.push_str(value) }
+            }
+
+            pos = start + value.len();
+        }
+
+        text.push_str(&line[pos..]);
+
+        Ok(fields.into_iter().fold(Record::new(text), |record, (_, name, value, kind)| record.with_typed(&name, &value, kind)))
+    }
+}
+
+impl Plugin for Scripted {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        if let Ok(script) = &self.script {
+            if let Some(state) = script.state.borrow_mut().write_lock::<Map>().as_deref_mut() {
+                state.insert("config".into(), dynamic(&toml::Value::Table(options.clone())));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let script = self.script();
+
+        let answer = match self.call(script, "parse", line) {
+            Ok(answer) => { answer }
+            Err(e) => { guarded::fail(e) }
+        };
+
+        if answer.is_unit() {
+            return ParseResult::Parsed(Record::new(line.to_string()));
+        }
+
+        if answer.as_bool() == Ok(false) {
+            return ParseResult::NotMatched;
+        }
+
+        let record = answer.try_cast::<Map>()
+            .ok_or("parse(): expected a map of fields and styles, () or false".to_string())
+            .and_then(|answer| self.record(line, answer));
+
+        match record {
+            Ok(record) => { ParseResult::Parsed(record) }
+            Err(e) => { guarded::fail(e) }
+        }
+    }
+
+    // the lines detect(line) is true for, or those parse gives fields for
+    // when the script has no detect
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        let script = match &self.script {
+            Ok(s) => { s }
+            Err(_) => { return 0.0 }
+        };
+
+        if !defines(&script.ast, "detect") {
+            let parsed = lines.iter().filter(|l| !l.trim().is_empty()).filter(|l| {
+                matches!(self.call(script, "parse", l).ok().and_then(|a| a.try_cast::<Map>()), Some(a) if a.contains_key("fields"))
+            }).count();
+
+            return ratio(parsed, lines);
+        }
+
+        let detected = lines.iter().filter(|l| !l.trim().is_empty())
+            .filter(|l| self.call(script, "detect", l).ok().and_then(|d| d.as_bool().ok()).unwrap_or(false))
+            .count();
+
+        ratio(detected, lines)
+    }
+}
+
+// whether the script has a function of that name taking the line
+fn defines(ast: &AST, function: &str) -> bool {
+    ast.iter_functions().any(|f| f.name == function && f.params.len() == 1)
+}
+
+fn dynamic(value: &toml::Value) -> Dynamic {
+    match value {
+        toml::Value::String(s) => { Dynamic::from(s.clone()) }
+        toml::Value::Integer(n) => { Dynamic::from_int(*n) }
+        toml::Value::Float(n) => { Dynamic::from_float(*n) }
+        toml::Value::Boolean(b) => { Dynamic::from_bool(*b) }
+        toml::Value::Datetime(d) => { Dynamic::from(d.to_string()) }
+        toml::Value::Array(items) => { Dynamic::from_array(items.iter().map(dynamic).collect()) }
+        toml::Value::Table(table) => { Dynamic::from_map(table.iter().map(|(k, v)| (k.into(), dynamic(v))).collect()) }
+    }
+}
+
+fn kind(value: &Dynamic) -> FieldType {
+    match value {
+        v if v.is_int() => { FieldType::Int }
+        v if v.is_float() => { FieldType::Float }
+        v if v.is_bool() => { FieldType::Bool }
+        _ => { FieldType::Text }
+    }
+}
+
+fn ratio(n: usize, lines: &[&str]) -> f64 {
+    match lines.iter().filter(|l| !l.trim().is_empty()).count() {
+        0 => { 0.0 }
+        sampled => { n as f64 / sampled as f64 }
+    }
+}
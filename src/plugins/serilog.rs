@@ -0,0 +1,103 @@
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::Regex;
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// [HH:mm:ss LVL] message
+static SERILOG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[((?:\d{4}-\d{2}-\d{2} )?\d{2}:\d{2}:\d{2}(?:\.\d+)?) (VRB|DBG|INF|WRN|ERR|FTL)\] ?(.*)$").unwrap()
+});
+
+// info: Category[EventId]
+static ASPNET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(trce|dbug|info|warn|fail|crit): (\S+?)\[(\d+)\] ?(.*)$").unwrap()
+});
+
+static STACK_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+at \S").unwrap()
+});
+
+static EXCEPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*[\w.]+(Exception|Error)(: |$)").unwrap()
+});
+
+pub struct Serilog;
+
+impl Plugin for Serilog {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "serilog".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        if let Some(caps) = SERILOG.captures(line) {
+            let s = format!(
+                "{}{} {}{} {}",
+                "[".bright_white(),
+                &caps[1].cyan(),
+                level(&caps[2]),
+                "]".bright_white(),
+                &caps[3],
+            );
+
+            return ParseResult::Parsed(
+                Record::new(s)
+                    .with_field("datetime", &caps[1])
+                    .with_field("level", &caps[2])
+                    .with_field("message", &caps[3])
+            );
+        }
+
+        if let Some(caps) = ASPNET.captures(line) {
+            let mut s = format!(
+                "{}: {}{}{}{}",
+                level(&caps[1]),
+                &caps[2].bright_magenta(),
+                "[".bright_white(),
+                &caps[3].bright_blue(),
+                "]".bright_white(),
+            );
+
+            if !caps[4].is_empty() {
+                s.push(' ');
+                s.push_str(&caps[4]);
+            }
+
+            return ParseResult::Parsed(
+                Record::new(s)
+                    .with_field("level", &caps[1])
+                    .with_field("category", &caps[2])
+                    .with_field("event_id", &caps[3])
+                    .with_field("message", &caps[4])
+            );
+        }
+
+        if STACK_FRAME.is_match(line) {
+            return ParseResult::Parsed(Record::new(line.dimmed().to_string()));
+        }
+
+        if EXCEPTION.is_match(line) {
+            return ParseResult::Parsed(Record::new(line.red().to_string()));
+        }
+
+        ParseResult::Parsed(Record::new(line.to_string()))
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "level", "category", "event_id", "message"]
+    }
+}
+
+fn level(abbr: &str) -> ColoredString {
+    match abbr {
+        "VRB" | "trce" => { abbr.dimmed() }
+        "DBG" | "dbug" => { abbr.blue() }
+        "INF" | "info" => { abbr.bright_green() }
+        "WRN" | "warn" => { abbr.bright_yellow() }
+        "ERR" | "fail" => { abbr.bright_red() }
+        "FTL" | "crit" => { abbr.white().on_red().bold() }
+        _ => { abbr.normal() }
+    }
+}
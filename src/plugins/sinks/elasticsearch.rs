@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use serde_json::{json, Value};
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+use crate::time::parse_timestamp;
+use super::{fields_json, BATCH_SIZE};
+
+// indexes each line as a document through the _bulk API,
+// the target is the index url, e.g. http://localhost:9200/logs
+pub struct Elasticsearch {
+    url: String,
+    batch: Vec<Value>,
+    last_flush: Instant,
+}
+
+impl Elasticsearch {
+    pub fn new(url: &str) -> Self {
+        Elasticsearch { url: url.trim_end_matches('/').to_string(), batch: Vec::new(), last_flush: Instant::now() }
+    }
+}
+
+impl Sink for Elasticsearch {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "elasticsearch".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, record: Option<&Record>) -> Result<(), String> {
+        let timestamp = record
+            .and_then(|r| r.field("datetime"))
+            .and_then(parse_timestamp)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+        let mut doc = fields_json(record);
+        doc.insert("@timestamp".to_string(), Value::String(timestamp));
+        doc.insert("message".to_string(), Value::String(strip_ansi(text)));
+
+        self.batch.push(Value::Object(doc));
+
+        if self.batch.len() >= BATCH_SIZE || self.last_flush.elapsed() > Duration::from_secs(1) {
+            return self.flush();
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.last_flush = Instant::now();
+
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut body: String = "".to_owned();
+        for doc in self.batch.drain(..) {
+            body.push_str(&json!({ "index": {} }).to_string());
+            body.push('\n');
+            body.push_str(&doc.to_string());
+            body.push('\n');
+        }
+
+        ureq::post(&format!("{}/_bulk", self.url))
+            .set("Content-Type", "application/x-ndjson")
+            .send_string(&body)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+
+// a discovered splash-sink-<name> executable, fed plain lines on stdin
+pub struct External {
+    name: String,
+    child: Child,
+    stdin: Option<ChildStdin>,
+}
+
+impl External {
+    pub fn spawn(name: &str, path: &Path, target: Option<&str>) -> Result<Self, String> {
+        let mut child = Command::new(path)
+            .args(target)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let stdin = child.stdin.take();
+
+        Ok(External { name: name.to_string(), child, stdin })
+    }
+}
+
+impl Sink for External {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, _record: Option<&Record>) -> Result<(), String> {
+        match &mut self.stdin {
+            Some(stdin) => { writeln!(stdin, "{}", strip_ansi(text)).map_err(|e| e.to_string()) }
+            None => { Ok(()) }
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        match &mut self.stdin {
+            Some(stdin) => { stdin.flush().map_err(|e| e.to_string()) }
+            None => { Ok(()) }
+        }
+    }
+
+    // closing stdin tells the sink the stream is over
+    fn close(&mut self) -> Result<(), String> {
+        self.flush()?;
+        self.stdin.take();
+        self.child.wait().map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
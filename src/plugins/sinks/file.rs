@@ -0,0 +1,42 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+
+// appends plain text lines to a file, or with a #truncate fragment (as --tee
+// gives) starts it over
+pub struct FileSink {
+    file: File,
+}
+
+impl FileSink {
+    pub fn open(target: &str) -> Result<Self, String> {
+        let (path, truncate) = match target.strip_suffix("#truncate") {
+            Some(path) => { (path, true) }
+            None => { (target, false) }
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(!truncate)
+            .write(true)
+            .truncate(truncate)
+            .open(path)
+            .map_err(|e| format!("{}: {}", path, e))?;
+
+        Ok(FileSink { file })
+    }
+}
+
+impl Sink for FileSink {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "file".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, _record: Option<&Record>) -> Result<(), String> {
+        writeln!(self.file, "{}", strip_ansi(text)).map_err(|e| e.to_string())
+    }
+}
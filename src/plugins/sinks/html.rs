@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::Write;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::ansi_to_html;
+
+const HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>splash</title>
+<style>
+body { background: #1d1f21; color: #c5c8c6; }
+pre { font-family: monospace; white-space: pre-wrap; margin: 0; }
+</style>
+</head>
+<body>
+<pre>
+"#;
+
+const FOOTER: &str = "</pre>\n</body>\n</html>\n";
+
+// writes the colored output as a standalone html page
+pub struct Html {
+    file: File,
+    closed: bool,
+}
+
+impl Html {
+    pub fn create(path: &str) -> Result<Self, String> {
+        let mut file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        file.write_all(HEADER.as_bytes()).map_err(|e| e.to_string())?;
+
+        Ok(Html { file, closed: false })
+    }
+}
+
+impl Sink for Html {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "html".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, _record: Option<&Record>) -> Result<(), String> {
+        writeln!(self.file, "{}", ansi_to_html(text)).map_err(|e| e.to_string())
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        if self.closed {
+            return Ok(());
+        }
+
+        self.closed = true;
+        self.file.write_all(FOOTER.as_bytes()).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use serde_json::json;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+use crate::time::parse_timestamp;
+use super::BATCH_SIZE;
+
+// pushes lines to Grafana Loki, the target is the server url, e.g. http://localhost:3100
+pub struct Loki {
+    url: String,
+    values: Vec<(String, String)>,
+    last_flush: Instant,
+}
+
+impl Loki {
+    pub fn new(url: &str) -> Self {
+        Loki { url: url.trim_end_matches('/').to_string(), values: Vec::new(), last_flush: Instant::now() }
+    }
+}
+
+impl Sink for Loki {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "loki".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, record: Option<&Record>) -> Result<(), String> {
+        let nanos = record
+            .and_then(|r| r.field("datetime"))
+            .and_then(parse_timestamp)
+            .and_then(|t| t.timestamp_nanos_opt())
+            .or(Utc::now().timestamp_nanos_opt())
+            .unwrap_or(0);
+
+        self.values.push((nanos.to_string(), strip_ansi(text)));
+
+        if self.values.len() >= BATCH_SIZE || self.last_flush.elapsed() > Duration::from_secs(1) {
+            return self.flush();
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.last_flush = Instant::now();
+
+        if self.values.is_empty() {
+            return Ok(());
+        }
+
+        let values: Vec<[String; 2]> = self.values.drain(..).map(|(ts, line)| [ts, line]).collect();
+        let body = json!({
+            "streams": [{
+                "stream": { "job": "splash" },
+                "values": values,
+            }]
+        });
+
+        ureq::post(&format!("{}/loki/api/v1/push", self.url))
+            .send_json(body)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+mod elasticsearch;
+mod external;
+mod file;
+mod html;
+mod loki;
+mod quickfix;
+mod syslog;
+mod terminal;
+mod webhook;
+
+use serde_json::{Map, Value};
+use super::{FieldValue, Record};
+
+pub use elasticsearch::Elasticsearch;
+pub use external::External;
+pub use file::FileSink;
+pub use html::Html;
+pub use loki::Loki;
+pub use quickfix::Quickfix;
+pub use syslog::Syslog;
+pub use terminal::Terminal;
+pub use webhook::Webhook;
+
+const BATCH_SIZE: usize = 500;
+
+// the record's fields, numbers and flags as JSON numbers and booleans
+fn fields_json(record: Option<&Record>) -> Map<String, Value> {
+    let mut fields = Map::new();
+
+    if let Some(record) = record {
+        for (name, value) in record.typed_fields() {
+            let value = match value {
+                FieldValue::Text(s) => { Value::String(s) }
+                FieldValue::Int(n) => { Value::from(n) }
+                FieldValue::Float(n) => { Value::from(n) }
+                FieldValue::Bool(b) => { Value::Bool(b) }
+            };
+
+            fields.insert(name.to_string(), value);
+        }
+    }
+
+    fields
+}
+
+pub fn require_target<'a>(name: &str, target: Option<&'a str>) -> Result<&'a str, String> {
+    target.ok_or(format!("the {} sink needs a target, e.g. --sink {}:<target>", name, name))
+}
@@ -0,0 +1,101 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+
+// at com.example.Foo.bar(Foo.java:42)
+static JVM_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bat\s+([\w$.]+)\.[\w$<>]+\(([\w$]+\.(?:java|kt|scala|groovy|clj)):(\d+)\)").unwrap()
+});
+
+// File "/app/main.py", line 12, in handler
+static PYTHON_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"File "([^"]+)", line (\d+)"#).unwrap()
+});
+
+// PHP Fatal error: ... in /var/www/index.php on line 12
+static PHP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bin (\S+\.php)(?: on line |:)(\d+)").unwrap()
+});
+
+// src/main.rs:10:5, main.c:3: error, /app/index.js:10:5 or worker.go:12 +0x1d
+static PATH_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?:^|[\s(\[])((?:[\w.\-~]*/)*[\w\-.]*\.(?:{})):(\d+)(?::(\d+))?\b", SOURCE_EXTENSIONS.join("|"))).unwrap()
+});
+
+// so host.example.com:443 isn't taken for a file
+const SOURCE_EXTENSIONS: [&str; 46] = [
+    "rs", "c", "h", "cc", "cpp", "cxx", "hpp", "hh", "m", "mm", "go", "py", "rb", "erb", "js", "mjs", "cjs", "jsx",
+    "ts", "tsx", "vue", "svelte", "java", "kt", "scala", "groovy", "clj", "php", "cs", "fs", "swift", "dart",
+    "ex", "exs", "erl", "hs", "ml", "lua", "pl", "pm", "sh", "zig", "nim", "sql", "yml", "yaml",
+];
+
+// Writes the source locations found in the output (stack frames, compiler
+// diagnostics) as file:line:col: text, the errorformat vim's :cfile and
+// most editors read, to stdout or a file. Lines without one are left out.
+pub struct Quickfix {
+    out: Box<dyn Write>,
+}
+
+impl Quickfix {
+    pub fn open(path: Option<&str>) -> Result<Self, String> {
+        let out: Box<dyn Write> = match path {
+            Some(p) => { Box::new(File::create(p).map_err(|e| format!("{}: {}", p, e))?) }
+            None => { Box::new(io::stdout()) }
+        };
+
+        Ok(Quickfix { out })
+    }
+}
+
+fn location(line: &str) -> Option<(String, String, Option<String>)> {
+    if let Some(c) = JVM_FRAME.captures(line) {
+        // the class's package is the directory its source lives in
+        let mut package: Vec<&str> = c[1].split('.').collect();
+        package.pop();
+        package.push(&c[2]);
+
+        return Some((package.join("/"), c[3].to_string(), None));
+    }
+
+    if let Some(c) = PYTHON_FRAME.captures(line).or_else(|| PHP.captures(line)) {
+        return Some((c[1].to_string(), c[2].to_string(), None));
+    }
+
+    let c = PATH_LINE.captures(line)?;
+    Some((c[1].to_string(), c[2].to_string(), c.get(3).map(|m| m.as_str().to_string())))
+}
+
+impl Sink for Quickfix {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "quickfix".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, _record: Option<&Record>) -> Result<(), String> {
+        let text = strip_ansi(text);
+
+        let (file, line, column) = match location(&text) {
+            Some(l) => { l }
+            None => { return Ok(()) }
+        };
+
+        let position = match column {
+            Some(c) => { format!("{}:{}:{}", file, line, c) }
+            None => { format!("{}:{}", file, line) }
+        };
+
+        // compiler diagnostics already start with their location
+        let message = text.trim().strip_prefix(&position).map(|m| m.trim_start_matches(':').trim()).unwrap_or(text.trim());
+
+        writeln!(self.out, "{}: {}", position, message).map_err(|e| e.to_string())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.out.flush().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,91 @@
+use std::net::{ToSocketAddrs, UdpSocket};
+use chrono::{Local, SecondsFormat};
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+use crate::severity::{self, Severity};
+
+const DEFAULT_PORT: u16 = 514;
+
+// the user facility, shifted as syslog priorities are
+const FACILITY: u8 = 1 << 3;
+
+// forwards plain lines to a syslog collector over UDP as RFC 5424 messages,
+// with the severity of their record when it has one
+pub struct Syslog {
+    socket: UdpSocket,
+    hostname: String,
+}
+
+impl Syslog {
+    // HOST[:PORT], or //HOST[:PORT] as a syslog:// url leaves it
+    pub fn connect(target: &str) -> Result<Self, String> {
+        let target = target.trim_start_matches("//").trim_end_matches('/');
+
+        let address = match target.rsplit_once(':') {
+            Some((_, port)) if port.parse::<u16>().is_ok() => { target.to_string() }
+            _ => { format!("{}:{}", target, DEFAULT_PORT) }
+        };
+
+        let remote = address.to_socket_addrs().map_err(|e| format!("{}: {}", address, e))?
+            .next()
+            .ok_or(format!("{}: no address", address))?;
+
+        let local = match remote.is_ipv4() {
+            true => { "0.0.0.0:0" }
+            false => { "[::]:0" }
+        };
+
+        let socket = UdpSocket::bind(local).map_err(|e| e.to_string())?;
+        socket.connect(remote).map_err(|e| format!("{}: {}", address, e))?;
+
+        Ok(Syslog { socket, hostname: hostname() })
+    }
+}
+
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+
+    match unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } {
+        0 => {
+            let end = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+            String::from_utf8_lossy(&buf[..end]).to_string()
+        }
+        _ => { "-".to_string() }
+    }
+}
+
+fn priority(record: Option<&Record>) -> u8 {
+    let code = match record.and_then(severity::of) {
+        Some(Severity::Error) => { 3 }
+        Some(Severity::Warning) => { 4 }
+        Some(Severity::Info) => { 6 }
+        Some(Severity::Debug) => { 7 }
+        // notice, for splash's own markers and lines without a severity
+        None => { 5 }
+    };
+
+    FACILITY | code
+}
+
+impl Sink for Syslog {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "syslog".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, record: Option<&Record>) -> Result<(), String> {
+        let message = format!(
+            "<{}>1 {} {} splash - - - {}",
+            priority(record),
+            Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            self.hostname,
+            strip_ansi(text),
+        );
+
+        self.socket.send(message.as_bytes()).map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{self, ErrorKind, Write};
+use colored::control::SHOULD_COLORIZE;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+use crate::theme;
+
+// Prints to stdout. One whose reader has gone, like head once it has its
+// lines, is closed rather than failing on every line that follows.
+#[derive(Default)]
+pub struct Terminal {
+    closed: bool,
+}
+
+impl Sink for Terminal {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "terminal".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    // external plugins color their own output, so escapes can turn up even
+    // when colors are off
+    fn write(&mut self, text: &str, _record: Option<&Record>) -> Result<(), String> {
+        if self.closed {
+            return Ok(());
+        }
+
+        let text = match SHOULD_COLORIZE.should_colorize() {
+            true => { theme::current().apply(text) }
+            false => { strip_ansi(text) }
+        };
+
+        match writeln!(io::stdout().lock(), "{}", text) {
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            written => { written.map_err(|e| e.to_string()) }
+        }
+    }
+
+    fn closed(&self) -> bool {
+        self.closed
+    }
+}
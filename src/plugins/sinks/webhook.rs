@@ -0,0 +1,36 @@
+use serde_json::Value;
+use crate::plugins::{PluginMetadata, Record, Sink};
+use crate::render::strip_ansi;
+use super::fields_json;
+
+// posts every line as {"text": ..., "fields": {...}}, which chat webhooks accept as is
+pub struct Webhook {
+    url: String,
+}
+
+impl Webhook {
+    pub fn new(url: &str) -> Self {
+        Webhook { url: url.to_string() }
+    }
+}
+
+impl Sink for Webhook {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "webhook".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, text: &str, record: Option<&Record>) -> Result<(), String> {
+        let mut body = serde_json::Map::new();
+        body.insert("text".to_string(), Value::String(strip_ansi(text)));
+        body.insert("fields".to_string(), Value::Object(fields_json(record)));
+
+        ureq::post(&self.url)
+            .send_json(Value::Object(body))
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
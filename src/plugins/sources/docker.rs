@@ -0,0 +1,204 @@
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use serde_json::Value;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::IDLE;
+
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+
+// Streams a container's logs from the Docker Engine API. Containers without a
+// TTY send stdout and stderr multiplexed, as frames with an 8 byte header.
+pub struct Docker {
+    socket: PathBuf,
+    container: String,
+}
+
+impl Docker {
+    pub fn new(container: &str) -> Result<Self, String> {
+        let socket = match env::var("DOCKER_HOST") {
+            Ok(host) => {
+                match host.strip_prefix("unix://") {
+                    Some(path) => { PathBuf::from(path) }
+                    None => { return Err(format!("DOCKER_HOST {} isn't a unix:// socket", host)) }
+                }
+            }
+            Err(_) => { PathBuf::from(DEFAULT_SOCKET) }
+        };
+
+        Ok(Docker { socket, container: container.to_string() })
+    }
+
+    fn get(&self, path: &str) -> Result<Box<dyn BufRead + Send>, String> {
+        let mut stream = UnixStream::connect(&self.socket).map_err(|e| format!("{}: {}", self.socket.display(), e))?;
+
+        write!(stream, "GET {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\n\r\n", path).map_err(|e| e.to_string())?;
+
+        let mut reader = BufReader::new(stream);
+        let mut status = String::new();
+        reader.read_line(&mut status).map_err(|e| e.to_string())?;
+
+        let mut chunked = false;
+        let mut length: Option<u64> = None;
+
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).map_err(|e| e.to_string())?;
+
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                match name.to_ascii_lowercase().as_str() {
+                    "transfer-encoding" => { chunked = value.trim().eq_ignore_ascii_case("chunked") }
+                    "content-length" => { length = value.trim().parse().ok() }
+                    _ => {}
+                }
+            }
+        }
+
+        let body: Box<dyn BufRead + Send> = match (chunked, length) {
+            (true, _) => { Box::new(BufReader::new(Chunked { reader, remaining: 0, done: false })) }
+            (false, Some(n)) => { Box::new(reader.take(n)) }
+            (false, None) => { Box::new(reader) }
+        };
+
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(error_message(body, status.trim()));
+        }
+
+        Ok(body)
+    }
+}
+
+// the API explains errors as {"message": "..."}
+fn error_message(mut body: Box<dyn BufRead + Send>, status: &str) -> String {
+    let mut text = String::new();
+    let _ = body.read_to_string(&mut text);
+
+    match serde_json::from_str::<Value>(&text).ok().and_then(|v| v["message"].as_str().map(|m| m.to_string())) {
+        Some(message) => { message }
+        None => { status.to_string() }
+    }
+}
+
+// HTTP/1.1 chunked transfer encoding
+struct Chunked<R: BufRead> {
+    reader: R,
+    remaining: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Read for Chunked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.done {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            let mut size = String::new();
+            self.reader.read_line(&mut size)?;
+
+            // the CRLF ending the previous chunk
+            if size.trim().is_empty() {
+                size.clear();
+                self.reader.read_line(&mut size)?;
+            }
+
+            let size = size.trim().split(';').next().unwrap_or("");
+            self.remaining = usize::from_str_radix(size, 16).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+            if self.remaining == 0 {
+                self.done = true;
+                return Ok(0);
+            }
+        }
+
+        let max = buf.len().min(self.remaining);
+        let n = self.reader.read(&mut buf[..max])?;
+        self.remaining -= n;
+
+        Ok(n)
+    }
+}
+
+// (stderr, line) pairs from a multiplexed stream
+fn demultiplex(mut body: Box<dyn BufRead + Send>, tx: mpsc::Sender<(bool, String)>) {
+    let mut partial: [Vec<u8>; 2] = [Vec::new(), Vec::new()];
+    let mut header = [0u8; 8];
+
+    while body.read_exact(&mut header).is_ok() {
+        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let mut payload = vec![0u8; size];
+
+        if body.read_exact(&mut payload).is_err() {
+            return;
+        }
+
+        let stderr = header[0] == 2;
+        let buffer = &mut partial[stderr as usize];
+        buffer.extend_from_slice(&payload);
+
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']).to_string();
+
+            if tx.send((stderr, line)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Source for Docker {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "docker".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let mut inspect = String::new();
+        self.get(&format!("/containers/{}/json", self.container))?
+            .read_to_string(&mut inspect)
+            .map_err(|e| e.to_string())?;
+
+        let tty = serde_json::from_str::<Value>(&inspect)
+            .map_err(|e| e.to_string())?["Config"]["Tty"]
+            .as_bool()
+            .unwrap_or(false);
+
+        let body = self.get(&format!("/containers/{}/logs?follow=1&stdout=1&stderr=1", self.container))?;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            match tty {
+                true => {
+                    for line in body.lines().map_while(Result::ok) {
+                        if tx.send((false, line)).is_err() {
+                            return;
+                        }
+                    }
+                }
+                false => { demultiplex(body, tx) }
+            }
+        });
+
+        while !input.done() {
+            match rx.recv_timeout(IDLE) {
+                Ok((true, line)) => { input.stderr(&(line + "\n")) }
+                Ok((false, line)) => { input.contents(&(line + "\n")) }
+                Err(RecvTimeoutError::Timeout) => { input.idle() }
+                Err(RecvTimeoutError::Disconnected) => { return Ok(()) }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::pump;
+
+// a discovered splash-source-<name> executable, read line by line from its stdout
+pub struct External {
+    name: String,
+    child: Child,
+}
+
+impl External {
+    pub fn spawn(name: &str, path: &Path, target: Option<&str>) -> Result<Self, String> {
+        let child = Command::new(path)
+            .args(target)
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        Ok(External { name: name.to_string(), child })
+    }
+}
+
+impl Source for External {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: self.name.clone(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let stdout = match self.child.stdout.take() {
+            Some(s) => { s }
+            None => { return Ok(()) }
+        };
+
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        pump(rx, input);
+
+        if input.done() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            return Ok(());
+        }
+
+        let status = self.child.wait().map_err(|e| e.to_string())?;
+        match status.success() {
+            true => { Ok(()) }
+            false => { Err(format!("exited with {}", status)) }
+        }
+    }
+}
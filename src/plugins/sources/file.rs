@@ -0,0 +1,112 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::{poll_fragment, IDLE};
+
+// how often files are polled where changes to them can't be watched for
+const DEFAULT_POLL: Duration = Duration::from_secs(2);
+
+// follows files, passing on whatever is appended to them, or with a #once
+// fragment (as splash cat gives) passes on all of them in turn and stops;
+// a #poll=SECONDS fragment sets how often they're polled where they must be
+pub struct FileSource {
+    paths: Vec<String>,
+    once: bool,
+    interval: Duration,
+}
+
+impl FileSource {
+    pub fn new(target: &str) -> Result<Self, String> {
+        let (target, interval) = poll_fragment(target)?;
+
+        match target.strip_suffix("#once") {
+            Some(path) => { Ok(FileSource::files(&[path.to_string()], true, interval)) }
+            None => { Ok(FileSource::files(&[target.to_string()], false, interval)) }
+        }
+    }
+
+    pub fn files(paths: &[String], once: bool, interval: Option<Duration>) -> Self {
+        FileSource { paths: paths.to_vec(), once, interval: interval.unwrap_or(DEFAULT_POLL) }
+    }
+}
+
+impl Source for FileSource {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "file".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let mut positions: Vec<u64> = Vec::new();
+
+        for path in &self.paths {
+            let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+            if self.once {
+                input.source(path);
+                input.contents(&contents);
+
+                if input.done() {
+                    break;
+                }
+            }
+
+            positions.push(contents.len() as u64);
+        }
+
+        if self.once {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel();
+
+        let config = Config::default()
+                        .with_poll_interval(self.interval)
+                        .with_compare_contents(true);
+
+        let mut watcher = RecommendedWatcher::new(tx, config).map_err(|e| e.to_string())?;
+
+        for path in &self.paths {
+            watcher.watch(path.as_ref(), RecursiveMode::NonRecursive).map_err(|e| format!("{}: {}", path, e))?;
+        }
+
+        let mut contents = String::new();
+
+        while !input.done() {
+            match rx.recv_timeout(IDLE) {
+                Ok(_) => {
+                    for (path, pos) in self.paths.iter().zip(positions.iter_mut()) {
+                        let mut f = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+                        let len = f.metadata().map_err(|e| e.to_string())?.len();
+
+                        if len == *pos {
+                            continue;
+                        }
+
+                        f.seek(SeekFrom::Start(*pos)).map_err(|e| e.to_string())?;
+                        *pos = len;
+
+                        contents.clear();
+                        f.read_to_string(&mut contents).map_err(|e| e.to_string())?;
+
+                        input.source(path);
+                        input.contents(&contents);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    input.idle();
+                }
+                Err(e) => {
+                    return Err(e.to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::IDLE;
+
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+const MAX_DATAGRAM: usize = 65536;
+
+// incomplete chunked messages are dropped after this long, as Graylog does
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Chunks {
+    parts: Vec<Option<Vec<u8>>>,
+    started: Instant,
+}
+
+// GELF messages over UDP, compressed or not and possibly chunked,
+// passed on one JSON message per line for --mode gelf
+pub struct Gelf {
+    socket: UdpSocket,
+    chunks: HashMap<[u8; 8], Chunks>,
+}
+
+impl Gelf {
+    pub fn bind(addr: &str) -> Result<Self, String> {
+        let socket = UdpSocket::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+        socket.set_read_timeout(Some(IDLE)).map_err(|e| e.to_string())?;
+
+        Ok(Gelf { socket, chunks: HashMap::new() })
+    }
+
+    // a whole message, once all of its chunks have arrived
+    fn assemble(&mut self, datagram: &[u8]) -> Option<Vec<u8>> {
+        if datagram.len() < 12 || datagram[..2] != CHUNK_MAGIC {
+            return Some(datagram.to_vec());
+        }
+
+        let id: [u8; 8] = datagram[2..10].try_into().unwrap();
+        let (seq, count) = (datagram[10] as usize, datagram[11] as usize);
+
+        if seq >= count {
+            return None;
+        }
+
+        self.chunks.retain(|_, c| c.started.elapsed() < CHUNK_TIMEOUT);
+
+        let chunks = self.chunks.entry(id).or_insert_with(|| Chunks { parts: vec![None; count], started: Instant::now() });
+        if chunks.parts.len() != count {
+            return None;
+        }
+        chunks.parts[seq] = Some(datagram[12..].to_vec());
+
+        if chunks.parts.iter().any(|p| p.is_none()) {
+            return None;
+        }
+
+        let chunks = self.chunks.remove(&id)?;
+        Some(chunks.parts.into_iter().flatten().flatten().collect())
+    }
+}
+
+fn decompress(payload: &[u8]) -> Option<String> {
+    let mut message = String::new();
+
+    let ok = match payload {
+        [0x1f, 0x8b, ..] => { GzDecoder::new(payload).read_to_string(&mut message).is_ok() }
+        [0x78, ..] => { ZlibDecoder::new(payload).read_to_string(&mut message).is_ok() }
+        _ => {
+            message = String::from_utf8_lossy(payload).to_string();
+            true
+        }
+    };
+
+    match ok {
+        true => { Some(message.replace('\n', " ")) }
+        false => { None }
+    }
+}
+
+impl Source for Gelf {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "gelf".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let mut buf = vec![0u8; MAX_DATAGRAM];
+
+        while !input.done() {
+            match self.socket.recv(&mut buf) {
+                Ok(n) => {
+                    let datagram = buf[..n].to_vec();
+
+                    if let Some(message) = self.assemble(&datagram).and_then(|p| decompress(&p)) {
+                        input.contents(message.trim_end_matches('\0'));
+                    }
+                }
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                    input.idle();
+                }
+                Err(e) => { return Err(e.to_string()) }
+            }
+        }
+
+        Ok(())
+    }
+}
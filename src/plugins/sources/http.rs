@@ -0,0 +1,129 @@
+use std::io::Read;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::{poll_fragment, IDLE};
+
+pub const DEFAULT_POLL: Duration = Duration::from_secs(5);
+
+// Follows a log file served over HTTP(S) by polling it with Range requests
+// for the bytes after the ones already read. Servers that ignore Range send
+// the whole file, and a file that shrank was rotated and is read again. The
+// poll interval in seconds can be given as a #poll=N fragment. A URL that
+// can't be requested at all is refused up front, and a poll that fails is
+// reported and tried again at the next one.
+pub struct Http {
+    url: String,
+    interval: Duration,
+    offset: u64,
+    pending: Vec<u8>,
+}
+
+enum Fetched {
+    Appended(Vec<u8>),
+    Replaced(Vec<u8>),
+    Unchanged,
+}
+
+impl Http {
+    pub fn new(url: &str) -> Result<Self, String> {
+        let (url, interval) = poll_fragment(url)?;
+        let interval = interval.unwrap_or(DEFAULT_POLL);
+        ureq::get(url).request_url().map_err(|e| e.to_string())?;
+
+        Ok(Http { url: url.to_string(), interval, offset: 0, pending: Vec::new() })
+    }
+
+    fn fetch(&self) -> Result<Fetched, String> {
+        let mut request = ureq::get(&self.url);
+        if self.offset > 0 {
+            request = request.set("Range", &format!("bytes={}-", self.offset));
+        }
+
+        let response = match request.call() {
+            Ok(r) => { r }
+            // nothing past the offset, unless the file is now shorter
+            Err(ureq::Error::Status(416, r)) => {
+                return match content_range_total(r.header("Content-Range")) {
+                    Some(total) if total < self.offset => { self.refetch() }
+                    _ => { Ok(Fetched::Unchanged) }
+                };
+            }
+            Err(e) => { return Err(e.to_string()) }
+        };
+
+        let status = response.status();
+        let body = read_body(response)?;
+
+        match status {
+            206 => { Ok(Fetched::Appended(body)) }
+            _ if self.offset == 0 || (body.len() as u64) < self.offset => { Ok(Fetched::Replaced(body)) }
+            _ => { Ok(Fetched::Appended(body[self.offset as usize..].to_vec())) }
+        }
+    }
+
+    fn refetch(&self) -> Result<Fetched, String> {
+        let response = ureq::get(&self.url).call().map_err(|e| e.to_string())?;
+        Ok(Fetched::Replaced(read_body(response)?))
+    }
+
+    fn emit(&mut self, bytes: &[u8], input: &mut dyn Input) {
+        self.pending.extend_from_slice(bytes);
+
+        // a line still being written is held back until it ends
+        if let Some(end) = self.pending.iter().rposition(|b| *b == b'\n') {
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            input.contents(&String::from_utf8_lossy(&lines));
+        }
+    }
+}
+
+fn read_body(response: ureq::Response) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body).map_err(|e| e.to_string())?;
+    Ok(body)
+}
+
+// "bytes */1234"
+fn content_range_total(header: Option<&str>) -> Option<u64> {
+    header?.rsplit('/').next()?.trim().parse().ok()
+}
+
+impl Source for Http {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "http".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        while !input.done() {
+            let fetched = self.fetch().unwrap_or_else(|e| {
+                eprintln!("Error: input http: {}, trying again in {}s", e, self.interval.as_secs_f64());
+                Fetched::Unchanged
+            });
+
+            match fetched {
+                Fetched::Appended(bytes) => {
+                    self.offset += bytes.len() as u64;
+                    self.emit(&bytes, input);
+                }
+                Fetched::Replaced(bytes) => {
+                    self.offset = bytes.len() as u64;
+                    self.pending.clear();
+                    self.emit(&bytes, input);
+                }
+                Fetched::Unchanged => {}
+            }
+
+            let next = Instant::now() + self.interval;
+            while Instant::now() < next && !input.done() {
+                thread::sleep(IDLE.min(next.saturating_duration_since(Instant::now())));
+                input.idle();
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::pump;
+
+// Follows the systemd journal through journalctl, which reads the journal
+// files directly, as JSON entries for --mode journald. The target is an
+// optional comma separated list of units to follow.
+pub struct Journald {
+    child: Child,
+}
+
+impl Journald {
+    pub fn spawn(units: Option<&str>) -> Result<Self, String> {
+        let mut command = Command::new("journalctl");
+        command.args(["--follow", "--output=json"]);
+
+        for unit in units.iter().flat_map(|u| u.split(',')).filter(|u| !u.is_empty()) {
+            command.arg(format!("--unit={}", unit));
+        }
+
+        let child = command
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("journalctl: {}", e))?;
+
+        Ok(Journald { child })
+    }
+}
+
+impl Source for Journald {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "journald".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let stdout = match self.child.stdout.take() {
+            Some(s) => { s }
+            None => { return Ok(()) }
+        };
+
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        pump(rx, input);
+
+        if input.done() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            return Ok(());
+        }
+
+        let status = self.child.wait().map_err(|e| e.to_string())?;
+        match status.success() {
+            true => { Ok(()) }
+            false => { Err(format!("journalctl exited with {}", status)) }
+        }
+    }
+}
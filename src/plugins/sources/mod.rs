@@ -0,0 +1,60 @@
+mod docker;
+mod external;
+mod file;
+mod gelf;
+mod http;
+mod journald;
+mod spool;
+mod ssh;
+mod stdin;
+mod tcp;
+
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+use super::Input;
+
+pub use docker::Docker;
+pub use external::External;
+pub use file::FileSource;
+pub use gelf::Gelf;
+pub use http::Http;
+pub use journald::Journald;
+pub use spool::Spool;
+pub use ssh::Ssh;
+pub use stdin::Stdin;
+pub use tcp::Tcp;
+
+const IDLE: Duration = Duration::from_secs(1);
+
+pub fn require_target<'a>(name: &str, target: Option<&'a str>) -> Result<&'a str, String> {
+    target.ok_or(format!("the {} input needs a target, e.g. --input {}:<target>", name, name))
+}
+
+// --poll-interval or the config file's poll_interval, which must be positive
+pub fn poll_interval(secs: f64) -> Result<Duration, String> {
+    Some(secs).filter(|s| *s > 0.0).and_then(|s| Duration::try_from_secs_f64(s).ok())
+        .ok_or(format!("invalid poll interval '{}', expected seconds", secs))
+}
+
+// splits the #poll=SECONDS fragment off the target of an input that polls
+fn poll_fragment(target: &str) -> Result<(&str, Option<Duration>), String> {
+    match target.rsplit_once("#poll=") {
+        Some((target, secs)) => {
+            let secs = secs.parse::<f64>().map_err(|_| format!("invalid poll interval '{}', expected seconds", secs))?;
+            Ok((target, Some(poll_interval(secs)?)))
+        }
+        None => { Ok((target, None)) }
+    }
+}
+
+// feeds lines read on other threads to the input until every sender is gone
+// or the input is done
+fn pump(rx: Receiver<String>, input: &mut dyn Input) {
+    while !input.done() {
+        match rx.recv_timeout(IDLE) {
+            Ok(line) => { input.contents(&(line + "\n")) }
+            Err(RecvTimeoutError::Timeout) => { input.idle() }
+            Err(RecvTimeoutError::Disconnected) => { return }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::{poll_fragment, IDLE};
+
+// a file unchanged for this long is taken to be completely written
+const SETTLE: Duration = Duration::from_secs(1);
+
+// Watches a drop folder and reads every file that appears in it once, in
+// name order, including the ones already there. Hidden files are left
+// alone, since that is how most tools write a file before renaming it into
+// place. Read files are moved to the directory given as a #done=DIR
+// fragment, or remembered so they aren't read again. A #poll=SECONDS
+// fragment sets how often the folder is polled where it must be.
+pub struct Spool {
+    dir: PathBuf,
+    done: Option<PathBuf>,
+    interval: Option<Duration>,
+    seen: HashSet<PathBuf>,
+}
+
+impl Spool {
+    pub fn new(target: &str) -> Result<Self, String> {
+        let (target, interval) = poll_fragment(target)?;
+
+        let (dir, done) = match target.split_once("#done=") {
+            Some((d, done)) => { (d, Some(PathBuf::from(done))) }
+            None => { (target, None) }
+        };
+
+        if !Path::new(dir).is_dir() {
+            return Err(format!("{}: not a directory", dir));
+        }
+
+        if let Some(done) = &done {
+            fs::create_dir_all(done).map_err(|e| format!("{}: {}", done.display(), e))?;
+        }
+
+        Ok(Spool { dir: PathBuf::from(dir), done, interval, seen: HashSet::new() })
+    }
+
+    // files that are new and have stopped changing
+    fn ready(&self) -> Result<Vec<PathBuf>, String> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| format!("{}: {}", self.dir.display(), e))?;
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+            .filter(|e| e.metadata().is_ok_and(|m| {
+                m.is_file() && m.modified().ok().and_then(|t| t.elapsed().ok()).is_some_and(|age| age >= SETTLE)
+            }))
+            .map(|e| e.path())
+            .filter(|p| !self.seen.contains(p))
+            .collect();
+
+        files.sort();
+
+        Ok(files)
+    }
+
+    fn process(&mut self, path: PathBuf, input: &mut dyn Input) -> Result<(), String> {
+        let bytes = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut contents = String::from_utf8_lossy(&bytes).to_string();
+
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+
+        input.contents(&contents);
+
+        if let Some(done) = &self.done {
+            let target = done.join(path.file_name().unwrap_or_default());
+
+            // a rename can't cross filesystems
+            fs::rename(&path, &target)
+                .or_else(|_| fs::copy(&path, &target).and_then(|_| fs::remove_file(&path)))
+                .map_err(|e| format!("{}: moving to {}: {}", path.display(), done.display(), e))?;
+        }
+
+        self.seen.insert(path);
+
+        Ok(())
+    }
+}
+
+impl Source for Spool {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "spool".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+
+        let config = match self.interval {
+            Some(interval) => { Config::default().with_poll_interval(interval) }
+            None => { Config::default() }
+        };
+
+        let mut watcher = RecommendedWatcher::new(tx, config).map_err(|e| e.to_string())?;
+
+        watcher.watch(&self.dir, RecursiveMode::NonRecursive).map_err(|e| e.to_string())?;
+
+        while !input.done() {
+            for path in self.ready()? {
+                if input.done() {
+                    break;
+                }
+
+                self.process(path, input)?;
+            }
+
+            // files still being written are looked at again on the next tick
+            match rx.recv_timeout(IDLE) {
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    input.idle();
+                }
+                Err(e) => {
+                    return Err(e.to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
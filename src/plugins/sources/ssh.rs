@@ -0,0 +1,82 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::pump;
+
+// Follows a file on another machine by running tail over ssh, so splash only
+// needs to be installed locally. The target is [user@]host:/path; ports, keys
+// and jump hosts come from ~/.ssh/config.
+pub struct Ssh {
+    destination: String,
+    child: Child,
+}
+
+impl Ssh {
+    pub fn spawn(target: &str) -> Result<Self, String> {
+        let target = target.trim_start_matches("//");
+
+        let (destination, path) = match target.split_once(':') {
+            Some((d, p)) if !d.is_empty() && !p.is_empty() => { (d, p) }
+            _ => { return Err(format!("invalid ssh target '{}', expected ssh://[user@]host:/path", target)) }
+        };
+
+        // the remote command goes through the remote shell
+        let command = format!("tail -n +1 -F {}", shell_quote(path));
+
+        let child = Command::new("ssh")
+            .args(["-n", "-T", "--", destination, &command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("ssh: {}", e))?;
+
+        Ok(Ssh { destination: destination.to_string(), child })
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+impl Source for Ssh {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "ssh".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let stdout = match self.child.stdout.take() {
+            Some(s) => { s }
+            None => { return Ok(()) }
+        };
+
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        pump(rx, input);
+
+        if input.done() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+            return Ok(());
+        }
+
+        let status = self.child.wait().map_err(|e| e.to_string())?;
+        match status.code() {
+            Some(0) => { Ok(()) }
+            Some(255) => { Err(format!("ssh to {} failed", self.destination)) }
+            _ => { Err(format!("tail on {} exited with {}", self.destination, status)) }
+        }
+    }
+}
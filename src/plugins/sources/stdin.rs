@@ -0,0 +1,47 @@
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::thread;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::pump;
+
+pub struct Stdin;
+
+impl Source for Stdin {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "stdin".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    // read on a thread so the input still gets idle ticks while a pipe is quiet
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+
+        let reader = thread::spawn(move || -> Result<(), String> {
+            let mut stdin = std::io::stdin().lock();
+            let mut line: Vec<u8> = Vec::new();
+
+            loop {
+                line.clear();
+
+                if stdin.read_until(b'\n', &mut line).map_err(|e| e.to_string())? == 0 {
+                    return Ok(());
+                }
+
+                let text = String::from_utf8_lossy(&line);
+                if tx.send(text.strip_suffix('\n').unwrap_or(&text).to_string()).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+
+        pump(rx, input);
+
+        // the reader may be blocked on a pipe that never ends
+        match input.done() {
+            true => { Ok(()) }
+            false => { reader.join().unwrap_or(Ok(())) }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use crate::plugins::{Input, PluginMetadata, Source};
+use super::pump;
+
+// accepts any number of connections, each sending newline separated lines
+pub struct Tcp {
+    listener: TcpListener,
+}
+
+impl Tcp {
+    pub fn bind(addr: &str) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr).map_err(|e| format!("{}: {}", addr, e))?;
+
+        Ok(Tcp { listener })
+    }
+}
+
+impl Source for Tcp {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "tcp".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, input: &mut dyn Input) -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+        let listener = self.listener.try_clone().map_err(|e| e.to_string())?;
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+
+                thread::spawn(move || {
+                    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                        if tx.send(line).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        pump(rx, input);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::Regex;
+use crate::render::hash_color;
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// 17-Oct-2026 10:15:30.123 INFO [main] org.apache.catalina.startup.Catalina.start message
+static CATALINA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?x)
+        ^(\d{2}-\p{L}{3,5}\.?-\d{4}\s\d{2}:\d{2}:\d{2}(?:\.\d+)?) # datetime
+        \s+
+        (SEVERE|WARNING|INFO|CONFIG|FINE|FINER|FINEST)         # level
+        \s+
+        \[([^\]]*)\]                                           # thread
+        \s+
+        (\S+)                                                  # class
+        \s?
+        (.*)$                                                  # message
+        "#
+    ).unwrap()
+});
+
+static STACK_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+(at\s+)(\S+)(\(.*\))\s*$").unwrap()
+});
+
+static MORE_FRAMES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+\.\.\. \d+ (more|common frames omitted)").unwrap()
+});
+
+static EXCEPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(Caused by: |Suppressed: |\s*)?([\w$.]+(?:Exception|Error|Throwable))(:.*)?$").unwrap()
+});
+
+pub struct Tomcat;
+
+impl Plugin for Tomcat {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "tomcat".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        if let Some(caps) = CATALINA.captures(line) {
+            let s = format!(
+                "{} {} {}{}{} {} {}",
+                caps[1].cyan(),
+                level(&caps[2]),
+                "[".bright_white(),
+                caps[3].color(hash_color(&caps[3])),
+                "]".bright_white(),
+                caps[4].dimmed(),
+                &caps[5],
+            );
+
+            return ParseResult::Parsed(
+                Record::new(s)
+                    .with_field("datetime", &caps[1])
+                    .with_field("level", &caps[2])
+                    .with_field("thread", &caps[3])
+                    .with_field("class", &caps[4])
+                    .with_field("message", &caps[5])
+            );
+        }
+
+        if let Some(caps) = STACK_FRAME.captures(line) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let s = format!("{}{}{}{}", indent, caps[1].dimmed(), caps[2].white(), caps[3].dimmed());
+
+            return ParseResult::Parsed(Record::new(s));
+        }
+
+        if MORE_FRAMES.is_match(line) {
+            return ParseResult::Parsed(Record::new(line.dimmed().to_string()));
+        }
+
+        if let Some(caps) = EXCEPTION.captures(line) {
+            let prefix = caps.get(1).map(|m| m.as_str().bright_red().to_string()).unwrap_or_default();
+            let message = caps.get(3).map(|m| m.as_str().red().to_string()).unwrap_or_default();
+            let s = format!("{}{}{}", prefix, caps[2].bright_red().bold(), message);
+
+            return ParseResult::Parsed(Record::new(s));
+        }
+
+        ParseResult::Parsed(Record::new(line.to_string()))
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "level", "thread", "class", "message"]
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        Some(format!(
+            "{} {} [{}] {} {}",
+            record.field("datetime")?,
+            record.field("level")?,
+            record.field("thread")?,
+            record.field("class")?,
+            record.field("message")?,
+        ))
+    }
+}
+
+fn level(name: &str) -> ColoredString {
+    match name {
+        "SEVERE" => { name.bright_red().bold() }
+        "WARNING" => { name.bright_yellow() }
+        "INFO" => { name.bright_green() }
+        "CONFIG" => { name.bright_blue() }
+        _ => { name.dimmed() }
+    }
+}
@@ -0,0 +1,115 @@
+use std::cell::{OnceCell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use colored::Colorize;
+use notify::RecommendedWatcher;
+use crate::reload;
+use super::{ParseResult, Plugin, PluginMetadata, Record};
+
+// loads a plugin file again, or says why it doesn't load
+pub type Load = fn(&Path) -> Result<Box<dyn Plugin>, String>;
+
+// A declared or scripted format that's loaded again from its file when the
+// file changes, from the next line on, so its author sees an edit without
+// starting splash over. One that doesn't load, or is renamed, leaves the
+// format as it was and says why.
+pub struct Watched {
+    name: String,
+    path: PathBuf,
+    load: Load,
+    plugin: RefCell<Box<dyn Plugin>>,
+    // the [plugin.NAME] options, given again to what's reloaded
+    options: RefCell<Option<toml::Table>>,
+    changed: Arc<AtomicBool>,
+    // when the file was written as it was loaded, for changes made before
+    // the watcher starts
+    modified: Option<SystemTime>,
+    // started by the first line, not by every splash that discovers plugins
+    watcher: OnceCell<Option<RecommendedWatcher>>,
+}
+
+impl Watched {
+    pub fn new(path: PathBuf, plugin: Box<dyn Plugin>, load: Load) -> Self {
+        Watched {
+            name: plugin.metadata().name,
+            modified: modified(&path),
+            path,
+            load,
+            plugin: RefCell::new(plugin),
+            options: RefCell::new(None),
+            changed: Arc::new(AtomicBool::new(false)),
+            watcher: OnceCell::new(),
+        }
+    }
+
+    fn poll(&self) {
+        self.watcher.get_or_init(|| {
+            if modified(&self.path) != self.modified {
+                self.changed.store(true, Ordering::Relaxed);
+            }
+
+            reload::watch_file(&self.path, self.changed.clone())
+        });
+
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        let loaded = (self.load)(&self.path).and_then(|plugin| {
+            match plugin.metadata().name {
+                name if name != self.name => { Err(format!("renamed to {}, restart splash to use it", name)) }
+                _ => { Ok(plugin) }
+            }
+        });
+
+        let configured = loaded.and_then(|plugin| {
+            match self.options.borrow().as_ref() {
+                Some(options) => { plugin.configure(options).map(|_| plugin) }
+                None => { Ok(plugin) }
+            }
+        });
+
+        match configured {
+            Ok(plugin) => {
+                *self.plugin.borrow_mut() = plugin;
+                eprintln!("{}", format!("plugin {} reloaded", self.name).dimmed());
+            }
+            Err(e) => { eprintln!("Error: plugin {} not reloaded: {}", self.name, e) }
+        }
+    }
+}
+
+impl Plugin for Watched {
+    fn metadata(&self) -> PluginMetadata {
+        self.plugin.borrow().metadata()
+    }
+
+    fn configure(&self, options: &toml::Table) -> Result<(), String> {
+        *self.options.borrow_mut() = Some(options.clone());
+        self.plugin.borrow().configure(options)
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        self.plugin.borrow().fields()
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        self.poll();
+        self.plugin.borrow().parse_line(line)
+    }
+
+    fn serialize(&self, record: &Record) -> Option<String> {
+        self.plugin.borrow().serialize(record)
+    }
+
+    fn detect_format(&self, lines: &[&str]) -> f64 {
+        self.plugin.borrow().detect_format(lines)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
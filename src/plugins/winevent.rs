@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::sync::LazyLock;
+use colored::{Colorize, ColoredString};
+use regex::Regex;
+use crate::time::parse_timestamp;
+use super::{FieldType, ParseResult, Plugin, PluginMetadata, Record};
+
+static EVENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<Event[\s>].*?</Event>").unwrap()
+});
+
+static TIME_CREATED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<TimeCreated\s+SystemTime=['"]([^'"]+)['"]"#).unwrap()
+});
+
+static PROVIDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<Provider\s+Name=['"]([^'"]+)['"]"#).unwrap()
+});
+
+static EVENT_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<EventID(?:\s[^>]*)?>(\d+)</EventID>").unwrap()
+});
+
+static LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<Level>(\d+)</Level>").unwrap()
+});
+
+static COMPUTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<Computer>([^<]*)</Computer>").unwrap()
+});
+
+// present when exported with /rd:true
+static MESSAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<Message>(.*?)</Message>").unwrap()
+});
+
+static DATA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<Data(?:\s+Name=['"]([^'"]*)['"])?\s*>(.*?)</Data>"#).unwrap()
+});
+
+// Windows events exported with `wevtutil qe <log> /f:xml`. Events can span
+// several lines, or several can share one, so input is buffered until a
+// closing </Event> arrives.
+pub struct WinEvent {
+    buffer: RefCell<String>,
+}
+
+impl WinEvent {
+    pub fn new() -> Self {
+        WinEvent { buffer: RefCell::new(String::new()) }
+    }
+}
+
+impl Plugin for WinEvent {
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata {
+            name: "winevent".to_string(),
+            styles: Vec::new(),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> ParseResult {
+        let mut buffer = self.buffer.borrow_mut();
+
+        if buffer.is_empty() && !line.contains("<Event") {
+            return ParseResult::NotMatched;
+        }
+
+        buffer.push_str(line);
+        buffer.push('\n');
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut record: Option<Record> = None;
+        let mut consumed = 0;
+
+        for m in EVENT.find_iter(&buffer) {
+            let event = parse_event(m.as_str());
+            lines.push(event.text.clone());
+            record.get_or_insert(event);
+            consumed = m.end();
+        }
+
+        buffer.drain(..consumed);
+        if !buffer.contains("<Event") {
+            buffer.clear();
+        }
+
+        match record {
+            Some(mut r) => {
+                r.text = lines.join("\n");
+                ParseResult::Parsed(r)
+            }
+            None => { ParseResult::Incomplete }
+        }
+    }
+
+    fn fields(&self) -> Vec<&'static str> {
+        vec!["datetime", "level", "provider", "event_id", "computer", "message"]
+    }
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn capture(re: &Regex, event: &str) -> String {
+    re.captures(event).map(|c| unescape(&c[1])).unwrap_or_default()
+}
+
+fn level_name(level: &str) -> &'static str {
+    match level {
+        "1" => { "CRITICAL" }
+        "2" => { "ERROR" }
+        "3" => { "WARNING" }
+        "5" => { "VERBOSE" }
+        _ => { "INFO" }
+    }
+}
+
+fn level(name: &str) -> ColoredString {
+    match name {
+        "CRITICAL" => { name.white().on_red().bold() }
+        "ERROR" => { name.bright_red().bold() }
+        "WARNING" => { name.bright_yellow() }
+        "VERBOSE" => { name.dimmed() }
+        _ => { name.bright_green() }
+    }
+}
+
+fn parse_event(event: &str) -> Record {
+    let time_created = capture(&TIME_CREATED, event);
+    let datetime = match parse_timestamp(&time_created) {
+        Some(t) => { t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string() }
+        None => { time_created.clone() }
+    };
+
+    let level_name = level_name(&capture(&LEVEL, event));
+    let provider = capture(&PROVIDER, event);
+    let event_id = capture(&EVENT_ID, event);
+    let computer = capture(&COMPUTER, event);
+
+    let message = match MESSAGE.captures(event) {
+        Some(c) => { unescape(&c[1]) }
+        None => {
+            DATA.captures_iter(event).map(|c| {
+                match c.get(1) {
+                    Some(name) => { format!("{}={}", name.as_str(), unescape(&c[2])) }
+                    None => { unescape(&c[2]) }
+                }
+            }).collect::<Vec<String>>().join(" ")
+        }
+    };
+    let message = message.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    let s = format!(
+        "{} {} {} {} {}",
+        datetime.cyan(),
+        level(level_name),
+        provider.bright_magenta(),
+        event_id.bright_blue(),
+        message,
+    );
+
+    Record::new(s)
+        .with_field("datetime", &time_created)
+        .with_field("level", level_name)
+        .with_field("provider", &provider)
+        .with_typed("event_id", &event_id, FieldType::Int)
+        .with_field("computer", &computer)
+        .with_field("message", &message)
+}
@@ -0,0 +1,272 @@
+use regex::Regex;
+use crate::plugins::{FieldValue, Record};
+
+// Expressions over a record's fields for --where:
+//
+//     status >= 500 && (method == "POST" || path =~ "^/api") && !(client == 10.0.0.1)
+//
+// A comparison is a field, an operator and a value. A field the mode read as
+// an int, float or bool compares as one with a value of that kind, exactly
+// for ints; other values that both read as numbers (10_000 and 1.5 do)
+// compare as numbers, anything else as text. A field on its own is true
+// when the record has it. Like --filter, a record without a field only
+// passes != and !~ tests on it.
+pub struct Query {
+    expr: Expr,
+}
+
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Has(String),
+    Compare(String, Op, String),
+    Matches(String, Regex, bool),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+const OPERATORS: [&str; 12] = ["&&", "||", "==", "!=", "<=", ">=", "=~", "!~", "<", ">", "!", "="];
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source).map_err(|e| format!("invalid --where '{}': {}", source, e))?;
+        let mut parser = Parser { tokens, position: 0 };
+
+        let expr = parser.or().map_err(|e| format!("invalid --where '{}': {}", source, e))?;
+
+        match parser.peek() {
+            None => { Ok(Query { expr }) }
+            Some(t) => { Err(format!("invalid --where '{}': unexpected {}", source, describe(t))) }
+        }
+    }
+
+    pub fn matches(&self, record: &Record) -> bool {
+        self.expr.eval(record)
+    }
+}
+
+impl Expr {
+    fn eval(&self, record: &Record) -> bool {
+        match self {
+            Expr::And(a, b) => { a.eval(record) && b.eval(record) }
+            Expr::Or(a, b) => { a.eval(record) || b.eval(record) }
+            Expr::Not(e) => { !e.eval(record) }
+            Expr::Has(field) => { record.field(field).is_some() }
+            Expr::Compare(field, op, value) => {
+                match (record.field(field), record.value(field)) {
+                    (Some(text), Some(typed)) => { compare(text, &typed, *op, value) }
+                    _ => { *op == Op::Ne }
+                }
+            }
+            Expr::Matches(field, re, negated) => {
+                record.field(field).is_some_and(|v| re.is_match(v)) != *negated
+            }
+        }
+    }
+}
+
+fn number(s: &str) -> Option<f64> {
+    let s = s.trim().replace('_', "");
+    s.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn compare(text: &str, typed: &FieldValue, op: Op, value: &str) -> bool {
+    let integer = value.trim().replace('_', "").parse::<i64>().ok();
+    let flag = value.trim().parse::<bool>().ok();
+
+    let ordering = match (typed, integer, number(value), flag) {
+        (FieldValue::Int(a), Some(b), _, _) => { Some(a.cmp(&b)) }
+        (FieldValue::Int(a), None, Some(b), _) => { (*a as f64).partial_cmp(&b) }
+        (FieldValue::Float(a), _, Some(b), _) => { a.partial_cmp(&b) }
+        (FieldValue::Bool(a), _, _, Some(b)) => { Some(a.cmp(&b)) }
+        (FieldValue::Text(_), _, Some(b), _) if number(text).is_some() => { number(text).and_then(|a| a.partial_cmp(&b)) }
+        _ => { Some(text.cmp(value)) }
+    };
+
+    let ordering = match ordering {
+        Some(o) => { o }
+        None => { return false }
+    };
+
+    match op {
+        Op::Eq => { ordering.is_eq() }
+        Op::Ne => { ordering.is_ne() }
+        Op::Lt => { ordering.is_lt() }
+        Op::Le => { ordering.is_le() }
+        Op::Gt => { ordering.is_gt() }
+        Op::Ge => { ordering.is_ge() }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest: String = chars[i..].iter().take(2).collect();
+
+        match c {
+            _ if c.is_whitespace() => { i += 1 }
+            '(' => { tokens.push(Token::Open); i += 1 }
+            ')' => { tokens.push(Token::Close); i += 1 }
+            '"' | '\'' => {
+                let mut text: String = "".to_owned();
+                i += 1;
+
+                loop {
+                    match chars.get(i) {
+                        None => { return Err("unterminated string".to_string()) }
+                        Some('\\') if chars.get(i + 1).is_some() => { text.push(chars[i + 1]); i += 2 }
+                        Some(q) if *q == c => { i += 1; break }
+                        Some(other) => { text.push(*other); i += 1 }
+                    }
+                }
+
+                tokens.push(Token::Text(text));
+            }
+            _ => {
+                if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+                    tokens.push(Token::Op(op));
+                    i += op.len();
+                    continue;
+                }
+
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()\"'&|=!<>".contains(chars[i]) {
+                    i += 1;
+                }
+
+                if i == start {
+                    return Err(format!("unexpected '{}'", c));
+                }
+
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(w) => { format!("'{}'", w) }
+        Token::Text(t) => { format!("\"{}\"", t) }
+        Token::Op(op) => { format!("'{}'", op) }
+        Token::Open => { "'('".to_string() }
+        Token::Close => { "')'".to_string() }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        match self.peek() {
+            Some(Token::Op(o)) if *o == op => { self.position += 1; true }
+            _ => { false }
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        match self.eat("!") {
+            true => { Ok(Expr::Not(Box::new(self.not()?))) }
+            false => { self.primary() }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let field = match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+
+                return match self.next() {
+                    Some(Token::Close) => { Ok(expr) }
+                    Some(t) => { Err(format!("expected ')' but found {}", describe(&t))) }
+                    None => { Err("missing ')'".to_string()) }
+                };
+            }
+            Some(Token::Word(field)) => { field }
+            Some(t) => { return Err(format!("expected a field name but found {}", describe(&t))) }
+            None => { return Err("expected a field name at the end".to_string()) }
+        };
+
+        let op = match self.peek() {
+            Some(Token::Op(op)) if !["&&", "||", "!"].contains(op) => { *op }
+            _ => { return Ok(Expr::Has(field)) }
+        };
+        self.position += 1;
+
+        let value = match self.next() {
+            Some(Token::Word(w)) | Some(Token::Text(w)) => { w }
+            Some(t) => { return Err(format!("expected a value after {} but found {}", op, describe(&t))) }
+            None => { return Err(format!("expected a value after {}", op)) }
+        };
+
+        let regex = |p: &str| Regex::new(p).map_err(|e| e.to_string());
+
+        Ok(match op {
+            "==" | "=" => { Expr::Compare(field, Op::Eq, value) }
+            "!=" => { Expr::Compare(field, Op::Ne, value) }
+            "<" => { Expr::Compare(field, Op::Lt, value) }
+            "<=" => { Expr::Compare(field, Op::Le, value) }
+            ">" => { Expr::Compare(field, Op::Gt, value) }
+            ">=" => { Expr::Compare(field, Op::Ge, value) }
+            "=~" => { Expr::Matches(field, regex(&value)?, false) }
+            _ => { Expr::Matches(field, regex(&value)?, true) }
+        })
+    }
+}
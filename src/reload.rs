@@ -0,0 +1,90 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use colored::Colorize;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::emphasis::Emphasis;
+
+static HANGUP: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn hangup(_: libc::c_int) {
+    HANGUP.store(true, Ordering::Relaxed);
+}
+
+// what a reload of the config file changes in a running pipeline; the theme
+// and the ad-hoc matchers are global and set by the reload itself
+pub struct Settings {
+    pub emphasis: Emphasis,
+    pub widths: Vec<(String, usize)>,
+}
+
+type Apply = Box<dyn FnMut() -> Result<Settings, String> + Send>;
+
+// Reloads the config file when it changes or splash gets a SIGHUP. apply
+// reads it again and only installs what it sets once all of it is valid, so
+// a mistake leaves the old settings in place and is reported.
+pub struct Reload {
+    changed: Arc<AtomicBool>,
+    _watcher: Option<RecommendedWatcher>,
+    apply: Apply,
+}
+
+// Raises the flag whenever the file is written or replaced, for as long as
+// the watcher is kept. None when its directory can't be watched.
+pub fn watch_file(path: &Path, flag: Arc<AtomicBool>) -> Option<RecommendedWatcher> {
+    let name: Option<OsString> = path.file_name().map(|n| n.to_os_string());
+
+    // editors often replace a file rather than write to it, which only
+    // shows in its directory
+    let dir = match path.parent() {
+        Some(dir) if dir.as_os_str().is_empty() => { Path::new(".") }
+        Some(dir) => { dir }
+        None => { Path::new("/") }
+    };
+
+    Some(dir).filter(|d| d.is_dir()).and_then(|dir| {
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if let Ok(event) = event {
+                let ours = event.paths.iter().any(|p| p.file_name() == name.as_deref());
+
+                if ours && (event.kind.is_modify() || event.kind.is_create()) {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            }
+        }).ok()?;
+
+        watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+        Some(watcher)
+    })
+}
+
+impl Reload {
+    pub fn watch(path: &Path, apply: Apply) -> Self {
+        unsafe { libc::signal(libc::SIGHUP, hangup as *const () as libc::sighandler_t) };
+
+        let changed = Arc::new(AtomicBool::new(false));
+        let watcher = watch_file(path, changed.clone());
+
+        Reload { changed, _watcher: watcher, apply }
+    }
+
+    pub fn poll(&mut self) -> Option<Settings> {
+        let hangup = HANGUP.swap(false, Ordering::Relaxed);
+
+        if !self.changed.swap(false, Ordering::Relaxed) && !hangup {
+            return None;
+        }
+
+        match (self.apply)() {
+            Ok(settings) => {
+                eprintln!("{}", "config reloaded".dimmed());
+                Some(settings)
+            }
+            Err(e) => {
+                eprintln!("Error: config not reloaded: {}", e);
+                None
+            }
+        }
+    }
+}
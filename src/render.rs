@@ -0,0 +1,368 @@
+use std::sync::LazyLock;
+use colored::Color;
+use regex::Regex;
+use crate::colors::rgb_of_256;
+
+static SGR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-9;]*m").unwrap()
+});
+
+const RESET: &str = "\x1b[0m";
+
+pub fn strip_ansi(s: &str) -> String {
+    SGR.replace_all(s, "").to_string()
+}
+
+// Shortens text to at most width characters by cutting out its middle, so
+// both how it starts and how it ends (a file extension, a version) stay.
+pub fn ellipsize(s: &str, width: usize) -> String {
+    let chars: Vec<char> = s.chars().collect();
+
+    if chars.len() <= width {
+        return s.to_string();
+    }
+
+    let keep = width.saturating_sub(1);
+    let extension = s.rfind('.').map(|i| s[i..].chars().count()).filter(|n| *n <= 8).unwrap_or(0);
+    let tail = (keep / 3).max(extension).min(keep / 2);
+    let head = keep - tail;
+
+    let mut out: String = chars[..head].iter().collect();
+    out.push('…');
+    out.extend(&chars[chars.len() - tail..]);
+
+    out
+}
+
+// Breaks text into lines of at most width characters.
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+
+    match chars.is_empty() {
+        true => { vec!["".to_string()] }
+        false => { chars.chunks(width.max(1)).map(|c| c.iter().collect()).collect() }
+    }
+}
+
+// Cuts a colored line to at most width visible characters, the last of them
+// an ellipsis when anything was cut.
+pub fn truncate(s: &str, width: usize) -> String {
+    let plain = strip_ansi(s);
+
+    let end = match plain.char_indices().nth(width.saturating_sub(1)) {
+        Some((i, _)) if plain.chars().count() > width => { i }
+        _ => { return s.to_string() }
+    };
+
+    let cut = insert(s, end, "\0");
+    let head = cut.split('\0').next().unwrap_or_default();
+
+    match head.contains('\x1b') {
+        true => { format!("{}…{}", head, RESET) }
+        false => { format!("{}…", head) }
+    }
+}
+
+// Breaks a colored line into lines of at most width visible characters,
+// those after the first indented, each carrying on in the style the last one
+// ended with.
+pub fn wrap_hanging(s: &str, width: usize, indent: &str) -> Vec<String> {
+    let plain = strip_ansi(s);
+    let first = width.max(1);
+    let rest = width.saturating_sub(indent.chars().count()).max(1);
+
+    let mut breaks: Vec<usize> = Vec::new();
+    let mut count = 0;
+    let mut limit = first;
+
+    for (i, _) in plain.char_indices() {
+        if count == limit {
+            breaks.push(i);
+            count = 0;
+            limit = rest;
+        }
+        count += 1;
+    }
+
+    if breaks.is_empty() {
+        return vec![s.to_string()];
+    }
+
+    let marked = breaks.iter().rev().fold(s.to_string(), |text, at| insert(&text, *at, "\0"));
+    let pieces: Vec<&str> = marked.split('\0').collect();
+
+    let mut lines = Vec::new();
+    let mut style: String = "".to_owned();
+
+    for (i, piece) in pieces.iter().enumerate() {
+        let line = match i {
+            0 => { piece.to_string() }
+            _ => { format!("{}{}{}", indent, style, piece) }
+        };
+
+        style = style_at(&format!("{}{}", style, piece), strip_ansi(piece).len());
+
+        match style.is_empty() {
+            true => { lines.push(line) }
+            false => { lines.push(format!("{}{}", line, RESET)) }
+        }
+    }
+
+    lines
+}
+
+// Layers a style over already colored text. Ranges are byte offsets into the
+// visible (stripped) text and must be sorted and non-overlapping. The overlay
+// is re-applied after every escape sequence inside a range so it stays on top,
+// and the underlying style is restored where a range ends.
+pub fn overlay(s: &str, ranges: &[(usize, usize)], on: &str, off: &str) -> String {
+    if ranges.is_empty() {
+        return s.to_string();
+    }
+
+    let mut out: String = "".to_owned();
+    let mut underlying: String = "".to_owned();
+    let mut visible = 0;
+    let mut pos = 0;
+    let mut range = 0;
+    let mut inside = false;
+
+    let escapes: Vec<(usize, usize)> = SGR.find_iter(s).map(|m| (m.start(), m.end())).collect();
+    let mut escape = 0;
+
+    while pos < s.len() {
+        if escape < escapes.len() && escapes[escape].0 == pos {
+            let seq = &s[pos..escapes[escape].1];
+
+            if seq == RESET {
+                underlying.clear();
+            } else {
+                underlying.push_str(seq);
+            }
+
+            out.push_str(seq);
+            if inside {
+                out.push_str(on);
+            }
+
+            pos = escapes[escape].1;
+            escape += 1;
+            continue;
+        }
+
+        if inside && visible == ranges[range].1 {
+            out.push_str(off);
+            out.push_str(&underlying);
+            inside = false;
+            range += 1;
+        }
+
+        if !inside && range < ranges.len() && visible == ranges[range].0 {
+            out.push_str(on);
+            inside = true;
+        }
+
+        let c = s[pos..].chars().next().unwrap();
+        out.push(c);
+        pos += c.len_utf8();
+        visible += c.len_utf8();
+    }
+
+    if inside {
+        out.push_str(off);
+        out.push_str(&underlying);
+    }
+
+    out
+}
+
+// The escape sequences in effect at a byte offset into the visible text, so
+// a piece of colored text can be shown elsewhere in the same style.
+pub fn style_at(s: &str, offset: usize) -> String {
+    let mut style: String = "".to_owned();
+    let mut visible = 0;
+    let mut pos = 0;
+
+    for m in SGR.find_iter(s) {
+        visible += m.start() - pos;
+        if visible > offset {
+            break;
+        }
+
+        match m.as_str() {
+            RESET => { style.clear() }
+            seq => { style.push_str(seq) }
+        }
+
+        pos = m.end();
+    }
+
+    style
+}
+
+// Where each value shows in a line, looked for in order so a status of 200
+// isn't found in the year before it. Values found nowhere after the last one
+// are looked for from the start.
+pub fn locate(plain: &str, values: &[&str]) -> Vec<Option<usize>> {
+    let mut offsets = Vec::new();
+    let mut cursor = 0;
+
+    for value in values {
+        if value.is_empty() {
+            offsets.push(None);
+            continue;
+        }
+
+        match plain[cursor..].find(value) {
+            Some(i) => {
+                offsets.push(Some(cursor + i));
+                cursor += i + value.len();
+            }
+            None => { offsets.push(plain.find(value)) }
+        }
+    }
+
+    offsets
+}
+
+// Puts text into a colored line at a byte offset into the visible text,
+// after any escape sequences there, so it takes no style from what ends.
+pub fn insert(s: &str, offset: usize, text: &str) -> String {
+    let mut visible = 0;
+    let mut pos = 0;
+
+    for m in SGR.find_iter(s) {
+        if visible + (m.start() - pos) > offset {
+            break;
+        }
+
+        visible += m.start() - pos;
+        pos = m.end();
+    }
+
+    let at = (pos + (offset - visible)).min(s.len());
+
+    format!("{}{}{}", &s[..at], text, &s[at..])
+}
+
+pub fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => { last.1 = last.1.max(end) }
+            _ => { merged.push((start, end)) }
+        }
+    }
+
+    merged
+}
+
+const HASH_PALETTE: [Color; 10] = [
+    Color::BrightBlue,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+// a stable color for a name, so the same thread or tag always looks the same
+pub fn hash_color(name: &str) -> Color {
+    let hash = name.bytes().fold(5381u32, |h, b| h.wrapping_mul(33) ^ b as u32);
+
+    HASH_PALETTE[hash as usize % HASH_PALETTE.len()]
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn html_color(code: u8) -> Option<&'static str> {
+    let colors = [
+        "#1d1f21", "#cc6666", "#b5bd68", "#f0c674", "#81a2be", "#b294bb", "#8abeb7", "#c5c8c6",
+        "#666666", "#d54e53", "#b9ca4a", "#e7c547", "#7aa6da", "#c397d8", "#70c0b1", "#eaeaea",
+    ];
+
+    match code {
+        30..=37 => { Some(colors[(code - 30) as usize]) }
+        90..=97 => { Some(colors[(code - 90 + 8) as usize]) }
+        40..=47 => { Some(colors[(code - 40) as usize]) }
+        100..=107 => { Some(colors[(code - 100 + 8) as usize]) }
+        _ => { None }
+    }
+}
+
+// converts SGR escape sequences into inline styled spans
+pub fn ansi_to_html(s: &str) -> String {
+    let mut out: String = "".to_owned();
+    let mut styles: Vec<String> = Vec::new();
+    let mut open = false;
+    let mut last = 0;
+
+    for m in SGR.find_iter(s) {
+        out.push_str(&html_escape(&s[last..m.start()]));
+        last = m.end();
+
+        let codes: Vec<u8> = s[m.start() + 2..m.end() - 1].split(';').map(|c| c.parse::<u8>().unwrap_or(0)).collect();
+        let mut i = 0;
+
+        while i < codes.len() {
+            let code = codes[i];
+            i += 1;
+
+            match code {
+                // 38;5;N and 38;2;R;G;B, and the same for backgrounds
+                38 | 48 => {
+                    let rgb = match codes.get(i) {
+                        Some(5) => { codes.get(i + 1).map(|n| rgb_of_256(*n)) }
+                        Some(2) if i + 3 < codes.len() => { Some((codes[i + 1], codes[i + 2], codes[i + 3])) }
+                        _ => { None }
+                    };
+                    i += match codes.get(i) {
+                        Some(5) => { 2 }
+                        Some(2) => { 4 }
+                        _ => { 0 }
+                    };
+
+                    if let Some((r, g, b)) = rgb {
+                        let property = if code == 38 { "color" } else { "background" };
+                        styles.push(format!("{}:#{:02x}{:02x}{:02x}", property, r, g, b));
+                    }
+                }
+                0 => { styles.clear() }
+                1 => { styles.push("font-weight:bold".to_string()) }
+                2 => { styles.push("opacity:0.6".to_string()) }
+                3 => { styles.push("font-style:italic".to_string()) }
+                4 => { styles.push("text-decoration:underline".to_string()) }
+                7 => { styles.push("background:#c5c8c6;color:#1d1f21".to_string()) }
+                30..=37 | 90..=97 => { styles.push(format!("color:{}", html_color(code).unwrap())) }
+                40..=47 | 100..=107 => { styles.push(format!("background:{}", html_color(code).unwrap())) }
+                _ => {}
+            }
+        }
+
+        if open {
+            out.push_str("</span>");
+            open = false;
+        }
+
+        if !styles.is_empty() {
+            out.push_str(&format!("<span style=\"{}\">", styles.join(";")));
+            open = true;
+        }
+    }
+
+    out.push_str(&html_escape(&s[last..]));
+    if open {
+        out.push_str("</span>");
+    }
+
+    out
+}
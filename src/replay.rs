@@ -0,0 +1,39 @@
+use std::thread;
+use chrono::{DateTime, FixedOffset};
+use crate::plugins::Record;
+use crate::time::parse_timestamp;
+
+// Holds each record back for as long as passed between it and the previous
+// one in the log, divided by the speed. Records without a timestamp, and
+// ones logged out of order, go straight through.
+pub struct Replay {
+    speed: f64,
+    previous: Option<DateTime<FixedOffset>>,
+}
+
+impl Replay {
+    pub fn new(speed: f64) -> Result<Self, String> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(format!("invalid speed {}, expected a number above 0", speed));
+        }
+
+        Ok(Replay { speed, previous: None })
+    }
+
+    pub fn wait(&mut self, record: &Record) {
+        let t = match record.field("datetime").and_then(parse_timestamp) {
+            Some(t) => { t }
+            None => { return }
+        };
+
+        if let Some(previous) = self.previous.filter(|p| t > *p) {
+            if let Ok(delay) = (t - previous).to_std() {
+                thread::sleep(delay.div_f64(self.speed));
+            }
+        }
+
+        if self.previous.is_none_or(|p| t > p) {
+            self.previous = Some(t);
+        }
+    }
+}
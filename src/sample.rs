@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+enum Rate {
+    // keep the first M of every N lines
+    Sample { keep: u64, every: u64 },
+    // keep at most N lines in each window
+    Limit { max: u64, window: Duration },
+}
+
+// Thins a busy stream, either by a fixed fraction (--sample 1/100) or to a
+// ceiling on lines per second, minute or hour (--rate-limit 500/s), and
+// counts what it dropped so it can be shown.
+pub struct Sampler {
+    rate: Rate,
+    seen: u64,
+    started: Instant,
+    skipped: u64,
+}
+
+impl Sampler {
+    pub fn sample(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid sample '{}', expected M/N like 1/100", spec);
+
+        let (keep, every) = spec.split_once('/').ok_or_else(invalid)?;
+        let keep: u64 = keep.trim().parse().map_err(|_| invalid())?;
+        let every: u64 = every.trim().parse().map_err(|_| invalid())?;
+
+        if keep == 0 || every < keep {
+            return Err(invalid());
+        }
+
+        Ok(Sampler::new(Rate::Sample { keep, every }))
+    }
+
+    pub fn rate_limit(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid rate limit '{}', expected N/s, N/m or N/h like 500/s", spec);
+
+        let (max, unit) = spec.split_once('/').ok_or_else(invalid)?;
+        let max: u64 = max.trim().parse().map_err(|_| invalid())?;
+
+        let window = match unit.trim() {
+            "s" => { Duration::from_secs(1) }
+            "m" => { Duration::from_secs(60) }
+            "h" => { Duration::from_secs(3600) }
+            _ => { return Err(invalid()) }
+        };
+
+        match max {
+            0 => { Err(invalid()) }
+            _ => { Ok(Sampler::new(Rate::Limit { max, window })) }
+        }
+    }
+
+    fn new(rate: Rate) -> Self {
+        Sampler { rate, seen: 0, started: Instant::now(), skipped: 0 }
+    }
+
+    pub fn keep(&mut self) -> bool {
+        let keep = match self.rate {
+            Rate::Sample { keep, every } => { self.seen % every < keep }
+            Rate::Limit { max, window } => {
+                if self.started.elapsed() >= window {
+                    self.started = Instant::now();
+                    self.seen = 0;
+                }
+
+                self.seen < max
+            }
+        };
+
+        self.seen += 1;
+
+        if !keep {
+            self.skipped += 1;
+        }
+
+        keep
+    }
+
+    // lines dropped since this was last asked
+    pub fn skipped(&mut self) -> u64 {
+        std::mem::take(&mut self.skipped)
+    }
+}
@@ -0,0 +1,90 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use crate::colors::{self, Depth};
+use crate::plugins::Record;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Debug,
+    Info,
+    #[value(alias = "warn")]
+    #[serde(alias = "warn")]
+    Warning,
+    Error,
+}
+
+impl Severity {
+    // shapes that tell severities apart without relying on color
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Severity::Debug => { "·" }
+            Severity::Info => { "●" }
+            Severity::Warning => { "▲" }
+            Severity::Error => { "✖" }
+        }
+    }
+}
+
+// --tint-lines: debug lines dimmed and warnings and errors in yellow and red,
+// over the whole line rather than the level alone. As a band dark shades go
+// behind the line, which keeps the mode's own colors; with only 16 colors
+// that isn't possible and the band is black text on yellow or red.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tint {
+    #[default]
+    Fg,
+    Band,
+}
+
+impl Tint {
+    // the escape sequences that start and end the tint of a severity
+    pub fn of(&self, severity: Severity) -> Option<(&'static str, &'static str)> {
+        let shades = colors::depth() > Depth::Ansi16;
+
+        match (self, severity) {
+            (_, Severity::Debug) => { Some(("\x1b[2m", "\x1b[22m")) }
+            (_, Severity::Info) => { None }
+            (Tint::Fg, Severity::Warning) => { Some(("\x1b[33m", "\x1b[39m")) }
+            (Tint::Fg, Severity::Error) => { Some(("\x1b[31m", "\x1b[39m")) }
+            (Tint::Band, Severity::Warning) if shades => { Some(("\x1b[48;5;58m", "\x1b[49m")) }
+            (Tint::Band, Severity::Error) if shades => { Some(("\x1b[48;5;52m", "\x1b[49m")) }
+            (Tint::Band, Severity::Warning) => { Some(("\x1b[30;43m", "\x1b[39;49m")) }
+            (Tint::Band, Severity::Error) => { Some(("\x1b[30;41m", "\x1b[39;49m")) }
+        }
+    }
+}
+
+// Normalizes the different ways modes record severity: level names,
+// syslog priorities and HTTP statuses.
+pub fn of(record: &Record) -> Option<Severity> {
+    if let Some(level) = record.field("level").or(record.field("priority")).or(record.field("severity")) {
+        return from_name(level);
+    }
+
+    let status = record.field("status")?.parse::<u16>().ok()?;
+    match status {
+        500.. => { Some(Severity::Error) }
+        400..=499 => { Some(Severity::Warning) }
+        _ => { Some(Severity::Info) }
+    }
+}
+
+fn from_name(name: &str) -> Option<Severity> {
+    match name.to_ascii_lowercase().as_str() {
+        "v" | "d" | "vrb" | "verbose" | "trace" | "trce" | "dbg" | "dbug" | "debug" | "fine" | "finer" | "finest" | "config" | "7" => {
+            Some(Severity::Debug)
+        }
+        "i" | "inf" | "info" | "information" | "informational" | "notice" | "5" | "6" => {
+            Some(Severity::Info)
+        }
+        "w" | "wrn" | "warn" | "warning" | "4" => {
+            Some(Severity::Warning)
+        }
+        "e" | "f" | "a" | "err" | "error" | "fail" | "ftl" | "fatal" | "crit" | "critical" | "severe" | "alert" | "emerg" | "0" | "1" | "2" | "3" => {
+            Some(Severity::Error)
+        }
+        _ => { None }
+    }
+}
@@ -0,0 +1,63 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use crate::numbers::count;
+use crate::stats::Stats;
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct Slo {
+    pub p50_latency_ms: Option<f64>,
+    pub p90_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+    pub p99_latency_ms: Option<f64>,
+
+    // percent of requests allowed to fail with a 5xx status
+    pub error_rate: Option<f64>,
+}
+
+impl Slo {
+    pub fn latency_budgets(&self) -> Vec<(f64, f64)> {
+        [
+            (50.0, self.p50_latency_ms),
+            (90.0, self.p90_latency_ms),
+            (95.0, self.p95_latency_ms),
+            (99.0, self.p99_latency_ms),
+        ]
+        .into_iter()
+        .filter_map(|(p, budget)| budget.map(|b| (p, b)))
+        .collect()
+    }
+
+    // prints each budget against the measured value, returns false on any violation
+    pub fn report(&self, stats: &Stats) -> bool {
+        let mut ok = true;
+
+        println!("{} {} requests, {} errors", "SLO".bold(), count(stats.requests), count(stats.errors));
+
+        if let Some(budget) = self.error_rate {
+            let line = match stats.error_rate() {
+                Some(rate) => { check(rate <= budget, &mut ok, format!("error rate {:.2}%", rate)) }
+                None => { "error rate n/a".dimmed().to_string() }
+            };
+            println!("  {} (budget {}%)", line, budget);
+        }
+
+        for (p, budget) in self.latency_budgets() {
+            let line = match stats.latency_percentile(p) {
+                Some(ms) => { check(ms <= budget, &mut ok, format!("p{} latency {:.1}ms", p, ms)) }
+                None => { format!("p{} latency n/a", p).dimmed().to_string() }
+            };
+            println!("  {} (budget {}ms)", line, budget);
+        }
+
+        ok
+    }
+}
+
+fn check(within: bool, ok: &mut bool, text: String) -> String {
+    if within {
+        return format!("{} {}", "✓".bright_green(), text.bright_green());
+    }
+
+    *ok = false;
+    format!("{} {}", "✗".bright_red().bold(), text.bright_red().bold())
+}
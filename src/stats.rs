@@ -0,0 +1,72 @@
+use std::time::Duration;
+use crate::plugins::Record;
+use crate::severity::{self, Severity};
+
+#[derive(Default)]
+pub struct Stats {
+    pub records: u64,
+    pub requests: u64,
+    pub errors: u64,
+    // records at error severity, whatever the mode
+    pub error_records: u64,
+    pub bytes: u64,
+    latencies: Vec<f64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    // duration is a latency measured outside the record, e.g. by request pairing
+    pub fn add(&mut self, record: &Record, duration: Option<Duration>) {
+        self.records += 1;
+
+        if severity::of(record) == Some(Severity::Error) {
+            self.error_records += 1;
+        }
+
+        if let Some(status) = record.field("status").and_then(|s| s.parse::<u16>().ok()) {
+            self.requests += 1;
+
+            if status >= 500 {
+                self.errors += 1;
+            }
+        }
+
+        if let Some(size) = record.field("size").and_then(|s| s.parse::<u64>().ok()) {
+            self.bytes += size;
+        }
+
+        let latency = match duration {
+            Some(d) => { Some(d.as_secs_f64() * 1000.0) }
+            None => { record.field("duration_ms").and_then(|ms| ms.parse().ok()) }
+        };
+
+        if let Some(ms) = latency {
+            self.latencies.push(ms);
+        }
+    }
+
+    // percent of requests with a 5xx status
+    pub fn error_rate(&self) -> Option<f64> {
+        match self.requests {
+            0 => { None }
+            n => { Some(self.errors as f64 * 100.0 / n as f64) }
+        }
+    }
+
+    // nearest-rank percentile of the recorded latencies in milliseconds
+    pub fn latency_percentile(&self, p: f64) -> Option<f64> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+
+        let mut sorted = self.latencies.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+}
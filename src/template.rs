@@ -0,0 +1,107 @@
+use colored::control::SHOULD_COLORIZE;
+use crate::plugins::Record;
+use crate::render::{ellipsize, locate, strip_ansi, style_at};
+
+const RESET: &str = "\x1b[0m";
+
+enum Part {
+    Text(String),
+    Field(String),
+}
+
+// A layout for a record's fields, --format '{client} {status} {request}'.
+// Each field keeps the style the mode gave it in the record's text, fields a
+// record doesn't have are shown as -, and {{ and }} are literal braces.
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text: String = "".to_owned();
+        let mut chars = spec.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    text.push(c);
+                    chars.next();
+                }
+                ('{', _) => {
+                    let mut name: String = "".to_owned();
+                    let mut closed = false;
+
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+
+                    if !closed || name.trim().is_empty() || name.contains('{') {
+                        return Err(format!("invalid format '{}': expected {{FIELD}} at '{{{}'", spec, name));
+                    }
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(name.trim().to_string()));
+                }
+                ('}', _) => { return Err(format!("invalid format '{}': unmatched '}}', use }}}} for a brace", spec)) }
+                _ => { text.push(c) }
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Template { parts })
+    }
+
+    pub fn render(&self, record: &Record, widths: &[(String, usize)]) -> String {
+        let values: Vec<&str> = record.fields.iter().map(|(_, v)| v.as_str()).collect();
+        let offsets = locate(&strip_ansi(&record.text), &values);
+        let colorize = SHOULD_COLORIZE.should_colorize();
+        let mut out: String = "".to_owned();
+
+        for part in &self.parts {
+            let (name, value) = match part {
+                Part::Text(text) => {
+                    out.push_str(text);
+                    continue;
+                }
+                Part::Field(name) => {
+                    match record.field(name) {
+                        Some(value) => { (name, value) }
+                        None => {
+                            out.push('-');
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let shown = match widths.iter().find(|(f, _)| f == name) {
+                Some((_, width)) => { ellipsize(value, *width) }
+                None => { value.to_string() }
+            };
+
+            let offset = record.fields.iter().position(|(n, _)| n == name).and_then(|i| offsets[i]);
+
+            let style = match (colorize, offset) {
+                (true, Some(offset)) => { style_at(&record.text, offset) }
+                _ => { "".to_string() }
+            };
+
+            match style.is_empty() {
+                true => { out.push_str(&shown) }
+                false => { out.push_str(&format!("{}{}{}", style, shown, RESET)) }
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::render::{truncate, wrap_hanging};
+
+// below this many columns records with fields are stacked, one field a line
+pub const NARROW: usize = 60;
+
+// continuation lines of --wrap
+const HANGING_INDENT: &str = "    ";
+
+static WATCH: Once = Once::new();
+static RESIZED: AtomicBool = AtomicBool::new(true);
+static COLUMNS: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn resized(_: libc::c_int) {
+    RESIZED.store(true, Ordering::Relaxed);
+}
+
+// The width of the terminal on stdout, or $COLUMNS when it isn't one. It is
+// asked for again after a SIGWINCH rather than on every call.
+pub fn width() -> Option<usize> {
+    WATCH.call_once(|| unsafe {
+        libc::signal(libc::SIGWINCH, resized as *const () as libc::sighandler_t);
+    });
+
+    if RESIZED.swap(false, Ordering::Relaxed) {
+        COLUMNS.store(query().unwrap_or(0), Ordering::Relaxed);
+    }
+
+    match COLUMNS.load(Ordering::Relaxed) {
+        0 => { None }
+        n => { Some(n) }
+    }
+}
+
+pub fn is_narrow() -> bool {
+    width().is_some_and(|w| w < NARROW)
+}
+
+fn query() -> Option<usize> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0 && size.ws_col > 0 {
+        return Some(size.ws_col as usize);
+    }
+
+    std::env::var("COLUMNS").ok().and_then(|c| c.parse::<usize>().ok()).filter(|c| *c > 0)
+}
+
+// What to do with lines longer than the terminal is wide: --truncate cuts
+// them, at a given width or the terminal's, and --wrap breaks them with a
+// hanging indent. Without a terminal or a width lines are left alone.
+pub enum Fit {
+    Truncate(Option<usize>),
+    Wrap,
+}
+
+impl Fit {
+    pub fn apply(&self, text: &str) -> String {
+        let lines = text.split('\n');
+
+        let limit = match self {
+            Fit::Truncate(Some(columns)) => { Some(*columns) }
+            _ => { width() }
+        };
+
+        match (self, limit) {
+            (Fit::Truncate(_), Some(columns)) => {
+                lines.map(|l| truncate(l, columns)).collect::<Vec<String>>().join("\n")
+            }
+            (Fit::Wrap, Some(columns)) => {
+                lines.flat_map(|l| wrap_hanging(l, columns, HANGING_INDENT)).collect::<Vec<String>>().join("\n")
+            }
+            _ => { text.to_string() }
+        }
+    }
+}
@@ -0,0 +1,668 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::net::IpAddr;
+use std::io::{IsTerminal, Read, Write};
+use std::os::fd::AsRawFd;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+use clap::ValueEnum;
+use colored::control::SHOULD_COLORIZE;
+use colored::{Color, Colorize};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use crate::colors;
+use crate::config;
+use crate::plugins::{Plugin, Record};
+use crate::render::{locate, overlay, strip_ansi};
+use crate::severity::{self, Severity};
+
+static SGR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[([0-9;]*)m").unwrap()
+});
+
+// A reloaded config file replaces the theme. The old one is leaked rather than
+// freed, as a line being colored may still hold it; that only happens when
+// someone edits the file.
+static THEME: RwLock<Option<&'static Theme>> = RwLock::new(None);
+
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Background {
+    Auto,
+    Dark,
+    Light,
+}
+
+// Replacement SGR parameters for the 16 ANSI foreground colors, black to
+// white and then bright black to bright white. None leaves a color alone.
+// Styles for plugins' semantic tags are keyed by "tag" or "plugin.tag", as
+// are the widths fields are shortened to and the styles of semantic roles
+// (see roles_of). A monochrome theme drops every color but keeps bold,
+// underline and the like.
+pub struct Theme {
+    palette: [Option<String>; 16],
+    styles: Vec<(String, String)>,
+    widths: Vec<(String, usize)>,
+    roles: Vec<(String, String)>,
+    monochrome: bool,
+}
+
+// the ANSI colors a theme can replace, in palette order
+pub const SLOTS: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright_black", "bright_red", "bright_green", "bright_yellow", "bright_blue", "bright_magenta", "bright_cyan", "bright_white",
+];
+
+pub const THEMES: [&str; 8] = ["default", "deuteranopia", "protanopia", "tritanopia", "solarized-dark", "solarized-light", "monochrome", "high-contrast"];
+
+// --palette: colors for a kind of color blindness, under any theme
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vision {
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+type Palette = [Option<&'static str>; 16];
+
+// the colors plugins are written for
+const DEFAULT_DARK: Palette = [None; 16];
+
+// darker shades in place of white, yellow and the bright colors, which wash
+// out on a light background
+const DEFAULT_LIGHT: Palette = [
+    None, None, Some("38;5;28"), Some("38;5;136"), None, None, Some("38;5;30"), Some("30"),
+    None, Some("31"), Some("38;5;28"), Some("38;5;136"), Some("34"), Some("35"), Some("38;5;30"), Some("30"),
+];
+
+// Okabe-Ito colors: red becomes vermillion and green becomes sky blue, so
+// errors and successes differ in lightness as well as hue
+const DEUTERANOPIA_DARK: Palette = [
+    None, Some("38;5;166"), Some("38;5;74"), Some("38;5;227"), None, Some("38;5;175"), None, None,
+    None, Some("38;5;208"), Some("38;5;117"), Some("38;5;227"), None, Some("38;5;212"), None, None,
+];
+
+const DEUTERANOPIA_LIGHT: Palette = [
+    None, Some("38;5;166"), Some("38;5;25"), Some("38;5;136"), None, Some("38;5;126"), Some("38;5;30"), Some("30"),
+    None, Some("38;5;166"), Some("38;5;25"), Some("38;5;136"), Some("34"), Some("38;5;126"), Some("38;5;30"), Some("30"),
+];
+
+// reds look dark to protanopes, so they get brighter oranges
+const PROTANOPIA_DARK: Palette = [
+    None, Some("38;5;202"), Some("38;5;74"), Some("38;5;227"), None, Some("38;5;175"), None, None,
+    None, Some("38;5;209"), Some("38;5;117"), Some("38;5;227"), None, Some("38;5;212"), None, None,
+];
+
+const PROTANOPIA_LIGHT: Palette = [
+    None, Some("38;5;202"), Some("38;5;25"), Some("38;5;136"), None, Some("38;5;126"), Some("38;5;30"), Some("30"),
+    None, Some("38;5;202"), Some("38;5;25"), Some("38;5;136"), Some("34"), Some("38;5;126"), Some("38;5;30"), Some("30"),
+];
+
+// Solarized's accents, with its base tones for the grays and whites: the
+// bright colors are the accents again, violet and orange, rather than the
+// base tones the Solarized terminal palette puts there
+const SOLARIZED_DARK: Palette = [
+    Some("38;2;7;54;66"), Some("38;2;220;50;47"), Some("38;2;133;153;0"), Some("38;2;181;137;0"),
+    Some("38;2;38;139;210"), Some("38;2;211;54;130"), Some("38;2;42;161;152"), Some("38;2;147;161;161"),
+    Some("38;2;88;110;117"), Some("38;2;203;75;22"), Some("38;2;133;153;0"), Some("38;2;181;137;0"),
+    Some("38;2;38;139;210"), Some("38;2;108;113;196"), Some("38;2;42;161;152"), Some("38;2;238;232;213"),
+];
+
+const SOLARIZED_LIGHT: Palette = [
+    Some("38;2;7;54;66"), Some("38;2;220;50;47"), Some("38;2;133;153;0"), Some("38;2;181;137;0"),
+    Some("38;2;38;139;210"), Some("38;2;211;54;130"), Some("38;2;42;161;152"), Some("38;2;88;110;117"),
+    Some("38;2;147;161;161"), Some("38;2;203;75;22"), Some("38;2;133;153;0"), Some("38;2;181;137;0"),
+    Some("38;2;38;139;210"), Some("38;2;108;113;196"), Some("38;2;42;161;152"), Some("38;2;7;54;66"),
+];
+
+// bold, and as far from the background as each hue goes; blue is hard to
+// read on black, so it becomes cyan
+const HIGH_CONTRAST_DARK: Palette = [
+    Some("37"), Some("1;91"), Some("1;92"), Some("1;93"), Some("1;96"), Some("1;95"), Some("1;96"), Some("1;97"),
+    Some("37"), Some("1;91"), Some("1;92"), Some("1;93"), Some("1;96"), Some("1;95"), Some("1;96"), Some("1;97"),
+];
+
+const HIGH_CONTRAST_LIGHT: Palette = [
+    Some("1;30"), Some("1;38;5;124"), Some("1;38;5;22"), Some("1;38;5;94"), Some("1;38;5;18"), Some("1;38;5;90"), Some("1;38;5;23"), Some("1;30"),
+    Some("38;5;238"), Some("1;38;5;124"), Some("1;38;5;22"), Some("1;38;5;94"), Some("1;38;5;18"), Some("1;38;5;90"), Some("1;38;5;23"), Some("1;30"),
+];
+
+// Blues and yellows look alike to tritanopes, as do blues and greens: yellow
+// becomes pink, green teal and blue a grayish violet, each a different
+// lightness
+const TRITANOPIA_DARK: Palette = [
+    None, None, Some("38;5;37"), Some("38;5;211"), Some("38;5;103"), None, Some("38;5;152"), None,
+    None, None, Some("38;5;43"), Some("38;5;218"), Some("38;5;146"), None, Some("38;5;159"), None,
+];
+
+const TRITANOPIA_LIGHT: Palette = [
+    None, None, Some("38;5;30"), Some("38;5;162"), Some("38;5;60"), None, Some("38;5;23"), Some("30"),
+    None, Some("31"), Some("38;5;30"), Some("38;5;162"), Some("38;5;60"), Some("35"), Some("38;5;23"), Some("30"),
+];
+
+fn vision_palette(vision: Vision, light: bool) -> Palette {
+    match (vision, light) {
+        (Vision::Deuteranopia, false) => { DEUTERANOPIA_DARK }
+        (Vision::Deuteranopia, true) => { DEUTERANOPIA_LIGHT }
+        (Vision::Protanopia, false) => { PROTANOPIA_DARK }
+        (Vision::Protanopia, true) => { PROTANOPIA_LIGHT }
+        (Vision::Tritanopia, false) => { TRITANOPIA_DARK }
+        (Vision::Tritanopia, true) => { TRITANOPIA_LIGHT }
+    }
+}
+
+fn is_light(background: Background) -> bool {
+    match background {
+        Background::Light => { true }
+        Background::Dark => { false }
+        Background::Auto => { detect_background() == Background::Light }
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme::from_palette(DEFAULT_DARK)
+    }
+
+    fn from_palette(palette: Palette) -> Self {
+        Theme {
+            palette: palette.map(|s| s.map(|s| s.to_string())),
+            styles: Vec::new(),
+            widths: Vec::new(),
+            roles: Vec::new(),
+            monochrome: false,
+        }
+    }
+
+    // A built-in theme in its variant for the background, one defined in the
+    // config file's [themes] or an imported one. The solarized themes and
+    // monochrome are made for one background and don't need to know it.
+    pub fn named(name: &str, background: Background, defined: &BTreeMap<String, toml::Table>) -> Result<Self, String> {
+        let palette = match name {
+            "solarized-dark" => { SOLARIZED_DARK }
+            "solarized-light" => { SOLARIZED_LIGHT }
+            "monochrome" => { return Ok(Theme { monochrome: true, ..Theme::dark() }) }
+            "default" | "high-contrast" => {
+                match (name, is_light(background)) {
+                    ("default", false) => { DEFAULT_DARK }
+                    ("default", true) => { DEFAULT_LIGHT }
+                    (_, false) => { HIGH_CONTRAST_DARK }
+                    (_, true) => { HIGH_CONTRAST_LIGHT }
+                }
+            }
+            _ if Vision::from_str(name, false).is_ok() => {
+                vision_palette(Vision::from_str(name, false)?, is_light(background))
+            }
+            _ => {
+                if let Some(table) = defined.get(name) {
+                    return Theme::from_table(table).map_err(|e| format!("theme {} in the config file: {}", name, e));
+                }
+
+                let path = config::themes_dir().map(|d| d.join(format!("{}.toml", name)));
+
+                return match path {
+                    Some(p) if p.exists() => { Theme::load(&p) }
+                    _ => {
+                        let mut known: Vec<String> = THEMES.iter().map(|t| t.to_string()).collect();
+                        known.extend(defined.keys().cloned());
+                        known.extend(imported());
+                        Err(format!("unknown theme '{}', expected one of {}", name, known.join(", ")))
+                    }
+                };
+            }
+        };
+
+        Ok(Theme::from_palette(palette))
+    }
+
+    // an imported theme: slot names mapped to #rrggbb colors, a [styles]
+    // table for plugins' semantic tags, a [roles] table and a [widths] table
+    // for fields
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let table: toml::Table = toml::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        Theme::from_table(&table).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn from_table(table: &toml::Table) -> Result<Self, String> {
+        let mut palette: [Option<String>; 16] = Default::default();
+
+        for (i, slot) in SLOTS.iter().enumerate() {
+            if let Some(value) = table.get(*slot).and_then(|v| v.as_str()) {
+                let (r, g, b) = parse_hex(value).map_err(|e| format!("{} for {}", e, slot))?;
+                palette[i] = Some(format!("38;2;{};{};{}", r, g, b));
+            }
+        }
+
+        Ok(Theme { palette, styles: styles(table)?, widths: widths(table)?, roles: style_table(table, "roles")?, monochrome: false })
+    }
+
+    // --palette, replacing the colors of any theme but keeping its styles,
+    // roles and widths
+    pub fn with_vision(mut self, vision: Vision, background: Background) -> Self {
+        self.palette = vision_palette(vision, is_light(background)).map(|s| s.map(|s| s.to_string()));
+        self
+    }
+
+    // Styles for one plugin's fields, or status_2xx to status_5xx, winning
+    // over the theme's roles; the config file's [clf] table.
+    pub fn with_overrides(mut self, plugin: &str, fields: &[&str], overrides: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut roles: Vec<(String, String)> = Vec::new();
+
+        for (key, spec) in overrides {
+            let role = match key.strip_prefix("status_") {
+                Some(class) if matches!(class, "1xx" | "2xx" | "3xx" | "4xx" | "5xx") => { format!("status-{}", class) }
+                _ if fields.contains(&key.as_str()) => { key.clone() }
+                _ => { return Err(format!("unknown field '{}' in [{}], expected one of {}, status_1xx to status_5xx", key, plugin, fields.join(", "))) }
+            };
+
+            parse_style(spec).map_err(|e| format!("[{}] {}: {}", plugin, key, e))?;
+            roles.push((format!("{}.{}", plugin, role), spec.clone()));
+        }
+
+        roles.append(&mut self.roles);
+        self.roles = roles;
+
+        Ok(self)
+    }
+
+    // The style for a plugin's semantic tag: the theme's "plugin.tag", then
+    // its "tag", then the default the plugin suggests.
+    pub fn style<'a>(&'a self, plugin: &str, tag: &str, defaults: &'a [(String, String)]) -> Option<&'a str> {
+        let qualified = format!("{}.{}", plugin, tag);
+
+        self.styles.iter().find(|(t, _)| *t == qualified)
+            .or_else(|| self.styles.iter().find(|(t, _)| t == tag))
+            .or_else(|| defaults.iter().find(|(t, _)| t == tag))
+            .map(|(_, s)| s.as_str())
+    }
+
+    pub fn widths(&self, plugin: &str) -> Vec<(String, usize)> {
+        plugin_widths(&self.widths, plugin)
+    }
+
+    // the theme's colors, in as many colors as the terminal has
+    pub fn apply(&self, text: &str) -> String {
+        let extended = text.contains("8;5;") || text.contains("8;2;");
+
+        if self.palette.iter().all(|c| c.is_none()) && !self.monochrome && !extended {
+            return text.to_string();
+        }
+
+        SGR.replace_all(text, |c: &Captures| {
+            format!("\x1b[{}m", colors::degrade(&self.remap(&c[1])))
+        }).to_string()
+    }
+
+    fn remap(&self, params: &str) -> String {
+        let params: Vec<&str> = params.split(';').collect();
+        let mut out: Vec<String> = Vec::new();
+        let mut i = 0;
+
+        while i < params.len() {
+            let replacement = match params[i].parse::<usize>() {
+                // extended colors carry their own arguments
+                Ok(n @ (38 | 48)) => {
+                    let len = match params.get(i + 1) {
+                        Some(&"5") => { 3 }
+                        Some(&"2") => { 5 }
+                        _ => { 1 }
+                    };
+                    let end = (i + len).min(params.len());
+                    match (self.monochrome, n) {
+                        (true, 38) => { out.push("39".to_string()) }
+                        (true, _) => { out.push("49".to_string()) }
+                        (false, _) => { out.push(params[i..end].join(";")) }
+                    }
+                    i = end;
+                    continue;
+                }
+                // the default colors rather than nothing, as an empty sequence resets
+                Ok(30..=37 | 90..=97) if self.monochrome => {
+                    out.push("39".to_string());
+                    i += 1;
+                    continue;
+                }
+                Ok(40..=47 | 100..=107) if self.monochrome => {
+                    out.push("49".to_string());
+                    i += 1;
+                    continue;
+                }
+                Ok(n @ 30..=37) => { self.palette[n - 30].as_ref() }
+                Ok(n @ 90..=97) => { self.palette[n - 90 + 8].as_ref() }
+                _ => { None }
+            };
+
+            match replacement {
+                Some(r) => { out.push(r.clone()) }
+                None => { out.push(params[i].to_string()) }
+            }
+            i += 1;
+        }
+
+        out.join(";")
+    }
+}
+
+// Semantic roles a field can have, most specific first, each tried as
+// "plugin.role" and then "role": status-2xx to status-5xx for an HTTP status,
+// level-error, level-warning, level-info and level-debug for a severity, ip
+// for an address, datetime for a timestamp, and the field's own name.
+pub fn roles_of(name: &str, value: &str) -> Vec<String> {
+    let mut roles: Vec<String> = Vec::new();
+
+    if name == "status" && value.len() == 3 && value.chars().all(|c| c.is_ascii_digit()) {
+        roles.push(format!("status-{}xx", &value[..1]));
+    }
+
+    if matches!(name, "level" | "priority" | "severity") {
+        let record = Record::new("".to_string()).with_field("level", value);
+
+        match severity::of(&record) {
+            Some(Severity::Error) => { roles.push("level-error".to_string()) }
+            Some(Severity::Warning) => { roles.push("level-warning".to_string()) }
+            Some(Severity::Info) => { roles.push("level-info".to_string()) }
+            Some(Severity::Debug) => { roles.push("level-debug".to_string()) }
+            None => {}
+        }
+    }
+
+    if value.parse::<IpAddr>().is_ok() {
+        roles.push("ip".to_string());
+    }
+
+    if matches!(name, "datetime" | "timestamp" | "time" | "date") {
+        roles.push("datetime".to_string());
+    }
+
+    roles.push(name.to_string());
+    roles
+}
+
+impl Theme {
+    // the first of a field's roles the theme has a style for, with the style
+    pub fn role_style(&self, plugin: &str, name: &str, value: &str) -> Option<(String, &str)> {
+        roles_of(name, value).into_iter().find_map(|role| {
+            let qualified = format!("{}.{}", plugin, role);
+
+            self.roles.iter().find(|(r, _)| *r == qualified)
+                .or_else(|| self.roles.iter().find(|(r, _)| *r == role))
+                .map(|(_, s)| (role, s.as_str()))
+        })
+    }
+
+    // Restyles the fields of a record whose roles the theme has a style for,
+    // whatever colors the mode gave them.
+    pub fn paint_roles(&self, plugin: &dyn Plugin, mut record: Record) -> Record {
+        if self.roles.is_empty() || !SHOULD_COLORIZE.should_colorize() {
+            return record;
+        }
+
+        let plugin = plugin.metadata().name;
+        let plain = strip_ansi(&record.text);
+        let values: Vec<&str> = record.fields.iter().map(|(_, v)| v.as_str()).collect();
+        let offsets = locate(&plain, &values);
+
+        let style = |name: &str, value: &str| self.role_style(&plugin, name, value).map(|(_, s)| s);
+
+        let mut painted: Vec<(usize, usize, &str)> = record.fields.iter().zip(offsets)
+            .filter_map(|((name, value), offset)| Some((offset?, offset? + value.len(), style(name, value)?)))
+            .collect();
+
+        painted.sort();
+        painted.dedup_by(|b, a| b.0 < a.1);
+
+        let mut text = record.text.clone();
+
+        for (start, end, spec) in painted {
+            let on = format!("{}{}", RESET, paint("\0", spec).split('\0').next().unwrap_or_default());
+            text = overlay(&text, &[(start, end)], &on, RESET);
+        }
+
+        record.text = text;
+        record
+    }
+}
+
+// names of the themes in the themes directory
+pub fn imported() -> Vec<String> {
+    let entries = match config::themes_dir().and_then(|d| fs::read_dir(d).ok()) {
+        Some(e) => { e }
+        None => { return Vec::new() }
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.path().file_name()?.to_str()?.strip_suffix(".toml").map(|n| n.to_string()))
+        .collect();
+    names.sort();
+
+    names
+}
+
+#[derive(Default)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    attributes: Vec<String>,
+}
+
+// A style like "bright_red bold", "black on_yellow" or "#ff8700": a color
+// by name or as #rrggbb, a background color prefixed with on_, and any of
+// bold, dimmed, italic, underline, reversed and strikethrough.
+pub fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+
+    for word in spec.split_whitespace() {
+        let color = |name: &str| match name.starts_with('#') {
+            true => { parse_hex(name).map(|(r, g, b)| Color::TrueColor { r, g, b }).map_err(|e| format!("{} in style '{}'", e, spec)) }
+            false => { name.replace('_', " ").parse::<Color>().map_err(|_| format!("unknown color '{}' in style '{}'", name, spec)) }
+        };
+
+        match word {
+            "bold" | "dimmed" | "italic" | "underline" | "reversed" | "strikethrough" => { style.attributes.push(word.to_string()) }
+            _ => {
+                match word.strip_prefix("on_") {
+                    Some(bg) => { style.bg = Some(color(bg)?) }
+                    None => { style.fg = Some(color(word)?) }
+                }
+            }
+        }
+    }
+
+    Ok(style)
+}
+
+// the [styles] table of a theme or plugin manifest
+pub fn styles(table: &toml::Table) -> Result<Vec<(String, String)>, String> {
+    style_table(table, "styles")
+}
+
+fn style_table(table: &toml::Table, key: &str) -> Result<Vec<(String, String)>, String> {
+    let mut styles: Vec<(String, String)> = Vec::new();
+
+    if let Some(table) = table.get(key).and_then(|v| v.as_table()) {
+        for (tag, value) in table {
+            let spec = value.as_str().ok_or(format!("style for {} must be a string", tag))?;
+            parse_style(spec)?;
+            styles.push((tag.clone(), spec.to_string()));
+        }
+    }
+
+    Ok(styles)
+}
+
+// the [widths] table of a theme or config file: the most characters of a
+// field to show, keyed by "field" or "plugin.field"
+pub fn widths(table: &toml::Table) -> Result<Vec<(String, usize)>, String> {
+    let mut widths: Vec<(String, usize)> = Vec::new();
+
+    if let Some(table) = table.get("widths").and_then(|v| v.as_table()) {
+        for (field, value) in table {
+            let width = value.as_integer().filter(|w| *w > 0).ok_or(format!("width for {} must be a number above 0", field))?;
+            widths.push((field.clone(), width as usize));
+        }
+    }
+
+    Ok(widths)
+}
+
+// the widths that apply to a plugin's fields, "plugin.field" winning over "field"
+pub fn plugin_widths(widths: &[(String, usize)], plugin: &str) -> Vec<(String, usize)> {
+    let prefix = format!("{}.", plugin);
+    let mut resolved: Vec<(String, usize)> = widths.iter().filter(|(f, _)| !f.contains('.')).cloned().collect();
+
+    for (field, width) in widths {
+        if let Some(field) = field.strip_prefix(&prefix) {
+            resolved.retain(|(f, _)| f != field);
+            resolved.push((field.to_string(), *width));
+        }
+    }
+
+    resolved
+}
+
+// text in a style spec, which is expected to be valid
+pub fn paint(text: &str, spec: &str) -> String {
+    let style = parse_style(spec).unwrap_or_default();
+    let mut s = text.normal();
+
+    if let Some(fg) = style.fg {
+        s = s.color(fg);
+    }
+    if let Some(bg) = style.bg {
+        s = s.on_color(bg);
+    }
+    for attribute in &style.attributes {
+        s = match attribute.as_str() {
+            "bold" => { s.bold() }
+            "dimmed" => { s.dimmed() }
+            "italic" => { s.italic() }
+            "underline" => { s.underline() }
+            "reversed" => { s.reversed() }
+            _ => { s.strikethrough() }
+        };
+    }
+
+    s.to_string()
+}
+
+// "#rrggbb", or the six digits without the #
+pub fn parse_hex(s: &str) -> Result<(u8, u8, u8), String> {
+    let hex = s.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid color '{}', expected #rrggbb", s));
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+pub fn init(theme: Theme) {
+    *THEME.write().unwrap() = Some(Box::leak(Box::new(theme)));
+}
+
+pub fn current() -> &'static Theme {
+    if let Some(theme) = *THEME.read().unwrap() {
+        return theme;
+    }
+
+    init(Theme::dark());
+    current()
+}
+
+// Asks the terminal for its background color (OSC 11), falling back to the
+// COLORFGBG variable some terminals set, and then to dark.
+pub fn detect_background() -> Background {
+    if std::io::stdout().is_terminal() && SHOULD_COLORIZE.should_colorize() {
+        if let Some(light) = query_background() {
+            return if light { Background::Light } else { Background::Dark };
+        }
+    }
+
+    // "foreground;background" as palette indexes, e.g. 0;15 for black on white
+    if let Ok(colors) = env::var("COLORFGBG") {
+        if let Some(Ok(bg)) = colors.rsplit(';').next().map(|b| b.parse::<u8>()) {
+            return if bg == 7 || bg > 8 { Background::Light } else { Background::Dark };
+        }
+    }
+
+    Background::Dark
+}
+
+fn query_background() -> Option<bool> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let fd = tty.as_raw_fd();
+
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) };
+
+    let answer = read_answer(&mut tty, fd);
+
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+
+    parse_answer(&answer?)
+}
+
+// terminals that don't support the query never answer, so give up quickly
+fn read_answer(tty: &mut std::fs::File, fd: i32) -> Option<String> {
+    tty.write_all(b"\x1b]11;?\x07").ok()?;
+
+    let started = Instant::now();
+    let mut answer: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 64];
+
+    while started.elapsed() < QUERY_TIMEOUT {
+        let remaining = QUERY_TIMEOUT.saturating_sub(started.elapsed()).as_millis() as i32;
+        let mut poll = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+
+        if unsafe { libc::poll(&mut poll, 1, remaining) } <= 0 {
+            return None;
+        }
+
+        let n = tty.read(&mut buf).ok()?;
+        answer.extend_from_slice(&buf[..n]);
+
+        // terminated by BEL or ST
+        if answer.ends_with(b"\x07") || answer.ends_with(b"\x1b\\") {
+            return Some(String::from_utf8_lossy(&answer).to_string());
+        }
+    }
+
+    None
+}
+
+// \e]11;rgb:RRRR/GGGG/BBBB\a, with 1 to 4 hex digits per channel
+fn parse_answer(answer: &str) -> Option<bool> {
+    let rgb = answer.split("rgb:").nth(1)?;
+    let rgb = rgb.trim_end_matches(['\x07', '\\', '\x1b']);
+
+    let channels: Vec<f64> = rgb.split('/').map(|c| {
+        let max = 16f64.powi(c.len() as i32) - 1.0;
+        u32::from_str_radix(c, 16).ok().map(|v| v as f64 / max)
+    }).collect::<Option<Vec<f64>>>()?;
+
+    if channels.len() != 3 {
+        return None;
+    }
+
+    let luminance = 0.2126 * channels[0] + 0.7152 * channels[1] + 0.0722 * channels[2];
+    Some(luminance > 0.5)
+}
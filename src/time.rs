@@ -0,0 +1,347 @@
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, SecondsFormat, TimeZone};
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::env;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static EPOCH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{9,19})(?:\.(\d{1,9})\d*)?$").unwrap()
+});
+
+// digits past nanoseconds, which chrono won't parse
+static EXTRA_DIGITS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(:\d{2}\.\d{9})\d+").unwrap()
+});
+
+// offsets written as a zone name, or as hours alone
+static ODD_OFFSET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(:\d{2}(?:\.\d+)?)\s*(?:(?:UTC|GMT|Z)([+-]\d{1,2}(?::?\d{2})?)?|([+-]\d{2}))$").unwrap()
+});
+
+static WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\p{L}+\.?").unwrap()
+});
+
+// the locale dates are shown in, from LC_ALL, LC_TIME or LANG
+static LOCALE: LazyLock<String> = LazyLock::new(|| {
+    ["LC_ALL", "LC_TIME", "LANG"].iter()
+        .filter_map(|v| env::var(v).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_default()
+});
+
+const ENGLISH_MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// Month names in the languages appliances commonly log in. Abbreviations are
+// matched as prefixes of these, so Okt, févr. and set. are understood too.
+const MONTHS: [[&str; 12]; 9] = [
+    ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"],
+    ["januar", "februar", "märz", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "dezember"],
+    ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+    ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+    ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+    ["janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"],
+    ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober", "november", "december"],
+    ["januari", "februari", "mars", "april", "maj", "juni", "juli", "augusti", "september", "oktober", "november", "december"],
+    ["januar", "februar", "marts", "april", "maj", "juni", "juli", "august", "september", "oktober", "november", "december"],
+];
+
+// abbreviations that aren't prefixes of the month's name
+const ODD_MONTHS: [(&str, usize); 2] = [("mrt", 2), ("mrz", 2)];
+
+const ZONED_FORMATS: [&str; 4] = [
+    "%d/%b/%Y:%H:%M:%S %z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+];
+
+const NAIVE_FORMATS: [&str; 7] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%d/%b/%Y:%H:%M:%S",
+    "%d-%b-%Y %H:%M:%S%.f",
+    "%d %b %Y %H:%M:%S%.f",
+    "%d.%m.%Y %H:%M:%S%.f",
+    "%d-%m-%Y %H:%M:%S%.f",
+];
+
+// 03/04/2026 is the 3rd of April outside the US, which comes first
+// depends on the locale; dates with a day past 12 can only be read one way
+const DAY_FIRST: &str = "%d/%m/%Y %H:%M:%S%.f";
+const MONTH_FIRST: &str = "%m/%d/%Y %H:%M:%S%.f";
+
+pub fn parse_timestamp(s: &str) -> Option<DateTime<FixedOffset>> {
+    let s = s.trim().trim_matches(|c| c == '[' || c == ']');
+
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t);
+    }
+
+    if let Some(t) = epoch(s) {
+        return Some(t);
+    }
+
+    for format in ZONED_FORMATS {
+        if let Ok(t) = DateTime::parse_from_str(s, format) {
+            return Some(t);
+        }
+    }
+
+    let slashed = match month_first() {
+        true => { [MONTH_FIRST, DAY_FIRST] }
+        false => { [DAY_FIRST, MONTH_FIRST] }
+    };
+
+    for format in NAIVE_FORMATS.iter().chain(&slashed) {
+        if let Ok(t) = NaiveDateTime::parse_from_str(s, format) {
+            return local(t);
+        }
+    }
+
+    // syslog timestamps leave out the year
+    let with_year = format!("{} {}", Local::now().year(), s);
+    if let Ok(t) = NaiveDateTime::parse_from_str(&with_year, "%Y %b %e %H:%M:%S") {
+        return local(t);
+    }
+
+    // as does Android's logcat
+    if let Ok(t) = NaiveDateTime::parse_from_str(&with_year, "%Y %m-%d %H:%M:%S%.f") {
+        return local(t);
+    }
+
+    // a localized month name, tried again with the English abbreviation
+    let english = english_months(s);
+    if english != s {
+        return parse_timestamp(&english);
+    }
+
+    let normalized = normalize(s);
+    if normalized != s {
+        return parse_timestamp(&normalized);
+    }
+
+    None
+}
+
+// Seconds, milliseconds, microseconds or nanoseconds since the epoch, told
+// apart by their magnitude, with any fraction kept to the nanosecond.
+fn epoch(s: &str) -> Option<DateTime<FixedOffset>> {
+    let caps = EPOCH.captures(s)?;
+    let whole: i128 = caps[1].parse().ok()?;
+
+    // nanoseconds per unit
+    let unit: i128 = match caps[1].len() {
+        0..=11 => { 1_000_000_000 }
+        12..=14 => { 1_000_000 }
+        15..=17 => { 1_000 }
+        _ => { 1 }
+    };
+
+    let fraction = caps.get(2).map(|f| format!("{:0<9}", f.as_str())).unwrap_or_default();
+    let fraction: i128 = fraction.parse().unwrap_or(0) * unit / 1_000_000_000;
+
+    let nanos = whole * unit + fraction;
+    let t = DateTime::from_timestamp(nanos.div_euclid(1_000_000_000) as i64, nanos.rem_euclid(1_000_000_000) as u32)?;
+
+    Some(t.fixed_offset())
+}
+
+fn normalize(s: &str) -> String {
+    let s = EXTRA_DIGITS.replace(s, "$1");
+
+    ODD_OFFSET.replace(&s, |c: &Captures| {
+        let offset = match (c.get(2), c.get(3)) {
+            (Some(o), _) | (_, Some(o)) => { o.as_str() }
+            _ => { "+00" }
+        };
+
+        // hours alone, or hours of one digit as in GMT+5:45
+        let (sign, rest) = offset.split_at(1);
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((h, m)) => { (h, m) }
+            None if rest.len() > 2 => { rest.split_at(rest.len() - 2) }
+            None => { (rest, "00") }
+        };
+
+        let offset = format!("{}{:0>2}:{}", sign, hours, minutes);
+
+        format!("{} {}", &c[1], offset)
+    }).to_string()
+}
+
+fn english_months(s: &str) -> String {
+    WORD.replace_all(s, |c: &Captures| {
+        match month(&c[0]) {
+            Some(m) => { ENGLISH_MONTHS[m].to_string() }
+            None => { c[0].to_string() }
+        }
+    }).to_string()
+}
+
+// the month a name or abbreviation of at least three letters stands for, if
+// it's the same in every language that has it
+fn month(word: &str) -> Option<usize> {
+    let word = word.trim_end_matches('.').to_lowercase();
+    if word.chars().count() < 3 {
+        return None;
+    }
+
+    if let Some((_, m)) = ODD_MONTHS.iter().find(|(w, _)| *w == word) {
+        return Some(*m);
+    }
+
+    let mut found: Option<usize> = None;
+
+    for names in MONTHS {
+        for (m, name) in names.iter().enumerate() {
+            if name.starts_with(&word) {
+                match found {
+                    Some(f) if f != m => { return None }
+                    _ => { found = Some(m) }
+                }
+            }
+        }
+    }
+
+    found
+}
+
+fn month_first() -> bool {
+    LOCALE.starts_with("en_US")
+}
+
+// how the locale writes a date, for showing times to people
+pub fn date_format() -> &'static str {
+    let language = LOCALE.split(['_', '.', '@']).next().unwrap_or("");
+
+    match language {
+        "en" if month_first() => { "%m/%d/%Y" }
+        "en" if LOCALE.starts_with("en_GB") || LOCALE.starts_with("en_IE") || LOCALE.starts_with("en_AU") || LOCALE.starts_with("en_NZ") => { "%d/%m/%Y" }
+        "fr" | "es" | "it" | "pt" | "el" => { "%d/%m/%Y" }
+        "de" | "da" | "nb" | "nn" | "no" | "fi" | "pl" | "cs" | "sk" | "ru" | "uk" | "tr" | "ro" => { "%d.%m.%Y" }
+        "nl" => { "%d-%m-%Y" }
+        _ => { "%Y-%m-%d" }
+    }
+}
+
+fn local(t: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    Local.from_local_datetime(&t).earliest().map(|t| t.fixed_offset())
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let millis = d.as_millis();
+
+    if d.as_micros() == 0 {
+        return format!("{}ns", d.as_nanos());
+    }
+
+    if millis == 0 {
+        return format!("{}µs", d.as_micros());
+    }
+
+    if millis < 1000 {
+        return format!("{}ms", millis);
+    }
+
+    if millis < 60_000 {
+        return format!("{:.3}s", d.as_secs_f64());
+    }
+
+    let secs = d.as_secs();
+
+    if secs < 3600 {
+        return format!("{}m{:02}s", secs / 60, secs % 60);
+    }
+
+    format!("{}h{:02}m{:02}s", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
+
+// durations like 90s, 5m, 1h or 1d
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (n, unit) = s.split_at(split);
+
+    let n: u64 = n.parse().map_err(|_| format!("invalid duration '{}'", s))?;
+
+    let unit = match unit {
+        "" | "s" => { 1 }
+        "m" => { 60 }
+        "h" => { 3600 }
+        "d" => { 86400 }
+        _ => { return Err(format!("invalid duration '{}', expected a unit of s, m, h or d", s)) }
+    };
+
+    let secs = n.checked_mul(unit).ok_or(format!("invalid duration '{}', too long", s))?;
+    Ok(Duration::from_secs(secs))
+}
+
+// a duration something happens every so often over, which can't be 0
+pub fn parse_period(s: &str) -> Result<Duration, String> {
+    match parse_duration(s)? {
+        d if d.is_zero() => { Err(format!("invalid duration '{}', expected more than 0s", s.trim())) }
+        d => { Ok(d) }
+    }
+}
+
+// --since and --until: a timestamp, with or without seconds, a date alone
+// (midnight), or a duration back from now like 15m
+pub fn parse_bound(s: &str) -> Result<DateTime<FixedOffset>, String> {
+    let s = s.trim();
+
+    if let Some(t) = parse_timestamp(s).or(parse_timestamp(&format!("{}:00", s))).or(parse_timestamp(&format!("{} 00:00:00", s))) {
+        return Ok(t);
+    }
+
+    match parse_duration(s) {
+        Ok(d) => {
+            let ago = chrono::Duration::from_std(d).map_err(|e| e.to_string())?;
+            Local::now().fixed_offset().checked_sub_signed(ago).ok_or(format!("invalid time '{}', too long ago", s))
+        }
+        Err(_) => { Err(format!("invalid time '{}', expected a timestamp like \"2024-05-01 12:00\" or a duration like 15m", s)) }
+    }
+}
+
+// how --timefmt shows a record's timestamp
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFormat {
+    #[default]
+    Original,
+    Relative,
+    Iso,
+    Unix,
+}
+
+impl TimeFormat {
+    // None leaves the timestamp as it was logged
+    pub fn apply(&self, datetime: &str) -> Option<String> {
+        let t = match self {
+            TimeFormat::Original => { return None }
+            _ => { parse_timestamp(datetime)? }
+        };
+
+        match self {
+            TimeFormat::Relative => { Some(format_ago(Local::now().signed_duration_since(t).num_seconds())) }
+            TimeFormat::Iso => { Some(t.with_timezone(&Local).to_rfc3339_opts(SecondsFormat::AutoSi, false)) }
+            TimeFormat::Unix => { Some(t.timestamp().to_string()) }
+            TimeFormat::Original => { None }
+        }
+    }
+}
+
+// -3m12s for three minutes ago, +5s for times still to come
+fn format_ago(secs: i64) -> String {
+    let sign = if secs < 0 { "+" } else { "-" };
+    let secs = secs.unsigned_abs();
+
+    match secs {
+        0..60 => { format!("{}{}s", sign, secs) }
+        60..3600 => { format!("{}{}m{:02}s", sign, secs / 60, secs % 60) }
+        3600..86400 => { format!("{}{}h{:02}m", sign, secs / 3600, (secs % 3600) / 60) }
+        _ => { format!("{}{}d{:02}h", sign, secs / 86400, (secs % 86400) / 3600) }
+    }
+}
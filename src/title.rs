@@ -0,0 +1,87 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+use crate::numbers::{count, short};
+use crate::plugins::Record;
+use crate::severity::{self, Severity};
+
+const UPDATE_EVERY: Duration = Duration::from_secs(1);
+
+// Keeps live counters in the terminal's title (OSC 2), like
+// "splash: 1.2k l/s, 3 errors", so a tail running in another tmux window or
+// tab shows how it's doing. The previous title is saved and put back at the end.
+pub struct Title {
+    sources: Vec<String>,
+    source: usize,
+    lines: Vec<u64>,
+    errors: u64,
+    counted: Vec<u64>,
+    updated: Instant,
+    terminal: Option<Box<dyn Write>>,
+}
+
+impl Title {
+    // sources are named when a pipeline reads from more than one
+    pub fn new(sources: Vec<String>) -> Self {
+        // the title goes to whichever of stdout and stderr is a terminal
+        let mut terminal: Option<Box<dyn Write>> = match (io::stdout().is_terminal(), io::stderr().is_terminal()) {
+            (true, _) => { Some(Box::new(io::stdout())) }
+            (false, true) => { Some(Box::new(io::stderr())) }
+            (false, false) => { None }
+        };
+
+        if let Some(t) = &mut terminal {
+            let _ = write!(t, "\x1b[22;2t");
+        }
+
+        let count = sources.len().max(1);
+        Title { sources, source: 0, lines: vec![0; count], errors: 0, counted: vec![0; count], updated: Instant::now(), terminal }
+    }
+
+    // which source the next records come from
+    pub fn source(&mut self, index: usize) {
+        self.source = index.min(self.lines.len() - 1);
+    }
+
+    pub fn add(&mut self, record: &Record) {
+        self.lines[self.source] += 1;
+
+        if severity::of(record) == Some(Severity::Error) {
+            self.errors += 1;
+        }
+
+        self.tick();
+    }
+
+    pub fn tick(&mut self) {
+        let elapsed = self.updated.elapsed();
+        if elapsed < UPDATE_EVERY {
+            return;
+        }
+
+        let rates: Vec<f64> = self.lines.iter().zip(&self.counted)
+            .map(|(lines, counted)| (lines - counted) as f64 / elapsed.as_secs_f64())
+            .collect();
+
+        let mut title = format!("splash: {} l/s, {} {}", short(rates.iter().sum()), count(self.errors), if self.errors == 1 { "error" } else { "errors" });
+
+        if self.sources.len() > 1 {
+            let each: Vec<String> = self.sources.iter().zip(&rates).map(|(name, rate)| format!("{} {}", name, short(*rate))).collect();
+            title.push_str(&format!(" ({})", each.join(", ")));
+        }
+
+        if let Some(t) = &mut self.terminal {
+            let _ = write!(t, "\x1b]2;{}\x07", title);
+            let _ = t.flush();
+        }
+
+        self.counted = self.lines.clone();
+        self.updated = Instant::now();
+    }
+
+    pub fn finish(&mut self) {
+        if let Some(t) = &mut self.terminal {
+            let _ = write!(t, "\x1b[23;2t");
+            let _ = t.flush();
+        }
+    }
+}
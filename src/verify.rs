@@ -0,0 +1,121 @@
+use colored::Colorize;
+use crate::plugins::{Input, ParseResult, Plugin};
+use crate::render::strip_ansi;
+
+pub enum Verification {
+    // the record serializes back to exactly the original line
+    RoundTrip,
+
+    // information was lost or changed on the way through the parser
+    Mismatch { serialized: String },
+
+    NotParsed,
+
+    // the mode can't serialize its records
+    Unsupported,
+}
+
+// Parses a line and serializes the record back to text, checking that nothing
+// was lost. Records without fields are passed through as text, so they are
+// compared as they would be printed.
+pub fn verify_line(plugin: &dyn Plugin, line: &str) -> Verification {
+    let record = match plugin.parse_line(line) {
+        ParseResult::Parsed(r) => { r }
+        ParseResult::NotMatched | ParseResult::Incomplete => { return Verification::NotParsed }
+    };
+
+    let serialized = match plugin.serialize(&record) {
+        Some(s) => { s }
+        None if record.fields.is_empty() => { strip_ansi(&record.text) }
+        None => { return Verification::Unsupported }
+    };
+
+    match serialized == line {
+        true => { Verification::RoundTrip }
+        false => { Verification::Mismatch { serialized } }
+    }
+}
+
+// --verify: checks every input line and reports the ones that don't survive
+pub struct Verifier<'a> {
+    plugin: &'a dyn Plugin,
+    line_number: u64,
+    round_trips: u64,
+    mismatches: u64,
+    not_parsed: u64,
+    unsupported: u64,
+}
+
+impl<'a> Verifier<'a> {
+    pub fn new(plugin: &'a dyn Plugin) -> Self {
+        Verifier { plugin, line_number: 0, round_trips: 0, mismatches: 0, not_parsed: 0, unsupported: 0 }
+    }
+
+    // prints the summary, returns false when any line lost information
+    pub fn report(&self) -> bool {
+        let summary = format!(
+            "{} lines: {} round-trip, {} lost information, {} not parsed, {} not serializable",
+            self.line_number, self.round_trips, self.mismatches, self.not_parsed, self.unsupported,
+        );
+
+        match self.mismatches {
+            0 => { println!("{} {}", "✓".bright_green(), summary) }
+            _ => { println!("{} {}", "✗".bright_red(), summary) }
+        }
+
+        self.mismatches == 0
+    }
+}
+
+impl Input for Verifier<'_> {
+    fn contents(&mut self, contents: &str) {
+        for line in contents.lines() {
+            self.line_number += 1;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match verify_line(self.plugin, line) {
+                Verification::RoundTrip => { self.round_trips += 1 }
+                Verification::Mismatch { serialized } => {
+                    self.mismatches += 1;
+
+                    let (original, serialized) = diff(line, &serialized);
+                    println!("{} {}", format!("line {}:", self.line_number).bright_white().bold(), "lost information".bright_red());
+                    println!("  {} {}", "-".bright_red(), original);
+                    println!("  {} {}", "+".bright_green(), serialized);
+                }
+                Verification::NotParsed => { self.not_parsed += 1 }
+                Verification::Unsupported => { self.unsupported += 1 }
+            }
+        }
+    }
+
+    fn idle(&mut self) {}
+}
+
+// highlights the part of each string between their common prefix and suffix
+fn diff(a: &str, b: &str) -> (String, String) {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+
+    let mark = |s: &[char], changed: &dyn Fn(&str) -> String| {
+        let head: String = s[..prefix].iter().collect();
+        let middle: String = s[prefix..s.len() - suffix].iter().collect();
+        let tail: String = s[s.len() - suffix..].iter().collect();
+
+        match middle.is_empty() {
+            true => { format!("{}{}", head, tail) }
+            false => { format!("{}{}{}", head, changed(&middle), tail) }
+        }
+    };
+
+    (
+        mark(&a, &|m| m.black().on_red().to_string()),
+        mark(&b, &|m| m.black().on_green().to_string()),
+    )
+}
@@ -0,0 +1,66 @@
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+use colored::Colorize;
+use crate::time::format_duration;
+
+// Notices when the input has been quiet for longer than expected, alerts and
+// runs a hook once per stall, and notes when lines start arriving again.
+pub struct Watchdog {
+    window: Duration,
+    hook: Option<String>,
+    last_activity: Instant,
+    stalled: bool,
+}
+
+impl Watchdog {
+    pub fn new(window: Duration, hook: Option<&str>) -> Self {
+        Watchdog { window, hook: hook.map(|h| h.to_string()), last_activity: Instant::now(), stalled: false }
+    }
+
+    // input arrived, returns a notice when it ends a stall
+    pub fn activity(&mut self) -> Option<String> {
+        let quiet = self.last_activity.elapsed();
+        self.last_activity = Instant::now();
+
+        match std::mem::replace(&mut self.stalled, false) {
+            true => { Some(format!("input resumed after {}", format_duration(quiet)).bright_green().bold().to_string()) }
+            false => { None }
+        }
+    }
+
+    // called periodically, returns an alert when the input just stalled
+    pub fn check(&mut self) -> Option<String> {
+        let quiet = self.last_activity.elapsed();
+        if self.stalled || quiet < self.window {
+            return None;
+        }
+
+        self.stalled = true;
+
+        let mut alert = format!("stall: no input for {}", format_duration(quiet));
+
+        if let Some(hook) = &self.hook {
+            match run_hook(hook, quiet) {
+                Ok(()) => { alert.push_str(&format!(", ran {}", hook)) }
+                Err(e) => { alert.push_str(&format!(", {}: {}", hook, e)) }
+            }
+        }
+
+        Some(alert.bright_red().bold().to_string())
+    }
+}
+
+// the hook runs in the background through the shell, with how long the
+// input has been quiet in $SPLASH_STALLED_SECONDS
+fn run_hook(hook: &str, quiet: Duration) -> Result<(), String> {
+    let mut child = Command::new("sh")
+        .args(["-c", hook])
+        .env("SPLASH_STALLED_SECONDS", quiet.as_secs().to_string())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    thread::spawn(move || child.wait());
+
+    Ok(())
+}